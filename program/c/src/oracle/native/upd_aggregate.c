@@ -13,6 +13,12 @@ extern bool c_upd_aggregate_pythnet( pc_price_t *ptr, uint64_t slot, int64_t tim
   return upd_aggregate(ptr, slot, timestamp );
 }
 
-extern void c_upd_twap( pc_price_t *ptr, int64_t nslots ){
-  upd_twap(ptr, nslots);
+extern void c_upd_twap( pc_price_t *ptr, int64_t nslots, int64_t decay, int64_t max_diff ){
+  upd_twap(ptr, nslots, decay, max_diff);
+}
+
+extern void c_upd_emas(
+    pc_ema_t *twap, pc_ema_t *twac, int64_t price, uint64_t conf, int32_t expo,
+    int64_t nslots, int64_t decay, int64_t max_diff ){
+  upd_emas(twap, twac, price, conf, expo, nslots, decay, max_diff);
 }
@@ -54,6 +54,8 @@ extern "C" {
 #define PC_ACCTYPE_PRICE      3
 #define PC_ACCTYPE_TEST       4
 #define PC_ACCTYPE_PERMISSIONS       5
+#define PC_ACCTYPE_PUBLISHER_MANAGER 6
+#define PC_ACCTYPE_PUBLISHER_BLOCKLIST 7
 
 
 // Compute budget requested per price update instruction
@@ -76,8 +76,11 @@ static pc_qset_t *qset_new()
   return qs;
 }
 
+// decay is the decay per slot with exponent PD_EMA_EXPO and max_diff the number of slots
+// without aggregation after which the ema is reset, PD_EMA_DECAY and PD_EMA_MAX_DIFF by default
 static void upd_ema(
-    pc_ema_t *ptr, pd_t *val, pd_t *conf, int64_t nslot, pc_qset_t *qs, int32_t expo
+    pc_ema_t *ptr, pd_t *val, pd_t *conf, int64_t nslot, int64_t decay_v, int64_t max_diff,
+    pc_qset_t *qs, int32_t expo
     )
 {
   pd_t numer[1], denom[1], cwgt[1], wval[1], decay[1], diff[1], one[1];
@@ -87,14 +90,14 @@ static void upd_ema(
   } else {
     pd_set( cwgt, one );
   }
-  if ( nslot > PD_EMA_MAX_DIFF ) {
+  if ( nslot > max_diff ) {
     // initial condition
     pd_mul( numer, val, cwgt );
     pd_set( denom, cwgt );
   } else {
     // compute decay factor
     pd_new( diff, nslot, 0 );
-    pd_new( decay, PD_EMA_DECAY, PD_EMA_EXPO );
+    pd_new( decay, decay_v, PD_EMA_EXPO );
     pd_mul( decay, decay, diff );
     pd_add( decay, decay, one, qs->fact_ );
 
@@ -119,16 +122,26 @@ static void upd_ema(
   }
 }
 
-static inline void upd_twap(
-    pc_price_t *ptr, int64_t nslots )
+// update a pair of emas of an aggregate price and its confidence
+static inline void upd_emas(
+    pc_ema_t *twap, pc_ema_t *twac, int64_t price, uint64_t conf, int32_t expo,
+    int64_t nslots, int64_t decay, int64_t max_diff )
 {
   pc_qset_t *qs = qset_new( );
 
-  pd_t px[1], conf[1];
-  pd_new_scale( px, ptr->agg_.price_, ptr->expo_ );
-  pd_new_scale( conf, ( int64_t )( ptr->agg_.conf_ ), ptr->expo_ );
-  upd_ema( &ptr->twap_, px, conf, nslots, qs, ptr->expo_ );
-  upd_ema( &ptr->twac_, conf, conf, nslots, qs, ptr->expo_ );
+  pd_t px[1], cf[1];
+  pd_new_scale( px, price, expo );
+  pd_new_scale( cf, ( int64_t )conf, expo );
+  upd_ema( twap, px, cf, nslots, decay, max_diff, qs, expo );
+  upd_ema( twac, cf, cf, nslots, decay, max_diff, qs, expo );
+}
+
+static inline void upd_twap(
+    pc_price_t *ptr, int64_t nslots, int64_t decay, int64_t max_diff )
+{
+  upd_emas(
+    &ptr->twap_, &ptr->twac_, ptr->agg_.price_, ptr->agg_.conf_, ptr->expo_,
+    nslots, decay, max_diff );
 }
 
 // update aggregate price
@@ -13,6 +13,7 @@ fn main() {
     let target_arch = std::env::var("CARGO_CFG_TARGET_ARCH").unwrap();
 
     let has_feat_check = std::env::var("CARGO_FEATURE_CHECK").is_ok();
+    let has_feat_rust_aggregation = std::env::var("CARGO_FEATURE_RUST_AGGREGATION").is_ok();
 
     // OUT_DIR is the path cargo provides to a build directory under `target/` specifically for
     // isolated build artifacts. We use this to build the C program and then link against the
@@ -24,13 +25,16 @@ fn main() {
     eprintln!("OUT_DIR is {}", out_dir);
     let out_dir = PathBuf::from(out_dir);
 
-    let mut make_targets = vec![];
-    if target_arch == "bpf" {
-        make_targets.push("cpyth-bpf");
+    // With the `rust-aggregation` feature the program doesn't depend on the C aggregation, but
+    // the native build still links it along with the C tests: the tests check the Rust port
+    // against the C code, and the build script can't tell whether they are being compiled.
+    let aggregation_lib = if target_arch == "bpf" {
+        (!has_feat_rust_aggregation).then_some("cpyth-bpf")
     } else {
-        make_targets.push("cpyth-native");
-    }
+        Some("cpyth-native")
+    };
 
+    let mut make_targets: Vec<&str> = aggregation_lib.into_iter().collect();
     make_targets.push("test");
 
     // When the `check` feature is active, we skip the make
@@ -42,10 +46,8 @@ fn main() {
         do_make_build(make_targets, &out_dir);
 
         // Link against the right library for the architecture
-        if target_arch == "bpf" {
-            println!("cargo:rustc-link-lib=static=cpyth-bpf");
-        } else {
-            println!("cargo:rustc-link-lib=static=cpyth-native");
+        if let Some(aggregation_lib) = aggregation_lib {
+            println!("cargo:rustc-link-lib=static={}", aggregation_lib);
         }
 
         println!("cargo:rustc-link-lib=static=cpyth-test");
@@ -38,6 +38,8 @@ mod mapping;
 mod permission;
 mod price;
 mod product;
+mod publisher_blocklist;
+mod publisher_manager;
 
 // Some types only exist during use as a library.
 #[cfg(feature = "strum")]
@@ -45,25 +47,68 @@ pub use price::MessageType;
 #[cfg(test)]
 pub use price::PriceCumulative;
 #[cfg(test)]
+pub use product::account_has_key_values;
+#[cfg(any(test, feature = "library"))]
 pub use product::{
-    account_has_key_values,
     create_pc_str_t,
+    read_pc_str_t,
 };
 pub use {
     mapping::MappingAccount,
-    permission::PermissionAccount,
+    permission::{
+        MultisigAuthority,
+        PendingAuthorities,
+        PermissionAccount,
+        MAX_MULTISIG_SIGNERS,
+    },
     price::{
+        AggregateConfidence,
+        AggregateConfidenceMessage,
+        AggregationDiagnostics,
+        AggregationExtensions,
+        CircuitBreaker,
+        CorpActStatusMessage,
+        EmaHorizons,
+        EmaHorizonsMessage,
+        ExclusionReason,
+        HorizonEma,
+        OutlierFilter,
+        OutlierFilterMode,
         PriceAccount,
         PriceAccountFlags,
+        PriceBand,
         PriceComponent,
         PriceEma,
+        PriceExtensionFlags,
+        PriceExtensionsHeader,
+        PriceHistory,
+        PriceHistoryEntry,
         PriceInfo,
+        PublisherStat,
+        PublisherStats,
+        PublisherStatsMessage,
+        PublisherWeight,
+        PublisherWeights,
         PythOracleSerialize,
         MAX_FEED_INDEX,
+        NUM_EMA_HORIZONS,
+        PRICE_HISTORY_LEN,
+        PUBLISHER_DEVIATION_SCALE,
     },
     product::{
         update_product_metadata,
         ProductAccount,
+        TradingInterval,
+        TradingSchedule,
+        MAX_TRADING_INTERVALS,
+    },
+    publisher_blocklist::{
+        PublisherBlocklistAccount,
+        MAX_BLOCKED_PUBLISHERS,
+    },
+    publisher_manager::{
+        PublisherManagerAccount,
+        MAX_PUBLISHER_MANAGER_PRODUCTS,
     },
 };
 
@@ -72,6 +117,10 @@ pub use {
 /// are authorized to perform certain administrative actions.
 pub const PERMISSIONS_SEED: &str = "permissions";
 
+/// There is a single `PublisherBlocklistAccount` under `PUBLISHER_BLOCKLIST_SEED` that stores the
+/// publishers whose quotes are ignored by every price account.
+pub const PUBLISHER_BLOCKLIST_SEED: &str = "publisher_blocklist";
+
 /// The update price instruction can optionally invoke another program via CPI. The
 /// CPI will be signed with the PDA `[UPD_PRICE_WRITE_SEED, invoked_program_public_key]`
 /// such that the caller can authenticate its origin.
@@ -5,6 +5,10 @@ use {
     },
     crate::{
         c_oracle_header::PC_ACCTYPE_PERMISSIONS,
+        deserialize::{
+            load_extension,
+            load_extension_mut,
+        },
         instruction::OracleCommand,
     },
     bytemuck::{
@@ -22,6 +26,16 @@ use {
     },
 };
 
+/// Maximum number of keys in the signer set of a `MultisigAuthority`
+pub const MAX_MULTISIG_SIGNERS: usize = 10;
+
+/// The roles of the authorities of the `PermissionAccount` other than the master authority
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum AuthorityRole {
+    DataCuration,
+    Security,
+}
+
 /// This account stores the pubkeys that can execute administrative instructions in the Pyth
 /// program. Only the upgrade authority of the program can update these permissions.
 #[repr(C)]
@@ -32,29 +46,107 @@ pub struct PermissionAccount {
     /// An authority that can do any administrative task
     pub master_authority:        Pubkey,
     /// An authority that can  :
-    /// - Add mapping accounts
-    /// - Add price accounts
     /// - Add product accounts
+    /// - Update product accounts
+    /// - Add price accounts
     /// - Delete price accounts
     /// - Delete product accounts
-    /// - Update product accounts
+    /// - Set trading schedules
     pub data_curation_authority: Pubkey,
     /// An authority that can  :
     /// - Add publishers
     /// - Delete publishers
     /// - Set minimum number of publishers
+    /// - Set max latency
+    /// - Set publisher managers
+    /// - Set confidence thresholds
+    /// - Set EMA half-lives
+    /// - Set EMA horizons
+    /// - Initialize price histories
+    /// - Set publisher weights
+    /// - Set outlier filters
+    /// - Initialize aggregation diagnostics
+    /// - Initialize publisher statistics
+    /// - Initialize aggregate confidences
+    /// - Set circuit breakers
+    /// - Set price bands
+    /// - Block and unblock publishers
+    /// - Rotate the keys of publishers
     pub security_authority:      Pubkey,
 }
 
 impl PermissionAccount {
+    /// Size of the account once it stores a `MultisigAuthority`
+    pub const MULTISIG_AUTHORITY_SPACE: usize =
+        Self::NEW_ACCOUNT_SPACE + size_of::<MultisigAuthority>();
+    /// Size of the account once it stores `PendingAuthorities`
+    pub const PENDING_AUTHORITIES_SPACE: usize =
+        Self::MULTISIG_AUTHORITY_SPACE + size_of::<PendingAuthorities>();
+
+    /// Whether `key` may execute `command`. The master authority may execute every command, the
+    /// other authorities may only execute the commands of their role. The roles only depend on
+    /// the command, so they apply to permission accounts of any size without migrating them.
     pub fn is_authorized(&self, key: &Pubkey, command: OracleCommand) -> bool {
-        #[allow(clippy::match_like_matches_macro)]
-        match (*key, command) {
-            (pubkey, _) if pubkey == self.master_authority => true,
-            (pubkey, OracleCommand::ResizePriceAccount) if pubkey == self.security_authority => {
-                true
-            } // Allow for an admin key to resize the price account
-            _ => false,
+        *key == self.master_authority || self.has_role(key, command)
+    }
+
+    /// Whether `key` is the authority of the role allowed to execute `command`. Unlike
+    /// `is_authorized`, this ignores the master authority.
+    pub fn has_role(&self, key: &Pubkey, command: OracleCommand) -> bool {
+        match Self::role(command) {
+            Some(AuthorityRole::DataCuration) => *key == self.data_curation_authority,
+            Some(AuthorityRole::Security) => *key == self.security_authority,
+            None => false,
+        }
+    }
+
+    /// Whether `command` needs the approval of the multisig authority, when there is one, even if
+    /// it is signed by the authority of its role: the commands changing the publishers or who can
+    /// change them, deleting price accounts or proposing new authorities, see
+    /// `check_multisig_approval` for the latter.
+    pub fn requires_multisig_authority(command: OracleCommand) -> bool {
+        matches!(
+            command,
+            OracleCommand::AddPublisher
+                | OracleCommand::DelPublisher
+                | OracleCommand::SetPublisherManager
+                | OracleCommand::RotatePublisher
+                | OracleCommand::DelPrice
+                | OracleCommand::ProposeAuthorities
+        )
+    }
+
+    /// The role, besides the master authority, allowed to execute `command`, if any.
+    fn role(command: OracleCommand) -> Option<AuthorityRole> {
+        match command {
+            OracleCommand::AddProduct
+            | OracleCommand::UpdProduct
+            | OracleCommand::AddPrice
+            | OracleCommand::DelPrice
+            | OracleCommand::DelProduct
+            | OracleCommand::SetTradingSchedule => Some(AuthorityRole::DataCuration),
+            OracleCommand::AddPublisher
+            | OracleCommand::DelPublisher
+            | OracleCommand::SetMinPub
+            | OracleCommand::SetMaxLatency
+            | OracleCommand::SetPublisherManager
+            | OracleCommand::SetConfThreshold
+            | OracleCommand::SetEmaHalfLife
+            | OracleCommand::SetEmaHorizons
+            | OracleCommand::InitPriceHistory
+            | OracleCommand::SetPublisherWeight
+            | OracleCommand::SetOutlierFilter
+            | OracleCommand::InitAggregationDiagnostics
+            | OracleCommand::InitPublisherStats
+            | OracleCommand::InitAggregateConfidence
+            | OracleCommand::SetCircuitBreaker
+            | OracleCommand::SetPriceBand
+            | OracleCommand::BlockPublisher
+            | OracleCommand::UnblockPublisher
+            | OracleCommand::RotatePublisher
+            // Allow for an admin key to resize the price account
+            | OracleCommand::ResizePriceAccount => Some(AuthorityRole::Security),
+            _ => None,
         }
     }
 
@@ -71,6 +163,117 @@ impl PermissionAccount {
             bytemuck::from_bytes_mut(&mut data[start..end])
         }))
     }
+
+    /// The multisig authority of the account, or `None` if it isn't enabled. Accounts created
+    /// before multisig authorities were introduced are too small to store one and have none.
+    pub fn load_multisig_authority(
+        account: &AccountInfo,
+    ) -> Result<Option<MultisigAuthority>, ProgramError> {
+        Ok(
+            load_extension::<MultisigAuthority>(account, Self::NEW_ACCOUNT_SPACE)?
+                .filter(MultisigAuthority::is_enabled),
+        )
+    }
+
+    pub fn load_multisig_authority_mut<'a>(
+        account: &'a AccountInfo,
+    ) -> Result<RefMut<'a, MultisigAuthority>, ProgramError> {
+        load_extension_mut(account, Self::NEW_ACCOUNT_SPACE)
+    }
+
+    /// The authorities proposed by the upgrade authority, or `None` if there is no pending
+    /// transfer of authority.
+    pub fn load_pending_authorities(
+        account: &AccountInfo,
+    ) -> Result<Option<PendingAuthorities>, ProgramError> {
+        Ok(
+            load_extension::<PendingAuthorities>(account, Self::MULTISIG_AUTHORITY_SPACE)?
+                .filter(PendingAuthorities::is_pending),
+        )
+    }
+
+    pub fn load_pending_authorities_mut<'a>(
+        account: &'a AccountInfo,
+    ) -> Result<RefMut<'a, PendingAuthorities>, ProgramError> {
+        load_extension_mut(account, Self::MULTISIG_AUTHORITY_SPACE)
+    }
+}
+
+/// A set of keys that replaces the master authority once enabled: an administrative instruction
+/// that isn't signed by the authority of its role needs the signatures of `threshold` distinct
+/// keys of the set, and so do the commands of `PermissionAccount::requires_multisig_authority` in
+/// any case. This extension is stored right after `last_feed_index` in the `PermissionAccount`.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct MultisigAuthority {
+    /// Number of signatures required, the multisig authority is disabled if it is 0
+    pub threshold:   u8,
+    /// Number of keys in `signers`
+    pub num_signers: u8,
+    pub unused_:     [u8; 2],
+    pub signers:     [Pubkey; MAX_MULTISIG_SIGNERS],
+}
+
+impl MultisigAuthority {
+    pub fn is_enabled(&self) -> bool {
+        self.threshold > 0
+    }
+
+    /// Whether the configuration is valid: the threshold can be reached and the signers are
+    /// distinct. A zero threshold (with any number of signers) disables the multisig authority.
+    pub fn is_valid(&self) -> bool {
+        let num_signers = self.num_signers as usize;
+        if num_signers > MAX_MULTISIG_SIGNERS || self.threshold > self.num_signers {
+            return false;
+        }
+        let signers = &self.signers[..num_signers];
+        signers
+            .iter()
+            .enumerate()
+            .all(|(i, signer)| *signer != Pubkey::default() && !signers[..i].contains(signer))
+            && self.signers[num_signers..]
+                .iter()
+                .all(|signer| *signer == Pubkey::default())
+    }
+
+    /// Whether the keys in `signing_keys` include `threshold` distinct keys of the set. Repeated
+    /// keys and keys outside of the set don't count.
+    pub fn is_approved<'a>(&self, signing_keys: impl Iterator<Item = &'a Pubkey>) -> bool {
+        let signers = &self.signers[..usize::from(self.num_signers).min(MAX_MULTISIG_SIGNERS)];
+        let mut approved = [false; MAX_MULTISIG_SIGNERS];
+        for key in signing_keys {
+            if let Some(i) = signers.iter().position(|signer| signer == key) {
+                approved[i] = true;
+            }
+        }
+        self.is_enabled()
+            && approved.iter().filter(|approved| **approved).count() >= usize::from(self.threshold)
+    }
+}
+
+/// Authorities proposed by the upgrade authority, which replace the authorities of the
+/// `PermissionAccount` once the proposed master authority accepts them, along with the minimum
+/// delay of the proposals. This extension is stored right after the `MultisigAuthority` in the
+/// `PermissionAccount`.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct PendingAuthorities {
+    /// The proposed master authority, the default pubkey if there is no pending transfer
+    pub master_authority:         Pubkey,
+    pub data_curation_authority:  Pubkey,
+    pub security_authority:       Pubkey,
+    /// First slot at which the proposed authorities can be accepted
+    pub accept_slot:              u64,
+    /// Minimum number of slots between a proposal and its acceptance, kept across proposals
+    pub min_delay_slots:          u64,
+    /// The minimum delay that replaces `min_delay_slots` once the proposal is accepted
+    pub proposed_min_delay_slots: u64,
+}
+
+impl PendingAuthorities {
+    pub fn is_pending(&self) -> bool {
+        self.master_authority != Pubkey::default()
+    }
 }
 
 impl PythAccount for PermissionAccount {
@@ -29,12 +29,33 @@ mod price_pythnet {
 
     use {
         super::*,
-        crate::c_oracle_header::{
-            PC_MAX_SEND_LATENCY,
-            PC_NUM_COMP_PYTHNET,
-            PC_STATUS_TRADING,
+        crate::{
+            c_oracle_header::{
+                MAX_CI_DIVISOR,
+                PC_MAX_SEND_LATENCY,
+                PC_NUM_COMP,
+                PC_NUM_COMP_PYTHNET,
+                PC_STATUS_TRADING,
+            },
+            deserialize::{
+                extension_mut,
+                load_checked_with_extensions,
+                load_extension_mut,
+                read_extension,
+            },
+            utils::{
+                pyth_assert,
+                try_convert,
+            },
         },
         bitflags::bitflags,
+        num_derive::FromPrimitive,
+        num_traits::FromPrimitive,
+        solana_program::{
+            account_info::AccountInfo,
+            program_error::ProgramError,
+        },
+        std::cell::RefMut,
     };
 
     /// Pythnet-only extended price account format. This extension is
@@ -99,6 +120,551 @@ mod price_pythnet {
     // together with trading status in a single u32.
     pub const MAX_FEED_INDEX: u32 = (1 << 28) - 1;
 
+    /// Maximum number of extensions of a price account, one per bit of `PriceExtensionFlags`
+    pub const MAX_PRICE_EXTENSIONS: usize = 32;
+    /// The offsets of the extensions are multiples of it, so that they can be borrowed in place
+    const PRICE_EXTENSION_ALIGNMENT: usize = 8;
+
+    /// Header stored right after the account struct, in front of the extensions of the price
+    /// account. The data following the struct is only read as extensions if it starts with a valid
+    /// header, which is written when the first extension is configured: the bytes past the struct
+    /// of an account allocated larger by its creator are never mistaken for extensions.
+    ///
+    /// Each extension is stored at its own offset, allocated at the end of the extensions stored
+    /// so far when it is first configured, so that configuring an extension only grows the
+    /// account by its own size.
+    #[repr(C)]
+    #[derive(Copy, Clone, Debug, PartialEq, Eq, Pod, Zeroable)]
+    pub struct PriceExtensionsHeader {
+        pub magic:   u32,
+        pub version: u32,
+        /// The extensions configured on the account
+        pub enabled: PriceExtensionFlags,
+        /// End of the header and of the extensions stored so far in the account data
+        pub end:     u32,
+        /// Offset in the account data of each extension, indexed by the position of its bit in
+        /// `PriceExtensionFlags`, or 0 if it isn't stored
+        pub offsets: [u32; MAX_PRICE_EXTENSIONS],
+    }
+
+    impl PriceExtensionsHeader {
+        /// "ext" in ASCII
+        pub const MAGIC: u32 = 0x0074_7865;
+        /// Version 1 stored the extensions one after the other in a fixed order
+        pub const VERSION: u32 = 2;
+
+        pub fn new() -> Self {
+            PriceExtensionsHeader {
+                magic:   Self::MAGIC,
+                version: Self::VERSION,
+                enabled: PriceExtensionFlags::empty(),
+                end:     PriceAccountPythnet::EXTENSIONS_HEADER_SPACE as u32,
+                offsets: [0; MAX_PRICE_EXTENSIONS],
+            }
+        }
+
+        pub fn is_valid(&self) -> bool {
+            self.magic == Self::MAGIC && self.version == Self::VERSION
+        }
+
+        /// Whether the header is valid and `extension` is enabled.
+        pub fn is_enabled(&self, extension: PriceExtensionFlags) -> bool {
+            self.is_valid() && self.enabled.contains(extension)
+        }
+
+        /// The offset of `extension` in the account data, or `None` if it isn't enabled.
+        pub fn offset(&self, extension: PriceExtensionFlags) -> Option<usize> {
+            if !self.is_enabled(extension) {
+                return None;
+            }
+            match self.offsets[Self::index(extension)] {
+                0 => None,
+                offset => Some(offset as usize),
+            }
+        }
+
+        /// The offset at which `extension` is stored: its current offset if it was stored
+        /// before, or else the aligned end of the extensions stored so far.
+        fn allocation(&self, extension: PriceExtensionFlags) -> usize {
+            match self.offsets[Self::index(extension)] {
+                0 => {
+                    let end = self.end as usize;
+                    end + (PRICE_EXTENSION_ALIGNMENT - end % PRICE_EXTENSION_ALIGNMENT)
+                        % PRICE_EXTENSION_ALIGNMENT
+                }
+                offset => offset as usize,
+            }
+        }
+
+        /// `extension` is a single flag of `PriceExtensionFlags`
+        fn index(extension: PriceExtensionFlags) -> usize {
+            extension.bits().trailing_zeros() as usize % MAX_PRICE_EXTENSIONS
+        }
+    }
+
+    impl Default for PriceExtensionsHeader {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    /// Number of horizons in `EmaHorizons`
+    pub const NUM_EMA_HORIZONS: usize = 3;
+
+    /// Additional EMAs of the aggregate price and confidence with their own half-lives, e.g. a
+    /// short, a medium and a long horizon. They are updated along with `twap_` and `twac_` on
+    /// each successful aggregation.
+    #[repr(C)]
+    #[derive(Copy, Clone, Pod, Zeroable)]
+    pub struct EmaHorizons {
+        /// Half-life in slots of the EMAs of each horizon, see `EmaParams::from_half_life`. A
+        /// horizon isn't used if its half-life is 0.
+        pub half_lives: [u64; NUM_EMA_HORIZONS],
+        /// Ema for price of each horizon
+        pub twap:       [PriceEma; NUM_EMA_HORIZONS],
+        /// Ema for confidence of each horizon
+        pub twac:       [PriceEma; NUM_EMA_HORIZONS],
+    }
+
+    impl EmaHorizons {
+        pub fn is_enabled(&self) -> bool {
+            self.half_lives.iter().any(|half_life| *half_life != 0)
+        }
+    }
+
+    /// Number of entries in `PriceHistory`
+    pub const PRICE_HISTORY_LEN: usize = 64;
+
+    /// A ring buffer of the most recent aggregates of a price account, written on every
+    /// aggregation whether it succeeds or not.
+    #[repr(C)]
+    #[derive(Copy, Clone, Pod, Zeroable)]
+    pub struct PriceHistory {
+        /// Number of entries written since the history was initialized, the latest entry is at
+        /// index `(num_writes - 1) % PRICE_HISTORY_LEN`
+        pub num_writes: u64,
+        pub entries:    [PriceHistoryEntry; PRICE_HISTORY_LEN],
+    }
+
+    #[repr(C)]
+    #[cfg_attr(test, derive(Debug, PartialEq))]
+    #[derive(Copy, Clone, Pod, Zeroable)]
+    pub struct PriceHistoryEntry {
+        /// Slot of the aggregation
+        pub slot:      u64,
+        pub timestamp: i64,
+        pub price:     i64,
+        pub conf:      u64,
+        /// Status of the aggregate, the price is only valid if it is `PC_STATUS_TRADING`
+        pub status:    u32,
+        pub unused_:   u32,
+    }
+
+    impl PriceHistory {
+        pub fn push(&mut self, entry: PriceHistoryEntry) {
+            self.entries[(self.num_writes % PRICE_HISTORY_LEN as u64) as usize] = entry;
+            self.num_writes += 1;
+        }
+
+        /// The entries of the history, from the latest to the oldest
+        pub fn iter_latest(&self) -> impl Iterator<Item = &PriceHistoryEntry> {
+            let len = PRICE_HISTORY_LEN as u64;
+            (self.num_writes.saturating_sub(len)..self.num_writes)
+                .rev()
+                .map(move |i| &self.entries[(i % len) as usize])
+        }
+
+        /// The latest aggregate with `PC_STATUS_TRADING` at or before `slot`, or `None` if there
+        /// is none in the history.
+        pub fn price_at_or_before(&self, slot: u64) -> Option<PriceHistoryEntry> {
+            self.iter_latest()
+                .find(|entry| entry.slot <= slot && entry.status == PC_STATUS_TRADING)
+                .copied()
+        }
+    }
+
+    /// The weights of the publishers of a price account in the stake-weighted aggregation, see
+    /// `PriceAccountFlags::STAKE_WEIGHTED`. Publishers without an entry have a weight of 0.
+    #[repr(C)]
+    #[derive(Copy, Clone, Pod, Zeroable)]
+    pub struct PublisherWeights {
+        /// Number of entries in `weights`
+        pub num_weights: u64,
+        /// The entries, sorted by publisher
+        pub weights:     [PublisherWeight; PC_NUM_COMP as usize],
+    }
+
+    #[repr(C)]
+    #[cfg_attr(test, derive(Debug, PartialEq))]
+    #[derive(Copy, Clone, Pod, Zeroable)]
+    pub struct PublisherWeight {
+        pub publisher: Pubkey,
+        pub weight:    u64,
+    }
+
+    impl PublisherWeights {
+        /// The entries in use
+        pub fn entries(&self) -> &[PublisherWeight] {
+            let num_weights = usize::try_from(self.num_weights).unwrap_or(usize::MAX);
+            &self.weights[..num_weights.min(self.weights.len())]
+        }
+
+        pub fn weight(&self, publisher: &Pubkey) -> u64 {
+            let entries = self.entries();
+            entries
+                .binary_search_by(|entry| entry.publisher.cmp(publisher))
+                .map_or(0, |i| entries[i].weight)
+        }
+
+        /// Set the weight of `publisher`, a weight of 0 removing its entry. Returns `false` if
+        /// there is no room left for a new entry.
+        pub fn set_weight(&mut self, publisher: &Pubkey, weight: u64) -> bool {
+            let num_weights = self.entries().len();
+            match self
+                .entries()
+                .binary_search_by(|entry| entry.publisher.cmp(publisher))
+            {
+                Ok(i) if weight != 0 => self.weights[i].weight = weight,
+                Ok(i) => {
+                    self.weights.copy_within(i + 1..num_weights, i);
+                    self.weights[num_weights - 1] = PublisherWeight::zeroed();
+                    self.num_weights = (num_weights - 1) as u64;
+                }
+                Err(_) if weight == 0 => {}
+                Err(_) if num_weights == self.weights.len() => return false,
+                Err(i) => {
+                    self.weights.copy_within(i..num_weights, i + 1);
+                    self.weights[i] = PublisherWeight {
+                        publisher: *publisher,
+                        weight,
+                    };
+                    self.num_weights = (num_weights + 1) as u64;
+                }
+            }
+            true
+        }
+    }
+
+    /// The reference a quote is compared to by the `OutlierFilter`
+    #[repr(u32)]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, FromPrimitive)]
+    pub enum OutlierFilterMode {
+        /// Quotes farther than `k` confidences of the previous aggregate from its price are
+        /// rejected
+        PrevAggregate = 1,
+        /// Quotes farther than `k` median absolute deviations (at least 1) from the median of the
+        /// quotes are rejected
+        Median        = 2,
+    }
+
+    /// Rejects the quotes that are too far from a reference before the aggregation model runs.
+    /// Nothing is rejected if the filter would reject more than half of the valid quotes, e.g.
+    /// after a genuine move of the price away from the previous aggregate.
+    #[repr(C)]
+    #[derive(Copy, Clone, Pod, Zeroable)]
+    pub struct OutlierFilter {
+        /// The `OutlierFilterMode`, the filter is disabled if it is 0
+        pub mode:         u32,
+        /// Maximum distance to the reference, in multiples of the scale of the mode
+        pub k:            u32,
+        /// Number of quotes rejected by the last aggregation
+        pub num_rejected: u32,
+        pub unused_:      u32,
+    }
+
+    impl OutlierFilter {
+        pub fn mode(&self) -> Option<OutlierFilterMode> {
+            OutlierFilterMode::from_u32(self.mode)
+        }
+
+        pub fn is_enabled(&self) -> bool {
+            self.mode().is_some()
+        }
+    }
+
+    /// Why the quote of a component was left out of an aggregation
+    #[repr(u8)]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, FromPrimitive)]
+    pub enum ExclusionReason {
+        /// The status of the quote isn't `PC_STATUS_TRADING`
+        Status            = 1,
+        /// The quote has `PC_STATUS_IGNORED`, which `upd_price` sets when its confidence is too
+        /// wide relative to its price
+        ConfidenceRatio   = 2,
+        /// The confidence is 0 or adding it to or subtracting it from the price would overflow
+        InvalidConfidence = 3,
+        /// The quote was published more than `max_latency_` slots before the aggregation
+        Stale             = 4,
+        /// The publisher has no weight in the stake-weighted aggregation
+        ZeroWeight        = 5,
+        /// The quote was rejected by the `OutlierFilter`
+        Outlier           = 6,
+        /// The publisher is in the `PublisherBlocklistAccount`
+        Blocked           = 7,
+        /// The quote has `PC_STATUS_IGNORED`, which `upd_price` sets when its price is outside
+        /// of the `PriceBand`
+        OutsidePriceBand  = 8,
+    }
+
+    /// The outcome of the last aggregation for each component of a price account.
+    #[repr(C)]
+    #[derive(Copy, Clone, Pod, Zeroable)]
+    pub struct AggregationDiagnostics {
+        /// Slot of the last aggregation, 0 if there was none since the diagnostics were
+        /// initialized
+        pub slot:    u64,
+        /// The `ExclusionReason` code of each component, in the order of `comp_` at the time of
+        /// the aggregation, or 0 if its quote was included
+        pub reasons: [u8; PC_NUM_COMP as usize],
+    }
+
+    impl AggregationDiagnostics {
+        /// Why the quote of the component at `index` was left out of the last aggregation, or
+        /// `None` if it was included.
+        pub fn exclusion_reason(&self, index: usize) -> Option<ExclusionReason> {
+            self.reasons
+                .get(index)
+                .and_then(|code| ExclusionReason::from_u8(*code))
+        }
+    }
+
+    /// Scale of the deviations in `PublisherStat`
+    pub const PUBLISHER_DEVIATION_SCALE: u64 = 1_000_000;
+
+    /// Running statistics of the quotes of the publishers of a price account, which compare each
+    /// valid quote to the aggregate price it was aggregated into, whether it was weighted or
+    /// rejected as an outlier or not. The entries are kept in the order of `comp_` and the entry
+    /// of a removed publisher is eventually reused.
+    #[repr(C)]
+    #[derive(Copy, Clone, Pod, Zeroable)]
+    pub struct PublisherStats {
+        pub entries: [PublisherStat; PC_NUM_COMP as usize],
+    }
+
+    #[repr(C)]
+    #[derive(Copy, Clone, Debug, PartialEq, Eq, Pod, Zeroable)]
+    pub struct PublisherStat {
+        pub publisher:        Pubkey,
+        /// Number of successful aggregations with a valid quote of the publisher
+        pub num_aggregations: u64,
+        /// Number of these aggregations whose price is outside of the confidence interval of
+        /// the quote
+        pub num_misses:       u64,
+        /// Sum over these aggregations of the distance between the quote and the aggregate
+        /// price, in confidences of the quote scaled by `PUBLISHER_DEVIATION_SCALE`. Saturates at
+        /// `u64::MAX`.
+        pub sum_deviation:    u64,
+    }
+
+    impl PublisherStat {
+        /// The average distance between the quotes of the publisher and the aggregate price, in
+        /// confidences of the quotes scaled by `PUBLISHER_DEVIATION_SCALE`
+        pub fn avg_deviation(&self) -> u64 {
+            self.sum_deviation
+                .checked_div(self.num_aggregations)
+                .unwrap_or(0)
+        }
+    }
+
+    impl PublisherStats {
+        /// The entry of the publisher of `comps[index]`, which is moved to `index` or created
+        /// there if the publisher has none, assuming the entries of `comps[..index]` are
+        /// already in place.
+        pub fn entry_mut(&mut self, comps: &[PriceComponent], index: usize) -> &mut PublisherStat {
+            let publisher = comps[index].pub_;
+            if self.entries[index].publisher != publisher {
+                match self.entries[index + 1..]
+                    .iter()
+                    .position(|entry| entry.publisher == publisher)
+                {
+                    Some(i) => self.entries.swap(index, index + 1 + i),
+                    None => {
+                        // Replace an entry which no later component needs, there is always one
+                        // since there are at least as many entries as components
+                        let is_needed = |entry: &PublisherStat| {
+                            comps[index + 1..]
+                                .iter()
+                                .any(|comp| comp.pub_ == entry.publisher)
+                        };
+                        if let Some(i) =
+                            (index..self.entries.len()).find(|i| !is_needed(&self.entries[*i]))
+                        {
+                            self.entries.swap(index, i);
+                        }
+                        self.entries[index] = PublisherStat {
+                            publisher,
+                            ..PublisherStat::zeroed()
+                        };
+                    }
+                }
+            }
+            &mut self.entries[index]
+        }
+    }
+
+    /// The two sides of the confidence interval of the last successful aggregate of a price
+    /// account, whose `agg_.conf_` is the larger of the two.
+    #[repr(C)]
+    #[derive(Copy, Clone, Debug, PartialEq, Eq, Pod, Zeroable)]
+    pub struct AggregateConfidence {
+        /// Slot of the aggregate, 0 if there was none since the extension was initialized
+        pub pub_slot:   u64,
+        /// Distance from the p25 of the quotes to the aggregate price
+        pub conf_left:  u64,
+        /// Distance from the aggregate price to the p75 of the quotes
+        pub conf_right: u64,
+    }
+
+    /// Halts a price account when its aggregate price moves too much too quickly: a successful
+    /// aggregate that moves by more than `max_move_bps` from the previous aggregate, at most
+    /// `max_slots` slots later, gets `PC_STATUS_HALTED` instead of `PC_STATUS_TRADING`. The
+    /// price account trades again once `required_confirmations` consecutive aggregates confirm
+    /// the new level, or as soon as an aggregate returns to the previous one.
+    #[repr(C)]
+    #[derive(Copy, Clone, Debug, PartialEq, Eq, Pod, Zeroable)]
+    pub struct CircuitBreaker {
+        /// Maximum move from the previous aggregate price, in basis points of it. The breaker is
+        /// disabled if it is 0.
+        pub max_move_bps:           u32,
+        /// Number of consecutive aggregates within `max_move_bps` of the new level needed to
+        /// trade again
+        pub required_confirmations: u32,
+        /// Maximum number of slots since the previous aggregate for a move to trip the breaker
+        pub max_slots:              u64,
+        /// 1 while the breaker is tripped
+        pub tripped:                u32,
+        /// Number of consecutive aggregates that confirmed `pending_price`
+        pub num_confirmations:      u32,
+        /// The new level that tripped the breaker
+        pub pending_price:          i64,
+    }
+
+    impl CircuitBreaker {
+        pub fn is_enabled(&self) -> bool {
+            self.max_move_bps != 0
+        }
+
+        pub fn is_tripped(&self) -> bool {
+            self.tripped != 0
+        }
+    }
+
+    /// Sanity bounds of the quotes of a price account, in its exponent: a quote whose price is
+    /// outside of `[min_price, max_price]` gets `PC_STATUS_IGNORED` when it is published, so it
+    /// doesn't count for the aggregate. This catches the quotes off by powers of ten after an
+    /// exponent mistake.
+    #[repr(C)]
+    #[derive(Copy, Clone, Debug, PartialEq, Eq, Pod, Zeroable)]
+    pub struct PriceBand {
+        pub min_price: i64,
+        pub max_price: i64,
+    }
+
+    impl PriceBand {
+        /// The band is disabled if both bounds are 0
+        pub fn is_enabled(&self) -> bool {
+            self.min_price != 0 || self.max_price != 0
+        }
+
+        pub fn contains(&self, price: i64) -> bool {
+            (self.min_price..=self.max_price).contains(&price)
+        }
+    }
+
+    /// The extensions of a price account used by the aggregation, which are `None` unless they
+    /// are enabled in the `PriceExtensionsHeader` of the account.
+    #[derive(Default)]
+    pub struct AggregationExtensions<'a> {
+        pub ema_horizons:            Option<&'a mut EmaHorizons>,
+        pub price_history:           Option<&'a mut PriceHistory>,
+        pub publisher_weights:       Option<&'a mut PublisherWeights>,
+        pub outlier_filter:          Option<&'a mut OutlierFilter>,
+        pub aggregation_diagnostics: Option<&'a mut AggregationDiagnostics>,
+        pub publisher_stats:         Option<&'a mut PublisherStats>,
+        pub aggregate_confidence:    Option<&'a mut AggregateConfidence>,
+        pub circuit_breaker:         Option<&'a mut CircuitBreaker>,
+        pub price_band:              Option<&'a mut PriceBand>,
+    }
+
+    impl<'a> AggregationExtensions<'a> {
+        /// `extensions` is the data of the price account following the account struct.
+        pub fn new(extensions: &'a mut [u8]) -> Self {
+            let mut aggregation_extensions = AggregationExtensions::default();
+            let header = match read_extension::<PriceExtensionsHeader>(extensions, 0)
+                .filter(|header| header.is_valid())
+            {
+                Some(header) => header,
+                None => return aggregation_extensions,
+            };
+            // The data is split at the offsets of the enabled extensions in increasing order, each
+            // extension getting the data up to the next one
+            let mut offsets: Vec<(usize, PriceExtensionFlags)> = header
+                .enabled
+                .iter()
+                .filter_map(|extension| Some((header.offset(extension)?, extension)))
+                .collect();
+            offsets.sort_unstable_by_key(|(offset, _)| *offset);
+            let mut data = extensions;
+            let mut position = size_of::<PriceAccountPythnet>();
+            for (i, (offset, extension)) in offsets.iter().copied().enumerate() {
+                if offset < position || offset - position > data.len() {
+                    break;
+                }
+                let next_offset = offsets
+                    .get(i + 1)
+                    .map_or(usize::MAX, |(next_offset, _)| *next_offset);
+                let (_, rest) = std::mem::take(&mut data).split_at_mut(offset - position);
+                let (extension_data, rest) =
+                    rest.split_at_mut((next_offset - offset).min(rest.len()));
+                data = rest;
+                position = offset + extension_data.len();
+                aggregation_extensions.insert(extension, extension_data);
+            }
+            aggregation_extensions
+        }
+
+        fn insert(&mut self, extension: PriceExtensionFlags, data: &'a mut [u8]) {
+            if extension == PriceExtensionFlags::EMA_HORIZONS {
+                self.ema_horizons = extension_mut(data, 0);
+            } else if extension == PriceExtensionFlags::PRICE_HISTORY {
+                self.price_history = extension_mut(data, 0);
+            } else if extension == PriceExtensionFlags::PUBLISHER_WEIGHTS {
+                self.publisher_weights = extension_mut(data, 0);
+            } else if extension == PriceExtensionFlags::OUTLIER_FILTER {
+                self.outlier_filter = extension_mut(data, 0);
+            } else if extension == PriceExtensionFlags::AGGREGATION_DIAGNOSTICS {
+                self.aggregation_diagnostics = extension_mut(data, 0);
+            } else if extension == PriceExtensionFlags::PUBLISHER_STATS {
+                self.publisher_stats = extension_mut(data, 0);
+            } else if extension == PriceExtensionFlags::AGGREGATE_CONFIDENCE {
+                self.aggregate_confidence = extension_mut(data, 0);
+            } else if extension == PriceExtensionFlags::CIRCUIT_BREAKER {
+                self.circuit_breaker = extension_mut(data, 0);
+            } else if extension == PriceExtensionFlags::PRICE_BAND {
+                self.price_band = extension_mut(data, 0);
+            }
+        }
+    }
+
+    bitflags! {
+        /// The extensions of a price account, see `PriceExtensionsHeader::enabled`.
+        #[repr(C)]
+        #[derive(Copy, Clone, Debug, PartialEq, Eq, Pod, Zeroable)]
+        pub struct PriceExtensionFlags: u32 {
+            const CONF_DIVISOR = 0b1;
+            const EMA_HALF_LIFE = 0b10;
+            const EMA_HORIZONS = 0b100;
+            const PRICE_HISTORY = 0b1000;
+            const PUBLISHER_WEIGHTS = 0b1_0000;
+            const OUTLIER_FILTER = 0b10_0000;
+            const AGGREGATION_DIAGNOSTICS = 0b100_0000;
+            const PUBLISHER_STATS = 0b1000_0000;
+            const AGGREGATE_CONFIDENCE = 0b1_0000_0000;
+            const CIRCUIT_BREAKER = 0b10_0000_0000;
+            const PRICE_BAND = 0b100_0000_0000;
+        }
+    }
+
     bitflags! {
         #[repr(C)]
         #[derive(Copy, Clone, Pod, Zeroable)]
@@ -108,10 +674,177 @@ mod price_pythnet {
             /// If unset, the program will remove old messages from its message buffer account
             /// and set this flag.
             const MESSAGE_BUFFER_CLEARED = 0b10;
+            /// If set, the quotes are weighted by the `PublisherWeights` of their publishers in
+            /// the aggregation.
+            const STAKE_WEIGHTED = 0b100;
+            /// If set, the corporate action statuses of the valid quotes are aggregated into
+            /// `agg_.corp_act_status_`, which is the status reported by a strict majority of the
+            /// quotes or 0, and published in a `CorpActStatusMessage`.
+            const CORP_ACT_STATUS = 0b1000;
+            /// If set along with `CORP_ACT_STATUS`, a corporate action reported by any valid
+            /// quote is enough: the aggregate status is the non-zero status reported by the most
+            /// quotes, the lowest one in case of a tie.
+            const CORP_ACT_ANY = 0b1_0000;
         }
     }
 
     impl PriceAccountPythnet {
+        /// Size of the account once it stores a `PriceExtensionsHeader`
+        pub const EXTENSIONS_HEADER_SPACE: usize =
+            size_of::<PriceAccountPythnet>() + size_of::<PriceExtensionsHeader>();
+
+        /// The `PriceExtensionsHeader` of the account given its data, or `None` if the account
+        /// doesn't store a valid one, in which case none of its extensions are enabled.
+        pub fn extensions_header(data: &[u8]) -> Option<PriceExtensionsHeader> {
+            read_extension::<PriceExtensionsHeader>(data, size_of::<PriceAccountPythnet>())
+                .filter(|header| header.is_valid())
+        }
+
+        /// Size of the account once it stores `extension`, of `size` bytes, given its data. The
+        /// account must be resized to it before calling `enable_extension`.
+        pub fn extension_space(data: &[u8], extension: PriceExtensionFlags, size: usize) -> usize {
+            Self::extensions_header(data)
+                .unwrap_or_default()
+                .allocation(extension)
+                + size
+        }
+
+        /// Enable `extension`, of `size` bytes, in the `PriceExtensionsHeader` of the account,
+        /// storing it at the end of the extensions stored so far if it wasn't stored before, and
+        /// return its offset. If the account doesn't store a valid header yet, its data following
+        /// the account struct is zeroed before writing one.
+        pub fn enable_extension(
+            account: &AccountInfo,
+            extension: PriceExtensionFlags,
+            size: usize,
+        ) -> Result<usize, ProgramError> {
+            let mut data = account.try_borrow_mut_data()?;
+            let mut header = match Self::extensions_header(&data) {
+                Some(header) => header,
+                None => {
+                    data.get_mut(size_of::<PriceAccountPythnet>()..)
+                        .unwrap_or_default()
+                        .fill(0);
+                    PriceExtensionsHeader::new()
+                }
+            };
+            let offset = header.allocation(extension);
+            pyth_assert(
+                data.len() >= offset + size,
+                ProgramError::AccountDataTooSmall,
+            )?;
+            header.offsets[PriceExtensionsHeader::index(extension)] = try_convert(offset)?;
+            header.end = header.end.max(try_convert(offset + size)?);
+            header.enabled.insert(extension);
+            *extension_mut(&mut data[..], size_of::<PriceAccountPythnet>())
+                .ok_or(ProgramError::AccountDataTooSmall)? = header;
+            Ok(offset)
+        }
+
+        fn read_price_extension<T: Pod>(data: &[u8], extension: PriceExtensionFlags) -> Option<T> {
+            read_extension(data, Self::extensions_header(data)?.offset(extension)?)
+        }
+
+        fn load_price_extension_mut<'a, T: Pod>(
+            account: &'a AccountInfo,
+            extension: PriceExtensionFlags,
+        ) -> Result<RefMut<'a, T>, ProgramError> {
+            let offset = Self::extensions_header(&account.try_borrow_data()?)
+                .and_then(|header| header.offset(extension))
+                .ok_or(ProgramError::InvalidAccountData)?;
+            load_extension_mut(account, offset)
+        }
+
+        /// The divisor of the confidence-to-price ratio threshold of the account: a publisher's
+        /// price is ignored if its confidence is bigger than the absolute value of the price
+        /// divided by it. Accounts without one enabled or storing 0 use `MAX_CI_DIVISOR`.
+        pub fn load_conf_divisor(account: &AccountInfo) -> Result<i64, ProgramError> {
+            let data = account.try_borrow_data()?;
+            match Self::read_price_extension::<u64>(&data, PriceExtensionFlags::CONF_DIVISOR) {
+                Some(conf_divisor) if conf_divisor != 0 => Ok(try_convert(conf_divisor)?),
+                _ => Ok(MAX_CI_DIVISOR),
+            }
+        }
+
+        /// The half-life in slots of the EMAs of the account, see `EmaParams::from_half_life`,
+        /// given the data of the account. Accounts without one enabled use the default EMAs.
+        pub fn ema_half_life(data: &[u8]) -> u64 {
+            Self::read_price_extension::<u64>(data, PriceExtensionFlags::EMA_HALF_LIFE).unwrap_or(0)
+        }
+
+        /// The `EmaHorizons` of the account given its data, or `None` if it isn't enabled.
+        pub fn ema_horizons(data: &[u8]) -> Option<EmaHorizons> {
+            Self::read_price_extension(data, PriceExtensionFlags::EMA_HORIZONS)
+        }
+
+        /// The `PriceHistory` of the account given its data, or `None` if it isn't enabled.
+        pub fn price_history(data: &[u8]) -> Option<PriceHistory> {
+            Self::read_price_extension(data, PriceExtensionFlags::PRICE_HISTORY)
+        }
+
+        /// The `PublisherWeights` of the account given its data, or `None` if it isn't enabled.
+        pub fn publisher_weights(data: &[u8]) -> Option<PublisherWeights> {
+            Self::read_price_extension(data, PriceExtensionFlags::PUBLISHER_WEIGHTS)
+        }
+
+        pub fn load_publisher_weights_mut<'a>(
+            account: &'a AccountInfo,
+        ) -> Result<RefMut<'a, PublisherWeights>, ProgramError> {
+            Self::load_price_extension_mut(account, PriceExtensionFlags::PUBLISHER_WEIGHTS)
+        }
+
+        /// The `OutlierFilter` of the account given its data, or `None` if it isn't enabled.
+        pub fn outlier_filter(data: &[u8]) -> Option<OutlierFilter> {
+            Self::read_price_extension(data, PriceExtensionFlags::OUTLIER_FILTER)
+        }
+
+        /// The `AggregationDiagnostics` of the account given its data, or `None` if it isn't
+        /// enabled.
+        pub fn aggregation_diagnostics(data: &[u8]) -> Option<AggregationDiagnostics> {
+            Self::read_price_extension(data, PriceExtensionFlags::AGGREGATION_DIAGNOSTICS)
+        }
+
+        pub fn load_aggregation_diagnostics_mut<'a>(
+            account: &'a AccountInfo,
+        ) -> Result<RefMut<'a, AggregationDiagnostics>, ProgramError> {
+            Self::load_price_extension_mut(account, PriceExtensionFlags::AGGREGATION_DIAGNOSTICS)
+        }
+
+        /// The `PublisherStats` of the account given its data, or `None` if it isn't enabled.
+        pub fn publisher_stats(data: &[u8]) -> Option<PublisherStats> {
+            Self::read_price_extension(data, PriceExtensionFlags::PUBLISHER_STATS)
+        }
+
+        pub fn load_publisher_stats_mut<'a>(
+            account: &'a AccountInfo,
+        ) -> Result<RefMut<'a, PublisherStats>, ProgramError> {
+            Self::load_price_extension_mut(account, PriceExtensionFlags::PUBLISHER_STATS)
+        }
+
+        /// The `AggregateConfidence` of the account given its data, or `None` if it isn't enabled.
+        pub fn aggregate_confidence(data: &[u8]) -> Option<AggregateConfidence> {
+            Self::read_price_extension(data, PriceExtensionFlags::AGGREGATE_CONFIDENCE)
+        }
+
+        /// The `CircuitBreaker` of the account given its data, or `None` if it isn't enabled.
+        pub fn circuit_breaker(data: &[u8]) -> Option<CircuitBreaker> {
+            Self::read_price_extension(data, PriceExtensionFlags::CIRCUIT_BREAKER)
+        }
+
+        /// The `PriceBand` of the account given its data, or `None` if it isn't enabled.
+        pub fn price_band(data: &[u8]) -> Option<PriceBand> {
+            Self::read_price_extension(data, PriceExtensionFlags::PRICE_BAND)
+        }
+
+        /// Load the price account along with the data of its extensions, see
+        /// `AggregationExtensions`.
+        pub fn load_with_extensions_mut<'a>(
+            account: &'a AccountInfo,
+            version: u32,
+        ) -> Result<(RefMut<'a, Self>, RefMut<'a, [u8]>), ProgramError> {
+            load_checked_with_extensions(account, version)
+        }
+
         pub fn as_price_feed_message(&self, key: &Pubkey) -> PriceFeedMessage {
             let (price, conf, publish_time) = if self.agg_.status_ == PC_STATUS_TRADING {
                 (self.agg_.price_, self.agg_.conf_, self.timestamp_)
@@ -142,6 +875,109 @@ mod price_pythnet {
             }
         }
 
+        pub fn as_ema_horizons_message(
+            &self,
+            key: &Pubkey,
+            ema_horizons: &EmaHorizons,
+        ) -> EmaHorizonsMessage {
+            let publish_time = if self.agg_.status_ == PC_STATUS_TRADING {
+                self.timestamp_
+            } else {
+                self.prev_timestamp_
+            };
+
+            EmaHorizonsMessage {
+                feed_id: key.to_bytes(),
+                exponent: self.exponent,
+                publish_time,
+                prev_publish_time: self.prev_timestamp_,
+                emas: std::array::from_fn(|i| HorizonEma {
+                    half_life: ema_horizons.half_lives[i],
+                    ema_price: ema_horizons.twap[i].val_,
+                    ema_conf:  ema_horizons.twac[i].val_ as u64,
+                }),
+            }
+        }
+
+        /// The statistics of the current publishers of the account, whose entries are in place
+        /// since the last successful aggregation
+        pub fn as_publisher_stats_message(
+            &self,
+            key: &Pubkey,
+            publisher_stats: &PublisherStats,
+        ) -> PublisherStatsMessage {
+            let num_comps = (self.num_ as usize).min(PC_NUM_COMP as usize);
+            PublisherStatsMessage {
+                feed_id:      key.to_bytes(),
+                publish_slot: self.last_slot_,
+                stats:        self.comp_[..num_comps]
+                    .iter()
+                    .zip(publisher_stats.entries.iter())
+                    .filter(|(comp, entry)| comp.pub_ == entry.publisher)
+                    .map(|(_, entry)| *entry)
+                    .collect(),
+            }
+        }
+
+        /// Like the price feed message, this gives the last aggregate if it has
+        /// `PC_STATUS_TRADING` or the previous one otherwise. Both sides of the confidence
+        /// interval are `conf` if that aggregate predates the `AggregateConfidence`.
+        pub fn as_aggregate_confidence_message(
+            &self,
+            key: &Pubkey,
+            aggregate_confidence: &AggregateConfidence,
+        ) -> AggregateConfidenceMessage {
+            let (price, conf, publish_slot, publish_time) =
+                if self.agg_.status_ == PC_STATUS_TRADING {
+                    (
+                        self.agg_.price_,
+                        self.agg_.conf_,
+                        self.agg_.pub_slot_,
+                        self.timestamp_,
+                    )
+                } else {
+                    (
+                        self.prev_price_,
+                        self.prev_conf_,
+                        self.prev_slot_,
+                        self.prev_timestamp_,
+                    )
+                };
+            let (conf_left, conf_right) = if aggregate_confidence.pub_slot == publish_slot {
+                (
+                    aggregate_confidence.conf_left,
+                    aggregate_confidence.conf_right,
+                )
+            } else {
+                (conf, conf)
+            };
+
+            AggregateConfidenceMessage {
+                feed_id: key.to_bytes(),
+                price,
+                conf_left,
+                conf_right,
+                exponent: self.exponent,
+                publish_time,
+                prev_publish_time: self.prev_timestamp_,
+            }
+        }
+
+        pub fn as_corp_act_status_message(&self, key: &Pubkey) -> CorpActStatusMessage {
+            let publish_time = if self.agg_.status_ == PC_STATUS_TRADING {
+                self.timestamp_
+            } else {
+                self.prev_timestamp_
+            };
+
+            CorpActStatusMessage {
+                feed_id: key.to_bytes(),
+                corp_act_status: self.agg_.corp_act_status_,
+                publish_time,
+                prev_publish_time: self.prev_timestamp_,
+            }
+        }
+
         pub fn as_twap_message(&self, key: &Pubkey) -> TwapMessage {
             let publish_time = if self.agg_.status_ == PC_STATUS_TRADING {
                 self.timestamp_
@@ -260,6 +1096,58 @@ pub struct PriceEma {
     pub denom_: i64,
 }
 
+/// The EMAs of the horizons of a price feed, see `EmaHorizons`. Unused horizons have a zero
+/// half-life.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EmaHorizonsMessage {
+    pub feed_id:           [u8; 32],
+    pub exponent:          i32,
+    pub publish_time:      i64,
+    pub prev_publish_time: i64,
+    pub emas:              [HorizonEma; NUM_EMA_HORIZONS],
+}
+
+/// The aggregate corporate action status of a price feed, see
+/// `PriceAccountFlags::CORP_ACT_STATUS`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CorpActStatusMessage {
+    pub feed_id:           [u8; 32],
+    /// 0 if no corporate action is reported
+    pub corp_act_status:   u32,
+    pub publish_time:      i64,
+    pub prev_publish_time: i64,
+}
+
+/// The aggregate price of a price feed with both sides of its confidence interval, see
+/// `AggregateConfidence`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AggregateConfidenceMessage {
+    pub feed_id:           [u8; 32],
+    pub price:             i64,
+    pub conf_left:         u64,
+    pub conf_right:        u64,
+    pub exponent:          i32,
+    pub publish_time:      i64,
+    pub prev_publish_time: i64,
+}
+
+/// The statistics of the publishers of a price feed, see `PublisherStats`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PublisherStatsMessage {
+    pub feed_id:      [u8; 32],
+    /// Slot of the last successful aggregation
+    pub publish_slot: u64,
+    pub stats:        Vec<PublisherStat>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HorizonEma {
+    /// Half-life in slots of the EMAs
+    pub half_life: u64,
+    pub ema_price: i64,
+    pub ema_conf:  u64,
+}
+
 pub trait PythOracleSerialize {
     fn to_bytes(self) -> Vec<u8>;
 }
@@ -369,3 +1257,130 @@ impl PythOracleSerialize for PublisherStakeCapsMessage {
         result
     }
 }
+
+impl PythOracleSerialize for EmaHorizonsMessage {
+    #[allow(unused_assignments)]
+    fn to_bytes(self) -> Vec<u8> {
+        const MESSAGE_SIZE: usize = 1 + 32 + 4 + 8 + 8 + (8 + 8 + 8) * NUM_EMA_HORIZONS;
+        const DISCRIMINATOR: u8 = 3;
+        let mut bytes = [0u8; MESSAGE_SIZE];
+
+        let mut i: usize = 0;
+
+        bytes[i..i + 1].clone_from_slice(&[DISCRIMINATOR]);
+        i += 1;
+
+        bytes[i..i + 32].clone_from_slice(&self.feed_id[..]);
+        i += 32;
+
+        bytes[i..i + 4].clone_from_slice(&self.exponent.to_be_bytes());
+        i += 4;
+
+        bytes[i..i + 8].clone_from_slice(&self.publish_time.to_be_bytes());
+        i += 8;
+
+        bytes[i..i + 8].clone_from_slice(&self.prev_publish_time.to_be_bytes());
+        i += 8;
+
+        for ema in self.emas {
+            bytes[i..i + 8].clone_from_slice(&ema.half_life.to_be_bytes());
+            i += 8;
+
+            bytes[i..i + 8].clone_from_slice(&ema.ema_price.to_be_bytes());
+            i += 8;
+
+            bytes[i..i + 8].clone_from_slice(&ema.ema_conf.to_be_bytes());
+            i += 8;
+        }
+
+        bytes.to_vec()
+    }
+}
+
+impl PythOracleSerialize for PublisherStatsMessage {
+    fn to_bytes(self) -> Vec<u8> {
+        const DISCRIMINATOR: u8 = 4;
+        let mut result = vec![DISCRIMINATOR];
+        result.extend_from_slice(&self.feed_id);
+        result.extend_from_slice(&self.publish_slot.to_be_bytes());
+        result.extend_from_slice(
+            &u16::try_from(self.stats.len())
+                .unwrap_or(u16::MAX)
+                .to_be_bytes(),
+        );
+
+        for stat in self.stats {
+            result.extend_from_slice(&stat.publisher.to_bytes());
+            result.extend_from_slice(&stat.num_aggregations.to_be_bytes());
+            result.extend_from_slice(&stat.num_misses.to_be_bytes());
+            result.extend_from_slice(&stat.sum_deviation.to_be_bytes());
+        }
+
+        result
+    }
+}
+
+impl PythOracleSerialize for CorpActStatusMessage {
+    #[allow(unused_assignments)]
+    fn to_bytes(self) -> Vec<u8> {
+        const MESSAGE_SIZE: usize = 1 + 32 + 4 + 8 + 8;
+        const DISCRIMINATOR: u8 = 5;
+        let mut bytes = [0u8; MESSAGE_SIZE];
+
+        let mut i: usize = 0;
+
+        bytes[i..i + 1].clone_from_slice(&[DISCRIMINATOR]);
+        i += 1;
+
+        bytes[i..i + 32].clone_from_slice(&self.feed_id[..]);
+        i += 32;
+
+        bytes[i..i + 4].clone_from_slice(&self.corp_act_status.to_be_bytes());
+        i += 4;
+
+        bytes[i..i + 8].clone_from_slice(&self.publish_time.to_be_bytes());
+        i += 8;
+
+        bytes[i..i + 8].clone_from_slice(&self.prev_publish_time.to_be_bytes());
+        i += 8;
+
+        bytes.to_vec()
+    }
+}
+
+impl PythOracleSerialize for AggregateConfidenceMessage {
+    #[allow(unused_assignments)]
+    fn to_bytes(self) -> Vec<u8> {
+        const MESSAGE_SIZE: usize = 1 + 32 + 8 + 8 + 8 + 4 + 8 + 8;
+        const DISCRIMINATOR: u8 = 6;
+        let mut bytes = [0u8; MESSAGE_SIZE];
+
+        let mut i: usize = 0;
+
+        bytes[i..i + 1].clone_from_slice(&[DISCRIMINATOR]);
+        i += 1;
+
+        bytes[i..i + 32].clone_from_slice(&self.feed_id[..]);
+        i += 32;
+
+        bytes[i..i + 8].clone_from_slice(&self.price.to_be_bytes());
+        i += 8;
+
+        bytes[i..i + 8].clone_from_slice(&self.conf_left.to_be_bytes());
+        i += 8;
+
+        bytes[i..i + 8].clone_from_slice(&self.conf_right.to_be_bytes());
+        i += 8;
+
+        bytes[i..i + 4].clone_from_slice(&self.exponent.to_be_bytes());
+        i += 4;
+
+        bytes[i..i + 8].clone_from_slice(&self.publish_time.to_be_bytes());
+        i += 8;
+
+        bytes[i..i + 8].clone_from_slice(&self.prev_publish_time.to_be_bytes());
+        i += 8;
+
+        bytes.to_vec()
+    }
+}
@@ -8,7 +8,10 @@ use {
             PC_ACCTYPE_PRODUCT,
             PC_PROD_ACC_SIZE,
         },
-        deserialize::load_checked,
+        deserialize::{
+            load_checked,
+            read_extension,
+        },
         instruction::CommandHeader,
         utils::{
             pyth_assert,
@@ -42,6 +45,154 @@ impl PythAccount for ProductAccount {
     const MINIMUM_SIZE: usize = PC_PROD_ACC_SIZE as usize;
 }
 
+impl ProductAccount {
+    /// Size of the account once it stores a `TradingSchedule`, which follows the space reserved
+    /// for the metadata
+    pub const TRADING_SCHEDULE_SPACE: usize = Self::MINIMUM_SIZE + size_of::<TradingSchedule>();
+
+    /// The `TradingSchedule` of the account given its data, or `None` if the account was never
+    /// resized to store one.
+    pub fn trading_schedule(data: &[u8]) -> Option<TradingSchedule> {
+        read_extension(data, Self::MINIMUM_SIZE)
+    }
+}
+
+/// Maximum number of intervals of a `TradingSchedule`
+pub const MAX_TRADING_INTERVALS: usize = 28;
+const MINUTES_PER_DAY: u16 = 24 * 60;
+const DAYS_PER_WEEK: u16 = 7;
+
+/// The weekly trading hours of the price accounts of a product, outside of which their
+/// aggregates have `PC_STATUS_HALTED`, see `update_aggregate`. The schedule has a fixed offset
+/// from UTC, so it needs to be updated when the market switches to or from daylight saving time.
+/// It is stored in the product account after its metadata, see `ProductAccount::trading_schedule`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Pod, Zeroable)]
+pub struct TradingSchedule {
+    /// The schedule is disabled if it is 0, the price accounts then always trade
+    pub enabled:       u32,
+    /// Offset from UTC in minutes of the time of the schedule
+    pub utc_offset:    i32,
+    /// Number of used entries of `intervals`
+    pub num_intervals: u32,
+    pub unused_:       u32,
+    /// The trading hours, in increasing order
+    pub intervals:     [TradingInterval; MAX_TRADING_INTERVALS],
+}
+
+/// A range of minutes of the week in the time of a `TradingSchedule`, 0 being Monday 00:00
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Pod, Zeroable)]
+pub struct TradingInterval {
+    pub start: u16,
+    /// First minute after the interval
+    pub end:   u16,
+}
+
+impl TradingSchedule {
+    /// Parse a schedule such as `-0500;0930-1600,0930-1600,0930-1600,0930-1600,0930-1600,C,C`:
+    /// the offset from UTC (`+HHMM` or `-HHMM`), then after a `;` the comma-separated trading
+    /// hours of each day from Monday to Sunday. The hours of a day are either `O` (open all
+    /// day), `C` (closed) or increasing `HHMM-HHMM` intervals separated by `&`, `2400` being
+    /// the end of the day. An empty schedule gives a disabled one.
+    pub fn parse(schedule: &[u8]) -> Result<Self, ProgramError> {
+        let mut trading_schedule = TradingSchedule::zeroed();
+        if schedule.is_empty() {
+            return Ok(trading_schedule);
+        }
+        let schedule = std::str::from_utf8(schedule).map_err(|_| ProgramError::InvalidArgument)?;
+        let (utc_offset, days) = schedule
+            .split_once(';')
+            .ok_or(ProgramError::InvalidArgument)?;
+        trading_schedule.enabled = 1;
+        trading_schedule.utc_offset = if let Some(time) = utc_offset.strip_prefix('+') {
+            i32::from(parse_time(time, MINUTES_PER_DAY - 1)?)
+        } else if let Some(time) = utc_offset.strip_prefix('-') {
+            -i32::from(parse_time(time, MINUTES_PER_DAY - 1)?)
+        } else {
+            return Err(ProgramError::InvalidArgument);
+        };
+
+        let mut num_days = 0;
+        for hours in days.split(',') {
+            pyth_assert(num_days < DAYS_PER_WEEK, ProgramError::InvalidArgument)?;
+            let day_start = num_days * MINUTES_PER_DAY;
+            match hours {
+                "C" => {}
+                "O" => trading_schedule.push(day_start, day_start + MINUTES_PER_DAY)?,
+                _ => {
+                    let mut prev_end = 0;
+                    for interval in hours.split('&') {
+                        let (start, end) = interval
+                            .split_once('-')
+                            .ok_or(ProgramError::InvalidArgument)?;
+                        let start = parse_time(start, MINUTES_PER_DAY)?;
+                        let end = parse_time(end, MINUTES_PER_DAY)?;
+                        pyth_assert(
+                            prev_end <= start && start < end,
+                            ProgramError::InvalidArgument,
+                        )?;
+                        trading_schedule.push(day_start + start, day_start + end)?;
+                        prev_end = end;
+                    }
+                }
+            }
+            num_days += 1;
+        }
+        pyth_assert(num_days == DAYS_PER_WEEK, ProgramError::InvalidArgument)?;
+
+        Ok(trading_schedule)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled != 0
+    }
+
+    /// Whether the unix timestamp `timestamp` is within the trading hours, which is always
+    /// the case if the schedule is disabled
+    pub fn is_open(&self, timestamp: i64) -> bool {
+        if !self.is_enabled() {
+            return true;
+        }
+        // 1970-01-01 was a Thursday, 3 days after the start of its week
+        let minute_of_week = (timestamp.div_euclid(60)
+            + i64::from(self.utc_offset)
+            + 3 * i64::from(MINUTES_PER_DAY))
+        .rem_euclid(i64::from(DAYS_PER_WEEK * MINUTES_PER_DAY));
+        let num_intervals = (self.num_intervals as usize).min(MAX_TRADING_INTERVALS);
+        self.intervals[..num_intervals].iter().any(|interval| {
+            i64::from(interval.start) <= minute_of_week && minute_of_week < i64::from(interval.end)
+        })
+    }
+
+    fn push(&mut self, start: u16, end: u16) -> Result<(), ProgramError> {
+        let interval = self
+            .intervals
+            .get_mut(self.num_intervals as usize)
+            .ok_or(ProgramError::InvalidArgument)?;
+        *interval = TradingInterval { start, end };
+        self.num_intervals += 1;
+        Ok(())
+    }
+}
+
+/// Parse a `HHMM` time into minutes, which may not exceed `max`
+fn parse_time(time: &str, max: u16) -> Result<u16, ProgramError> {
+    pyth_assert(
+        time.len() == 4 && time.bytes().all(|b| b.is_ascii_digit()),
+        ProgramError::InvalidArgument,
+    )?;
+    let hours: u16 = time[..2]
+        .parse()
+        .map_err(|_| ProgramError::InvalidArgument)?;
+    let minutes: u16 = time[2..]
+        .parse()
+        .map_err(|_| ProgramError::InvalidArgument)?;
+    let time = hours * 60 + minutes;
+    pyth_assert(minutes < 60 && time <= max, ProgramError::InvalidArgument)?;
+    Ok(time)
+}
+
 /// Updates the metadata in a product account.
 /// The product metadata is located after the header. It is a key-value storage
 /// where keys are strings and values are strings
@@ -106,7 +257,7 @@ pub fn read_pc_str_t(source: &[u8]) -> Result<&[u8], ProgramError> {
     }
 }
 
-#[cfg(test)]
+#[cfg(any(test, feature = "library"))]
 pub fn create_pc_str_t(s: &str) -> Vec<u8> {
     let mut v = vec![s.len() as u8];
     v.extend_from_slice(s.as_bytes());
@@ -0,0 +1,46 @@
+use {
+    super::{
+        AccountHeader,
+        PythAccount,
+    },
+    crate::c_oracle_header::PC_ACCTYPE_PUBLISHER_BLOCKLIST,
+    bytemuck::{
+        Pod,
+        Zeroable,
+    },
+    solana_program::pubkey::Pubkey,
+    std::mem::size_of,
+};
+
+/// Maximum number of keys in the `PublisherBlocklistAccount`
+pub const MAX_BLOCKED_PUBLISHERS: usize = 64;
+
+/// This account stores the publisher keys whose quotes are ignored by every price account, for
+/// example because they were compromised. There is a single blocklist account under
+/// `PUBLISHER_BLOCKLIST_SEED`, which is only ever initialized at that address, and it is updated
+/// by the security authority.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct PublisherBlocklistAccount {
+    pub header:         AccountHeader,
+    /// Number of keys in `publishers`
+    pub num_publishers: u32,
+    /// Bump seed of the PDA of the account, see `load_publisher_blocklist`
+    pub bump:           u8,
+    pub unused_:        [u8; 3],
+    pub publishers:     [Pubkey; MAX_BLOCKED_PUBLISHERS],
+}
+
+impl PublisherBlocklistAccount {
+    pub fn is_blocked(&self, publisher: &Pubkey) -> bool {
+        self.publishers
+            .iter()
+            .take(self.num_publishers as usize)
+            .any(|key| key == publisher)
+    }
+}
+
+impl PythAccount for PublisherBlocklistAccount {
+    const ACCOUNT_TYPE: u32 = PC_ACCTYPE_PUBLISHER_BLOCKLIST;
+    const INITIAL_SIZE: u32 = size_of::<PublisherBlocklistAccount>() as u32;
+}
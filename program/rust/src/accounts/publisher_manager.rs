@@ -0,0 +1,60 @@
+use {
+    super::{
+        AccountHeader,
+        MappingAccount,
+        PythAccount,
+    },
+    crate::c_oracle_header::PC_ACCTYPE_PUBLISHER_MANAGER,
+    bytemuck::{
+        Pod,
+        Zeroable,
+    },
+    solana_program::pubkey::Pubkey,
+    std::mem::size_of,
+};
+
+/// Maximum number of products in the scope of a `PublisherManagerAccount`
+pub const MAX_PUBLISHER_MANAGER_PRODUCTS: usize = 32;
+
+/// This account delegates the management of the publishers of some price accounts to a
+/// publisher manager key, which can then add and remove publishers like the security authority.
+/// The scope of the delegation is either the products of a mapping account or a list of products.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct PublisherManagerAccount {
+    pub header:          AccountHeader,
+    /// The key that can add and remove the publishers of the price accounts in scope
+    pub manager:         Pubkey,
+    /// If not the default pubkey, the price accounts of the products of this mapping account are
+    /// in scope and `products` is empty
+    pub mapping_account: Pubkey,
+    /// Number of products in `products`
+    pub num_products:    u32,
+    pub unused_:         u32,
+    /// The products whose price accounts are in scope
+    pub products:        [Pubkey; MAX_PUBLISHER_MANAGER_PRODUCTS],
+}
+
+impl PublisherManagerAccount {
+    /// Whether the price accounts of `product` are in the scope of the delegation. `mapping` must
+    /// be the mapping account of the scope, if there is one.
+    pub fn is_in_scope(&self, product: &Pubkey, mapping: Option<&MappingAccount>) -> bool {
+        match mapping {
+            Some(mapping) => mapping
+                .products_list
+                .iter()
+                .take(mapping.number_of_products as usize)
+                .any(|key| key == product),
+            None => self
+                .products
+                .iter()
+                .take(self.num_products as usize)
+                .any(|key| key == product),
+        }
+    }
+}
+
+impl PythAccount for PublisherManagerAccount {
+    const ACCOUNT_TYPE: u32 = PC_ACCTYPE_PUBLISHER_MANAGER;
+    const INITIAL_SIZE: u32 = size_of::<PublisherManagerAccount>() as u32;
+}
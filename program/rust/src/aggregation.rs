@@ -0,0 +1,390 @@
+//! Aggregation of the publishers' prices into the aggregate price of a price account.
+//!
+//! There are two implementations of the aggregation logic: the original C code (used by
+//! default) and a pure Rust port that is selected by the `rust-aggregation` cargo feature.
+//! The program built with the Rust port doesn't link the C code, which is only compiled for the
+//! tests checking the port against it. The stake-weighted aggregation and the outlier rejection
+//! of `weighted` are always implemented in Rust.
+
+use crate::{
+    accounts::{
+        AggregateConfidence,
+        AggregationExtensions,
+        EmaHorizons,
+        PriceAccount,
+        PriceAccountFlags,
+        PriceHistoryEntry,
+        PublisherBlocklistAccount,
+        TradingSchedule,
+    },
+    c_oracle_header::{
+        PC_NUM_COMP,
+        PC_STATUS_HALTED,
+        PC_STATUS_IGNORED,
+    },
+};
+
+#[cfg(any(test, not(feature = "rust-aggregation")))]
+#[cfg_attr(feature = "rust-aggregation", allow(dead_code))]
+pub mod c;
+pub mod circuit_breaker;
+pub mod diagnostics;
+pub mod outliers;
+#[cfg(any(test, feature = "rust-aggregation"))]
+mod pd;
+#[cfg(any(test, feature = "rust-aggregation"))]
+mod price_model;
+#[cfg(any(test, feature = "rust-aggregation"))]
+pub mod rust;
+pub mod weighted;
+
+#[cfg(not(feature = "rust-aggregation"))]
+pub use c::{
+    upd_aggregate,
+    upd_emas,
+    upd_twap,
+};
+#[cfg(feature = "rust-aggregation")]
+pub use rust::{
+    upd_aggregate,
+    upd_emas,
+    upd_twap,
+};
+
+/// Maximum number of slots between two aggregates before the EMAs get reset, by default
+pub const PD_EMA_MAX_DIFF: i64 = 4145;
+/// 1e9*-log(2)/5921, the default decay per slot of the EMAs
+pub const PD_EMA_DECAY: i64 = -117065;
+/// 1e9*log(2)
+const LN_2_SCALE9: i64 = 693_147_181;
+/// Maximum half-life of the EMAs, for which the decay per slot is still non-zero
+pub const MAX_EMA_HALF_LIFE: u64 = LN_2_SCALE9 as u64;
+
+/// Parameters of the EMAs of a price account
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EmaParams {
+    /// Decay per slot, with exponent -9
+    pub decay:    i64,
+    /// Maximum number of slots between two aggregates before the EMAs get reset
+    pub max_diff: i64,
+}
+
+impl Default for EmaParams {
+    fn default() -> Self {
+        EmaParams {
+            decay:    PD_EMA_DECAY,
+            max_diff: PD_EMA_MAX_DIFF,
+        }
+    }
+}
+
+impl EmaParams {
+    /// The parameters of EMAs with a half-life of `half_life` slots. Like the defaults, which
+    /// correspond to a half-life of 5921 slots, the EMAs are reset after 70% of the half-life
+    /// without aggregation. 0 or a half-life above `MAX_EMA_HALF_LIFE` give the defaults.
+    pub fn from_half_life(half_life: u64) -> Self {
+        match i64::try_from(half_life) {
+            Ok(half_life) if half_life > 0 && half_life <= LN_2_SCALE9 => EmaParams {
+                decay:    -(LN_2_SCALE9 / half_life),
+                max_diff: (7 * half_life + 9) / 10,
+            },
+            _ => EmaParams::default(),
+        }
+    }
+}
+
+/// Compute a new aggregate price from the publishers' latest prices, applying the stages
+/// configured by the `extensions` of the price account, ignoring the quotes of the publishers of
+/// `publisher_blocklist`, if any, and halting the aggregate outside of the hours of the
+/// `trading_schedule` of its product, if any. If the aggregation succeeds, this also updates the
+/// EMAs and the cumulative sums of the price account.
+/// Returns `true` if the aggregate was successfully updated.
+pub fn update_aggregate(
+    price_account: &mut PriceAccount,
+    slot: u64,
+    timestamp: i64,
+    ema_params: EmaParams,
+    extensions: &mut AggregationExtensions,
+    publisher_blocklist: Option<&PublisherBlocklistAccount>,
+    trading_schedule: Option<&TradingSchedule>,
+) -> bool {
+    // The quotes of the publishers of the `PublisherBlocklistAccount` are left out
+    let blocked_quotes =
+        publisher_blocklist.map(|blocklist| BlockedQuotes::ignore(price_account, blocklist));
+    // Quotes are weighted by the `PublisherWeights` with `PriceAccountFlags::STAKE_WEIGHTED`
+    // and the `OutlierFilter` rejects outliers before the aggregation
+    let stake_weighted = price_account
+        .flags
+        .contains(PriceAccountFlags::STAKE_WEIGHTED);
+    let outlier_filter = extensions
+        .outlier_filter
+        .as_deref_mut()
+        .filter(|outlier_filter| outlier_filter.is_enabled());
+    // Outside of the trading hours of the `TradingSchedule`, the aggregate keeps its previous
+    // price and gets `PC_STATUS_HALTED`
+    let halted = trading_schedule.map_or(false, |trading_schedule| {
+        !trading_schedule.is_open(timestamp)
+    });
+    // Both sides of the confidence interval of a successful aggregate are recorded in the
+    // `AggregateConfidence`
+    let last_aggregate_confidence = extensions.aggregate_confidence.as_deref().copied();
+    let aggregate_confidence = extensions
+        .aggregate_confidence
+        .as_deref_mut()
+        .filter(|_| !halted);
+    let last_aggregate = (
+        price_account.agg_.price_,
+        price_account.agg_.conf_,
+        price_account.last_slot_,
+    );
+    let mut updated = if stake_weighted || outlier_filter.is_some() {
+        let publisher_weights = extensions.publisher_weights.as_deref();
+        weighted::upd_aggregate_weighted(
+            price_account,
+            slot,
+            timestamp,
+            |publisher| match publisher_weights {
+                _ if !stake_weighted => 1,
+                Some(publisher_weights) => publisher_weights.weight(publisher),
+                None => 0,
+            },
+            outlier_filter,
+            extensions.aggregation_diagnostics.as_deref_mut(),
+            aggregate_confidence,
+        )
+    } else {
+        let updated = upd_aggregate(price_account, slot, timestamp);
+        // The reasons why quotes were left out are recorded in the `AggregationDiagnostics`
+        if let Some(diagnostics) = extensions.aggregation_diagnostics.as_deref_mut() {
+            diagnostics::record_exclusion_reasons(price_account, slot, diagnostics);
+        }
+        if let Some(aggregate_confidence) = aggregate_confidence.filter(|_| updated) {
+            record_aggregate_confidence(price_account, slot, aggregate_confidence);
+        }
+        updated
+    };
+    if let (Some(diagnostics), Some(blocked_quotes)) = (
+        extensions.aggregation_diagnostics.as_deref_mut(),
+        blocked_quotes.as_ref(),
+    ) {
+        diagnostics::record_blocked(blocked_quotes.mask, diagnostics);
+    }
+    if let (Some(diagnostics), Some(price_band)) = (
+        extensions.aggregation_diagnostics.as_deref_mut(),
+        extensions.price_band.as_deref(),
+    ) {
+        diagnostics::record_outside_price_band(price_account, price_band, diagnostics);
+    }
+    if halted {
+        (
+            price_account.agg_.price_,
+            price_account.agg_.conf_,
+            price_account.last_slot_,
+        ) = last_aggregate;
+        price_account.agg_.status_ = PC_STATUS_HALTED;
+        updated = false;
+    } else if let Some(circuit_breaker) = extensions
+        .circuit_breaker
+        .as_deref_mut()
+        .filter(|circuit_breaker| circuit_breaker.is_enabled())
+    {
+        // A successful aggregate that trips the `CircuitBreaker` gets `PC_STATUS_HALTED` and
+        // doesn't count as successful
+        let may_trade =
+            circuit_breaker::check_circuit_breaker(price_account, slot, updated, circuit_breaker);
+        if updated && !may_trade {
+            // Unlike outside of the trading hours, the new price is kept for reference
+            price_account.last_slot_ = last_aggregate.2;
+            price_account.agg_.status_ = PC_STATUS_HALTED;
+            if let (Some(aggregate_confidence), Some(last_aggregate_confidence)) = (
+                extensions.aggregate_confidence.as_deref_mut(),
+                last_aggregate_confidence,
+            ) {
+                *aggregate_confidence = last_aggregate_confidence;
+            }
+            updated = false;
+        }
+    }
+    // The corporate action statuses of the quotes are aggregated with
+    // `PriceAccountFlags::CORP_ACT_STATUS`
+    price_account.agg_.corp_act_status_ = if price_account
+        .flags
+        .contains(PriceAccountFlags::CORP_ACT_STATUS)
+    {
+        aggregate_corp_act_status(price_account, slot)
+    } else {
+        0
+    };
+
+    // Every aggregation is recorded in the `PriceHistory`, successful or not
+    if let Some(price_history) = extensions.price_history.as_deref_mut() {
+        price_history.push(PriceHistoryEntry {
+            slot,
+            timestamp,
+            price: price_account.agg_.price_,
+            conf: price_account.agg_.conf_,
+            status: price_account.agg_.status_,
+            unused_: 0,
+        });
+    }
+
+    // If the aggregate was successfully updated, calculate the difference and update TWAP.
+    if updated {
+        let agg_diff = (slot as i64) - price_account.prev_slot_ as i64;
+        upd_twap(price_account, agg_diff, ema_params);
+        if let Some(ema_horizons) = extensions.ema_horizons.as_deref_mut() {
+            upd_ema_horizons(price_account, ema_horizons, agg_diff);
+        }
+        // The quotes of a successful aggregation count in the `PublisherStats`
+        if let Some(publisher_stats) = extensions.publisher_stats.as_deref_mut() {
+            diagnostics::update_publisher_stats(price_account, slot, publisher_stats);
+        }
+
+        // We want to send a message every time the aggregate price updates. However, during the migration,
+        // not every publisher will necessarily provide the accumulator accounts. The message_sent_ flag
+        // ensures that after every aggregate update, the next publisher who provides the accumulator accounts
+        // will send the message.
+        price_account.message_sent_ = 0;
+        price_account.update_price_cumulative();
+    }
+
+    if let Some(blocked_quotes) = blocked_quotes {
+        blocked_quotes.restore(price_account);
+    }
+
+    updated
+}
+
+/// The statuses of the latest quotes of the blocked publishers of a price account, which get
+/// `PC_STATUS_IGNORED` while the aggregation runs so that both aggregation implementations leave
+/// them out. The `agg_` of these components keeps `PC_STATUS_IGNORED` as their quotes were left
+/// out, but their latest quotes are restored once the aggregation is done.
+struct BlockedQuotes {
+    /// Bit `i` is set if the publisher of `comp_[i]` is blocked
+    mask:     u64,
+    statuses: [u32; PC_NUM_COMP as usize],
+}
+
+impl BlockedQuotes {
+    fn ignore(
+        price_account: &mut PriceAccount,
+        publisher_blocklist: &PublisherBlocklistAccount,
+    ) -> Self {
+        let mut blocked_quotes = BlockedQuotes {
+            mask:     0,
+            statuses: [0; PC_NUM_COMP as usize],
+        };
+        let num_comps = (price_account.num_ as usize).min(PC_NUM_COMP as usize);
+        for (i, comp) in price_account.comp_[..num_comps].iter_mut().enumerate() {
+            if publisher_blocklist.is_blocked(&comp.pub_) {
+                blocked_quotes.mask |= 1 << i;
+                blocked_quotes.statuses[i] = comp.latest_.status_;
+                comp.latest_.status_ = PC_STATUS_IGNORED;
+            }
+        }
+        blocked_quotes
+    }
+
+    fn restore(&self, price_account: &mut PriceAccount) {
+        let num_comps = (price_account.num_ as usize).min(PC_NUM_COMP as usize);
+        for (i, comp) in price_account.comp_[..num_comps].iter_mut().enumerate() {
+            if self.mask & (1 << i) != 0 {
+                comp.latest_.status_ = self.statuses[i];
+            }
+        }
+    }
+}
+
+/// Record both sides of the confidence interval of the aggregate computed by `upd_aggregate` for
+/// `slot`, which only keeps the larger one, by running the price model again on the valid quotes.
+// This is kept out of `update_aggregate` so that its scratch space doesn't share its stack frame.
+#[inline(never)]
+fn record_aggregate_confidence(
+    price_account: &PriceAccount,
+    slot: u64,
+    aggregate_confidence: &mut AggregateConfidence,
+) {
+    let max_latency = diagnostics::max_latency(price_account);
+    let mut prcs = [(0i64, 1u64); PC_NUM_COMP as usize * 3];
+    let mut nprcs = 0;
+    let num_comps = (price_account.num_ as usize).min(PC_NUM_COMP as usize);
+    for comp in price_account.comp_[..num_comps].iter() {
+        if diagnostics::check_quote(&comp.agg_, slot, max_latency, 1).is_none() {
+            let price = comp.agg_.price_;
+            let conf = comp.agg_.conf_ as i64;
+            prcs[nprcs].0 = price - conf;
+            prcs[nprcs + 1].0 = price;
+            prcs[nprcs + 2].0 = price + conf;
+            nprcs += 3;
+        }
+    }
+
+    // With equal weights, this is the price model of `upd_aggregate`
+    let (agg_p25, agg_price, agg_p75) = weighted::weighted_price_model_core(&mut prcs[..nprcs]);
+    *aggregate_confidence = AggregateConfidence {
+        pub_slot:   slot,
+        conf_left:  agg_price.wrapping_sub(agg_p25) as u64,
+        conf_right: agg_p75.wrapping_sub(agg_price) as u64,
+    };
+}
+
+/// The aggregate corporate action status of the valid quotes of the aggregation of `slot`, see
+/// `PriceAccountFlags::CORP_ACT_STATUS`.
+fn aggregate_corp_act_status(price_account: &PriceAccount, slot: u64) -> u32 {
+    let max_latency = diagnostics::max_latency(price_account);
+    let mut statuses = [0u32; PC_NUM_COMP as usize];
+    let mut num_statuses = 0;
+    let num_comps = (price_account.num_ as usize).min(PC_NUM_COMP as usize);
+    for comp in price_account.comp_[..num_comps].iter() {
+        if diagnostics::check_quote(&comp.agg_, slot, max_latency, 1).is_none() {
+            statuses[num_statuses] = comp.agg_.corp_act_status_;
+            num_statuses += 1;
+        }
+    }
+    let statuses = &mut statuses[..num_statuses];
+    statuses.sort_unstable();
+
+    // Find the status reported by the most quotes, ignoring 0 with CORP_ACT_ANY, the lowest one
+    // in case of a tie
+    let any = price_account
+        .flags
+        .contains(PriceAccountFlags::CORP_ACT_ANY);
+    let (mut best_status, mut best_count) = (0, 0);
+    let mut i = 0;
+    while i < statuses.len() {
+        let status = statuses[i];
+        let count = statuses[i..].iter().take_while(|s| **s == status).count();
+        if (status != 0 || !any) && count > best_count {
+            (best_status, best_count) = (status, count);
+        }
+        i += count;
+    }
+
+    if any || 2 * best_count > num_statuses {
+        best_status
+    } else {
+        0
+    }
+}
+
+/// Update the EMAs of the horizons in use, `nslots` being the number of slots since the previous
+/// successful aggregation.
+fn upd_ema_horizons(price_account: &PriceAccount, ema_horizons: &mut EmaHorizons, nslots: i64) {
+    for ((half_life, twap), twac) in ema_horizons
+        .half_lives
+        .iter()
+        .zip(ema_horizons.twap.iter_mut())
+        .zip(ema_horizons.twac.iter_mut())
+    {
+        if *half_life != 0 {
+            upd_emas(
+                twap,
+                twac,
+                &price_account.agg_,
+                price_account.exponent,
+                nslots,
+                EmaParams::from_half_life(*half_life),
+            );
+        }
+    }
+}
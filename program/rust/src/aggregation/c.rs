@@ -0,0 +1,95 @@
+//! Bindings to the C implementation of the aggregation logic in `upd_aggregate.h`.
+
+use {
+    super::EmaParams,
+    crate::accounts::{
+        PriceAccount,
+        PriceEma,
+        PriceInfo,
+    },
+};
+
+#[cfg(target_arch = "bpf")]
+#[link(name = "cpyth-bpf")]
+extern "C" {
+    pub fn c_upd_aggregate_pythnet(_input: *mut u8, clock_slot: u64, clock_timestamp: i64) -> bool;
+
+    pub fn c_upd_twap(_input: *mut u8, nslots: i64, decay: i64, max_diff: i64);
+
+    pub fn c_upd_emas(
+        twap: *mut u8,
+        twac: *mut u8,
+        price: i64,
+        conf: u64,
+        expo: i32,
+        nslots: i64,
+        decay: i64,
+        max_diff: i64,
+    );
+}
+
+#[cfg(not(target_arch = "bpf"))]
+#[link(name = "cpyth-native")]
+extern "C" {
+    pub fn c_upd_aggregate_pythnet(_input: *mut u8, clock_slot: u64, clock_timestamp: i64) -> bool;
+
+    pub fn c_upd_twap(_input: *mut u8, nslots: i64, decay: i64, max_diff: i64);
+
+    pub fn c_upd_emas(
+        twap: *mut u8,
+        twac: *mut u8,
+        price: i64,
+        conf: u64,
+        expo: i32,
+        nslots: i64,
+        decay: i64,
+        max_diff: i64,
+    );
+}
+
+pub fn upd_aggregate(price_account: &mut PriceAccount, slot: u64, timestamp: i64) -> bool {
+    // NOTE: the C code must use a raw pointer to price data. We already have the exclusive mut
+    // reference so we can simply cast before calling the function.
+    unsafe {
+        c_upd_aggregate_pythnet(
+            price_account as *mut PriceAccount as *mut u8,
+            slot,
+            timestamp,
+        )
+    }
+}
+
+pub fn upd_twap(price_account: &mut PriceAccount, nslots: i64, ema_params: EmaParams) {
+    // See comment on unsafe `c_upd_aggregate_pythnet` call above for details.
+    unsafe {
+        c_upd_twap(
+            price_account as *mut PriceAccount as *mut u8,
+            nslots,
+            ema_params.decay,
+            ema_params.max_diff,
+        );
+    }
+}
+
+pub fn upd_emas(
+    twap: &mut PriceEma,
+    twac: &mut PriceEma,
+    agg: &PriceInfo,
+    expo: i32,
+    nslots: i64,
+    ema_params: EmaParams,
+) {
+    // See comment on unsafe `c_upd_aggregate_pythnet` call above for details.
+    unsafe {
+        c_upd_emas(
+            twap as *mut PriceEma as *mut u8,
+            twac as *mut PriceEma as *mut u8,
+            agg.price_,
+            agg.conf_,
+            expo,
+            nslots,
+            ema_params.decay,
+            ema_params.max_diff,
+        );
+    }
+}
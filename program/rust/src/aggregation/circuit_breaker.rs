@@ -0,0 +1,66 @@
+//! Halting of the price accounts whose aggregate price moves too quickly, see `CircuitBreaker`.
+
+use crate::accounts::{
+    CircuitBreaker,
+    PriceAccount,
+};
+
+/// Update the state of `circuit_breaker` after the aggregation of `slot`, which succeeded if
+/// `updated`, and return whether the aggregate may keep `PC_STATUS_TRADING`. The previous
+/// aggregate of `price_account` is the last one with `PC_STATUS_TRADING`, so it doesn't move
+/// while the breaker is tripped.
+pub fn check_circuit_breaker(
+    price_account: &PriceAccount,
+    slot: u64,
+    updated: bool,
+    circuit_breaker: &mut CircuitBreaker,
+) -> bool {
+    if !updated {
+        // Only consecutive successful aggregates confirm a new level
+        circuit_breaker.num_confirmations = 0;
+        return false;
+    }
+
+    let price = price_account.agg_.price_;
+    let moved = |reference: i64| has_moved(reference, price, circuit_breaker.max_move_bps);
+    if circuit_breaker.is_tripped() {
+        if !moved(price_account.prev_price_) {
+            reset(circuit_breaker);
+            return true;
+        }
+        if moved(circuit_breaker.pending_price) {
+            circuit_breaker.pending_price = price;
+            circuit_breaker.num_confirmations = 0;
+            return false;
+        }
+        circuit_breaker.num_confirmations += 1;
+        if circuit_breaker.num_confirmations >= circuit_breaker.required_confirmations {
+            reset(circuit_breaker);
+            return true;
+        }
+        false
+    } else {
+        // The confidence of an aggregate is never 0, so it is 0 if there is no previous one
+        let has_reference = price_account.prev_conf_ != 0
+            && slot.saturating_sub(price_account.prev_slot_) <= circuit_breaker.max_slots;
+        if has_reference && moved(price_account.prev_price_) {
+            circuit_breaker.tripped = 1;
+            circuit_breaker.pending_price = price;
+            circuit_breaker.num_confirmations = 0;
+            return false;
+        }
+        true
+    }
+}
+
+fn reset(circuit_breaker: &mut CircuitBreaker) {
+    circuit_breaker.tripped = 0;
+    circuit_breaker.pending_price = 0;
+    circuit_breaker.num_confirmations = 0;
+}
+
+/// Whether `price` is more than `max_move_bps` basis points of `reference` away from it
+fn has_moved(reference: i64, price: i64, max_move_bps: u32) -> bool {
+    let distance = (i128::from(price) - i128::from(reference)).unsigned_abs();
+    distance * 10_000 > u128::from(max_move_bps) * u128::from(reference.unsigned_abs())
+}
@@ -0,0 +1,168 @@
+//! Per-component diagnostics of the aggregation, see `AggregationDiagnostics` and
+//! `PublisherStats`.
+
+use crate::{
+    accounts::{
+        AggregationDiagnostics,
+        ExclusionReason,
+        PriceAccount,
+        PriceBand,
+        PriceInfo,
+        PublisherStats,
+        PUBLISHER_DEVIATION_SCALE,
+    },
+    c_oracle_header::{
+        PC_MAX_SEND_LATENCY,
+        PC_NUM_COMP,
+        PC_STATUS_IGNORED,
+        PC_STATUS_TRADING,
+    },
+};
+
+/// Maximum number of slots between the publication of a quote and the aggregation that includes
+/// it
+pub fn max_latency(price_account: &PriceAccount) -> i64 {
+    if price_account.max_latency_ == 0 {
+        i64::from(PC_MAX_SEND_LATENCY)
+    } else {
+        i64::from(price_account.max_latency_)
+    }
+}
+
+/// Why `quote`, with a weight of `weight`, is left out of the aggregation of `slot`, or `None`
+/// if it is a valid quote. These are the same checks as in `upd_aggregate`.
+pub fn check_quote(
+    quote: &PriceInfo,
+    slot: u64,
+    max_latency: i64,
+    weight: u64,
+) -> Option<ExclusionReason> {
+    let slot_diff = (slot as i64).wrapping_sub(quote.pub_slot_ as i64);
+    let conf = quote.conf_ as i64;
+    if quote.status_ == PC_STATUS_IGNORED {
+        Some(ExclusionReason::ConfidenceRatio)
+    } else if quote.status_ != PC_STATUS_TRADING {
+        Some(ExclusionReason::Status)
+    } else if !(0 < conf && (i64::MIN + conf..=i64::MAX - conf).contains(&quote.price_)) {
+        Some(ExclusionReason::InvalidConfidence)
+    } else if slot_diff > max_latency {
+        Some(ExclusionReason::Stale)
+    } else if weight == 0 {
+        Some(ExclusionReason::ZeroWeight)
+    } else {
+        None
+    }
+}
+
+/// Record in `diagnostics` why the quotes of the components of `price_account` were left out of
+/// the aggregation of `slot` by `upd_aggregate`, which copied them to the `agg_` of the
+/// components.
+pub fn record_exclusion_reasons(
+    price_account: &PriceAccount,
+    slot: u64,
+    diagnostics: &mut AggregationDiagnostics,
+) {
+    let max_latency = max_latency(price_account);
+    let num_comps = (price_account.num_ as usize).min(PC_NUM_COMP as usize);
+    diagnostics.slot = slot;
+    diagnostics.reasons.fill(0);
+    for (reason, comp) in diagnostics
+        .reasons
+        .iter_mut()
+        .zip(price_account.comp_[..num_comps].iter())
+    {
+        *reason = check_quote(&comp.agg_, slot, max_latency, 1).map_or(0, |reason| reason as u8);
+    }
+}
+
+/// Record as outliers in `diagnostics` the valid quotes of the components of `price_account`
+/// whose price isn't among the `(price - conf, price, price + conf)` triples of `kept_points`,
+/// the quotes left by `reject_outliers`. Since whether a quote is rejected only depends on its
+/// price, quotes with the same price are either all kept or all rejected.
+pub fn record_outliers(
+    price_account: &PriceAccount,
+    kept_points: &[(i64, u64)],
+    diagnostics: &mut AggregationDiagnostics,
+) {
+    let num_comps = (price_account.num_ as usize).min(PC_NUM_COMP as usize);
+    for (reason, comp) in diagnostics
+        .reasons
+        .iter_mut()
+        .zip(price_account.comp_[..num_comps].iter())
+    {
+        if *reason == 0
+            && !kept_points
+                .iter()
+                .skip(1)
+                .step_by(3)
+                .any(|(price, _)| *price == comp.agg_.price_)
+        {
+            *reason = ExclusionReason::Outlier as u8;
+        }
+    }
+}
+
+/// Record as blocked in `diagnostics` the quotes of the components in `blocked`, whose bit `i` is
+/// set if the publisher of `comp_[i]` is in the `PublisherBlocklistAccount`. Their quotes were
+/// given `PC_STATUS_IGNORED` for the aggregation, so they are otherwise recorded as
+/// `ExclusionReason::ConfidenceRatio`.
+pub fn record_blocked(blocked: u64, diagnostics: &mut AggregationDiagnostics) {
+    for (i, reason) in diagnostics.reasons.iter_mut().enumerate() {
+        if blocked & (1 << i) != 0 {
+            *reason = ExclusionReason::Blocked as u8;
+        }
+    }
+}
+
+/// Record as outside of `price_band` in `diagnostics` the quotes of the components of
+/// `price_account` recorded as `ExclusionReason::ConfidenceRatio` whose price is outside of the
+/// band: `upd_price` gives both kinds of quotes `PC_STATUS_IGNORED`.
+pub fn record_outside_price_band(
+    price_account: &PriceAccount,
+    price_band: &PriceBand,
+    diagnostics: &mut AggregationDiagnostics,
+) {
+    if !price_band.is_enabled() {
+        return;
+    }
+    let num_comps = (price_account.num_ as usize).min(PC_NUM_COMP as usize);
+    for (reason, comp) in diagnostics
+        .reasons
+        .iter_mut()
+        .zip(price_account.comp_[..num_comps].iter())
+    {
+        if *reason == ExclusionReason::ConfidenceRatio as u8
+            && !price_band.contains(comp.agg_.price_)
+        {
+            *reason = ExclusionReason::OutsidePriceBand as u8;
+        }
+    }
+}
+
+/// Add the valid quotes of the components of `price_account` to their `publisher_stats`, after
+/// the successful aggregation of `slot`.
+pub fn update_publisher_stats(
+    price_account: &PriceAccount,
+    slot: u64,
+    publisher_stats: &mut PublisherStats,
+) {
+    let max_latency = max_latency(price_account);
+    let num_comps = (price_account.num_ as usize).min(PC_NUM_COMP as usize);
+    let comps = &price_account.comp_[..num_comps];
+    for (i, comp) in comps.iter().enumerate() {
+        let stat = publisher_stats.entry_mut(comps, i);
+        if check_quote(&comp.agg_, slot, max_latency, 1).is_none() {
+            let distance = (i128::from(comp.agg_.price_) - i128::from(price_account.agg_.price_))
+                .unsigned_abs();
+            let conf = u128::from(comp.agg_.conf_);
+            stat.num_aggregations += 1;
+            if distance > conf {
+                stat.num_misses += 1;
+            }
+            let deviation = distance * u128::from(PUBLISHER_DEVIATION_SCALE) / conf;
+            stat.sum_deviation = stat
+                .sum_deviation
+                .saturating_add(u64::try_from(deviation).unwrap_or(u64::MAX));
+        }
+    }
+}
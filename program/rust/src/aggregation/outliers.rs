@@ -0,0 +1,97 @@
+//! Rejection of the outlier quotes before the aggregation model runs, see `OutlierFilter`.
+
+use crate::{
+    accounts::{
+        OutlierFilter,
+        OutlierFilterMode,
+    },
+    c_oracle_header::PC_NUM_COMP,
+};
+
+/// Remove the outlier quotes from `points`, which holds the `(price - conf, price, price + conf)`
+/// triples of the valid quotes (with their weights), keeping the remaining triples in order at
+/// the start of `points`. `prev_aggregate` is the price and confidence of the previous aggregate,
+/// if any. Returns the number of rejected quotes.
+// This is kept out of the aggregation function so that its scratch space doesn't share its
+// stack frame.
+#[inline(never)]
+pub fn reject_outliers(
+    points: &mut [(i64, u64)],
+    outlier_filter: &OutlierFilter,
+    prev_aggregate: Option<(i64, u64)>,
+) -> usize {
+    let num_quotes = points.len() / 3;
+    if num_quotes == 0 {
+        return 0;
+    }
+    let (reference, scale) = match outlier_filter.mode() {
+        Some(OutlierFilterMode::PrevAggregate) => match prev_aggregate {
+            Some(prev_aggregate) => prev_aggregate,
+            None => return 0,
+        },
+        Some(OutlierFilterMode::Median) => median_and_mad(points),
+        None => return 0,
+    };
+    let max_distance = u128::from(outlier_filter.k) * u128::from(scale);
+    let is_outlier = |price: i64| distance(price, reference) > max_distance;
+
+    let num_rejected = points
+        .iter()
+        .skip(1)
+        .step_by(3)
+        .filter(|(price, _)| is_outlier(*price))
+        .count();
+    // Don't reject a majority of the quotes, the reference is likely off
+    if num_rejected == 0 || 2 * (num_quotes - num_rejected) < num_quotes {
+        return 0;
+    }
+
+    let mut num_kept = 0;
+    for i in 0..num_quotes {
+        if !is_outlier(points[3 * i + 1].0) {
+            points.copy_within(3 * i..3 * i + 3, 3 * num_kept);
+            num_kept += 1;
+        }
+    }
+    num_rejected
+}
+
+/// The median of the prices of the quotes in `points` and their median absolute deviation, which
+/// is at least 1
+fn median_and_mad(points: &[(i64, u64)]) -> (i64, u64) {
+    let mut prices = [0i64; PC_NUM_COMP as usize];
+    let prices = &mut prices[..points.len() / 3];
+    for (price, (_, point)) in prices.iter_mut().zip(points.iter().skip(1).step_by(3)) {
+        *price = *point;
+    }
+    prices.sort_unstable();
+    let median = median_of_sorted(prices, |x, y| ((i128::from(x) + i128::from(y)) >> 1) as i64);
+
+    let mut deviations = [0u64; PC_NUM_COMP as usize];
+    let deviations = &mut deviations[..prices.len()];
+    for (deviation, price) in deviations.iter_mut().zip(prices.iter()) {
+        *deviation = distance(*price, median) as u64;
+    }
+    deviations.sort_unstable();
+    let mad = median_of_sorted(deviations, |x, y| {
+        ((u128::from(x) + u128::from(y)) >> 1) as u64
+    });
+
+    // A zero deviation would reject the quotes a single tick away from a majority
+    (median, mad.max(1))
+}
+
+/// The median of the non-empty sorted slice `values`, rounding down the average of the two
+/// middle values with `avg_2` if there is an even number of them
+fn median_of_sorted<T: Copy>(values: &[T], avg_2: impl Fn(T, T) -> T) -> T {
+    let cnt = values.len();
+    if cnt & 1 == 1 {
+        values[cnt >> 1]
+    } else {
+        avg_2(values[(cnt >> 1) - 1], values[cnt >> 1])
+    }
+}
+
+fn distance(x: i64, y: i64) -> u128 {
+    (i128::from(x) - i128::from(y)).unsigned_abs()
+}
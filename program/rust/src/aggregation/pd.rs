@@ -0,0 +1,215 @@
+//! Rust port of the decimal arithmetic in `pd.h`, used by the EMA computation.
+//!
+//! The C code relies on two's complement wrapping for its intermediate products, so the
+//! arithmetic below uses explicit `wrapping_*` operations to stay bit-for-bit identical with
+//! the C implementation without panicking in debug builds.
+
+pub const PD_SCALE9: i64 = 1_000_000_000;
+/// Exponent of the decay of the EMAs, see `EmaParams`
+pub const PD_EMA_EXPO: i32 = -9;
+
+const EXP_BITS: u32 = 5;
+const EXP_MASK: i64 = (1 << EXP_BITS) - 1;
+
+/// Powers of 10 used in decimal arithmetic scaling. `FACT[i] == 10^i`.
+const FACT: [i64; 18] = [
+    1,
+    10,
+    100,
+    1_000,
+    10_000,
+    100_000,
+    1_000_000,
+    10_000_000,
+    100_000_000,
+    1_000_000_000,
+    10_000_000_000,
+    100_000_000_000,
+    1_000_000_000_000,
+    10_000_000_000_000,
+    100_000_000_000_000,
+    1_000_000_000_000_000,
+    10_000_000_000_000_000,
+    100_000_000_000_000_000,
+];
+const PC_FACTOR_SIZE: i32 = FACT.len() as i32;
+
+/// Decimal number `v * 10^e`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Pd {
+    pub e: i32,
+    pub v: i64,
+}
+
+impl Pd {
+    pub fn new(v: i64, e: i32) -> Self {
+        Pd { e, v }
+    }
+
+    /// Same as `new`, but normalizes the mantissa so that it fits in 28 bits.
+    pub fn new_scale(v: i64, e: i32) -> Self {
+        let mut n = Pd::new(v, e);
+        n.scale();
+        n
+    }
+
+    fn scale(&mut self) {
+        let neg = self.v < 0;
+        let mut v = if neg { self.v.wrapping_neg() } else { self.v };
+        while v >= (1 << 28) {
+            v /= 10;
+            self.e += 1;
+        }
+        self.v = if neg { v.wrapping_neg() } else { v };
+    }
+
+    /// Pack the number into an `i64` with the exponent in the lowest `EXP_BITS` bits.
+    /// Returns `None` if the number can't be represented.
+    pub fn store(&self) -> Option<i64> {
+        let mut v = self.v;
+        let mut e = self.e;
+        while v < -(1 << 58) {
+            v /= 10;
+            e += 1;
+        }
+        while v > (1 << 58) - 1 {
+            v /= 10;
+            e += 1;
+        }
+        while e < -(1 << (EXP_BITS - 1)) {
+            v /= 10;
+            e += 1;
+        }
+        while e > (1 << (EXP_BITS - 1)) - 1 {
+            v *= 10;
+            if !(-(1 << 58)..(1 << 58)).contains(&v) {
+                return None;
+            }
+            e -= 1;
+        }
+        Some((v << EXP_BITS) | (i64::from(e) & EXP_MASK))
+    }
+
+    /// Inverse of `store`
+    pub fn load(n: i64) -> Self {
+        Pd::new_scale(n >> EXP_BITS, (((n & EXP_MASK) << 59) >> 59) as i32)
+    }
+
+    /// Rescale the number to exponent `e`, truncating digits if needed.
+    pub fn adjust(&mut self, e: i32) {
+        let d = self.e - e;
+        // The C code indexes a table of `PC_FACTOR_SIZE` powers of ten here, which is never
+        // exceeded for exponents in the permitted range. Outside of that range we compute the
+        // power directly rather than reading out of bounds.
+        if d > 0 {
+            self.v = self.v.wrapping_mul(10i64.wrapping_pow(d as u32));
+        } else if d < 0 {
+            self.v = 10i64
+                .checked_pow(d.unsigned_abs())
+                .map_or(0, |factor| self.v / factor);
+        }
+        self.e = e;
+    }
+
+    pub fn mul(&self, other: &Pd) -> Pd {
+        Pd::new_scale(self.v.wrapping_mul(other.v), self.e + other.e)
+    }
+
+    pub fn div(&self, other: &Pd) -> Pd {
+        if self.v == 0 {
+            return *self;
+        }
+        let neg1 = self.v < 0;
+        let neg2 = other.v < 0;
+        let mut v1 = if neg1 { self.v.wrapping_neg() } else { self.v };
+        let v2 = if neg2 {
+            other.v.wrapping_neg()
+        } else {
+            other.v
+        };
+        let mut m = 0;
+        while (v1 as u64) & 0xffff_ffff_f000_0000 == 0 {
+            v1 = v1.wrapping_mul(10);
+            m += 1;
+        }
+        // The C code traps on a division by zero, we return zero instead.
+        let mut v = v1.wrapping_mul(PD_SCALE9).checked_div(v2).unwrap_or(0);
+        if neg1 {
+            v = v.wrapping_neg();
+        }
+        if neg2 {
+            v = v.wrapping_neg();
+        }
+        Pd::new_scale(v, self.e - other.e - m - 9)
+    }
+
+    pub fn add(&self, other: &Pd) -> Pd {
+        let d = self.e - other.e;
+        let r = if d == 0 {
+            Pd::new(self.v.wrapping_add(other.v), self.e)
+        } else if d > 0 {
+            if d < 9 {
+                Pd::new(
+                    self.v.wrapping_mul(FACT[d as usize]).wrapping_add(other.v),
+                    other.e,
+                )
+            } else if d < PC_FACTOR_SIZE + 9 {
+                Pd::new(
+                    self.v
+                        .wrapping_mul(PD_SCALE9)
+                        .wrapping_add(other.v / FACT[(d - 9) as usize]),
+                    self.e - 9,
+                )
+            } else {
+                *self
+            }
+        } else {
+            let d = -d;
+            if d < 9 {
+                Pd::new(
+                    self.v.wrapping_add(other.v.wrapping_mul(FACT[d as usize])),
+                    self.e,
+                )
+            } else if d < PC_FACTOR_SIZE + 9 {
+                Pd::new(
+                    (self.v / FACT[(d - 9) as usize]).wrapping_add(other.v.wrapping_mul(PD_SCALE9)),
+                    other.e - 9,
+                )
+            } else {
+                *other
+            }
+        };
+        Pd::new_scale(r.v, r.e)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        quickcheck_macros::quickcheck,
+    };
+
+    #[quickcheck]
+    fn test_store_load_roundtrip(v: i32, e: i8) {
+        let e = i32::from(e % 15);
+        let n = Pd::new_scale(i64::from(v), e);
+        assert_eq!(Pd::load(n.store().unwrap()), n);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let one = Pd::new(100_000_000, -8);
+        let two = one.add(&one);
+        assert_eq!(two, Pd::new(200_000_000, -8));
+        assert_eq!(two.mul(&two), Pd::new(40_000_000, -7));
+        assert_eq!(one.div(&two), Pd::new(50_000_000, -8));
+
+        let mut x = Pd::new_scale(12_345_678_901, -3);
+        assert_eq!(x, Pd::new(123_456_789, -1));
+        x.adjust(-3);
+        assert_eq!(x, Pd::new(12_345_678_900, -3));
+        x.adjust(2);
+        assert_eq!(x, Pd::new(123_456, 2));
+    }
+}
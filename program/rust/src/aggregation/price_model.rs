@@ -0,0 +1,65 @@
+/// Rust port of `price_model_core` (see `model/price_model.c` for the rationale behind the
+/// choice of the rank statistics below).
+///
+/// Sorts `quotes` in ascending order and returns the loss model minimizing p25, p50 and p75.
+/// `quotes` must not be empty.
+pub fn price_model_core(quotes: &mut [i64]) -> (i64, i64, i64) {
+    quotes.sort_unstable();
+
+    let cnt = quotes.len();
+    let p25_idx = cnt >> 2;
+    let p25 = quotes[p25_idx];
+
+    let p50 = if cnt & 1 == 1 {
+        quotes[cnt >> 1]
+    } else {
+        avg_2_int64(quotes[(cnt >> 1) - 1], quotes[cnt >> 1])
+    };
+
+    // The p75 is the mirror image of the p25
+    let p75 = quotes[cnt - 1 - p25_idx];
+
+    (p25, p50, p75)
+}
+
+/// Computes `floor((x + y) / 2)` without intermediate overflow.
+fn avg_2_int64(x: i64, y: i64) -> i64 {
+    ((i128::from(x) + i128::from(y)) >> 1) as i64
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        quickcheck_macros::quickcheck,
+    };
+
+    #[quickcheck]
+    fn test_price_model_core(quotes: Vec<i64>) {
+        if quotes.is_empty() {
+            return;
+        }
+        let mut sorted = quotes.clone();
+        sorted.sort();
+        let mut quotes = quotes;
+        let (p25, p50, p75) = price_model_core(&mut quotes);
+        assert_eq!(quotes, sorted);
+
+        let cnt = sorted.len();
+        let is_even = 1 - (cnt & 1);
+        assert_eq!(p25, sorted[cnt >> 2]);
+        assert_eq!(
+            p50,
+            avg_2_int64(sorted[(cnt >> 1) - is_even], sorted[cnt >> 1])
+        );
+        assert_eq!(p75, sorted[cnt - 1 - (cnt >> 2)]);
+    }
+
+    #[test]
+    fn test_avg_2_int64() {
+        assert_eq!(avg_2_int64(i64::MAX, i64::MAX), i64::MAX);
+        assert_eq!(avg_2_int64(i64::MIN, i64::MIN), i64::MIN);
+        assert_eq!(avg_2_int64(-1, 0), -1);
+        assert_eq!(avg_2_int64(3, 4), 3);
+    }
+}
@@ -0,0 +1,161 @@
+//! Rust port of `upd_aggregate`, `upd_twap` and `upd_emas` from `upd_aggregate.h`. This implementation is
+//! kept bit-for-bit identical with the C one, see `tests/test_rust_aggregation.rs`.
+
+use {
+    super::{
+        pd::{
+            Pd,
+            PD_EMA_EXPO,
+        },
+        price_model::price_model_core,
+        EmaParams,
+    },
+    crate::{
+        accounts::{
+            PriceAccount,
+            PriceEma,
+            PriceInfo,
+        },
+        c_oracle_header::{
+            PC_MAX_SEND_LATENCY,
+            PC_NUM_COMP,
+            PC_STATUS_TRADING,
+            PC_STATUS_UNKNOWN,
+        },
+    },
+    std::cmp::max,
+};
+
+/// Update the aggregate price of `price_account` from its components' latest quotes.
+/// Returns `true` if the new aggregate has `PC_STATUS_TRADING`.
+pub fn upd_aggregate(price_account: &mut PriceAccount, slot: u64, timestamp: i64) -> bool {
+    // Update the value of the previous price, if it had TRADING status.
+    if price_account.agg_.status_ == PC_STATUS_TRADING {
+        price_account.prev_slot_ = price_account.agg_.pub_slot_;
+        price_account.prev_price_ = price_account.agg_.price_;
+        price_account.prev_conf_ = price_account.agg_.conf_;
+        price_account.prev_timestamp_ = price_account.timestamp_;
+    }
+
+    // update aggregate details ready for next slot
+    price_account.valid_slot_ = price_account.agg_.pub_slot_; // valid slot-time of agg. price
+    price_account.agg_.pub_slot_ = slot; // publish slot-time of agg. price
+    price_account.timestamp_ = timestamp;
+
+    let max_latency = if price_account.max_latency_ == 0 {
+        i64::from(PC_MAX_SEND_LATENCY)
+    } else {
+        i64::from(price_account.max_latency_)
+    };
+
+    // identify valid quotes
+    let mut numv: u32 = 0;
+    let mut nprcs: usize = 0;
+    let mut prcs = [0i64; PC_NUM_COMP as usize * 3];
+    let num_comps = (price_account.num_ as usize).min(PC_NUM_COMP as usize);
+    for comp in price_account.comp_[..num_comps].iter_mut() {
+        // copy contributing price to aggregate snapshot
+        comp.agg_ = comp.latest_;
+        // add quote to the quote set if it is valid
+        let slot_diff = (slot as i64).wrapping_sub(comp.agg_.pub_slot_ as i64);
+        let price = comp.agg_.price_;
+        let conf = comp.agg_.conf_ as i64;
+        if comp.agg_.status_ == PC_STATUS_TRADING
+            // These checks ensure that price - conf and price + conf do not overflow.
+            && 0 < conf
+            && (i64::MIN + conf..=i64::MAX - conf).contains(&price)
+            && slot_diff <= max_latency
+        {
+            numv += 1;
+            prcs[nprcs] = price - conf;
+            prcs[nprcs + 1] = price;
+            prcs[nprcs + 2] = price + conf;
+            nprcs += 3;
+        }
+    }
+
+    // too few valid quotes
+    price_account.num_qt_ = numv;
+    if numv == 0 || numv < u32::from(price_account.min_pub_) {
+        price_account.agg_.status_ = PC_STATUS_UNKNOWN;
+        return false;
+    }
+
+    // evaluate the model to get the p25/p50/p75 prices
+    let (agg_p25, agg_price, agg_p75) = price_model_core(&mut prcs[..nprcs]);
+
+    // use the larger of the left and right confidences
+    let agg_conf_left = agg_price.wrapping_sub(agg_p25);
+    let agg_conf_right = agg_p75.wrapping_sub(agg_price);
+    let agg_conf = max(agg_conf_left, agg_conf_right);
+
+    // if the confidences end up at zero, we abort
+    if agg_conf <= 0 {
+        price_account.agg_.status_ = PC_STATUS_UNKNOWN;
+        return false;
+    }
+
+    // update status and publish slot of last trading status price
+    price_account.agg_.status_ = PC_STATUS_TRADING;
+    price_account.last_slot_ = slot;
+    price_account.agg_.price_ = agg_price;
+    price_account.agg_.conf_ = agg_conf as u64;
+
+    true
+}
+
+/// Update the EMAs of the aggregate price and confidence, `nslots` being the number of slots
+/// since the previous successful aggregation.
+pub fn upd_twap(price_account: &mut PriceAccount, nslots: i64, ema_params: EmaParams) {
+    upd_emas(
+        &mut price_account.twap_,
+        &mut price_account.twac_,
+        &price_account.agg_,
+        price_account.exponent,
+        nslots,
+        ema_params,
+    );
+}
+
+/// Update a pair of EMAs of the aggregate price and confidence `agg`, like `upd_twap` does with
+/// the EMAs of the price account.
+pub fn upd_emas(
+    twap: &mut PriceEma,
+    twac: &mut PriceEma,
+    agg: &PriceInfo,
+    expo: i32,
+    nslots: i64,
+    ema_params: EmaParams,
+) {
+    let px = Pd::new_scale(agg.price_, expo);
+    let conf = Pd::new_scale(agg.conf_ as i64, expo);
+    upd_ema(twap, px, conf, nslots, ema_params, expo);
+    upd_ema(twac, conf, conf, nslots, ema_params, expo);
+}
+
+fn upd_ema(ema: &mut PriceEma, val: Pd, conf: Pd, nslot: i64, params: EmaParams, expo: i32) {
+    let one = Pd::new(100_000_000, -8);
+    let cwgt = if conf.v != 0 { one.div(&conf) } else { one };
+
+    let (mut val, numer, denom) = if nslot > params.max_diff {
+        // initial condition
+        (val, val.mul(&cwgt), cwgt)
+    } else {
+        // compute decay factor
+        let diff = Pd::new(nslot, 0);
+        let decay = Pd::new(params.decay, PD_EMA_EXPO).mul(&diff).add(&one);
+
+        // compute numer/denom and new value from decay factor
+        let numer = Pd::load(ema.numer_).mul(&decay).add(&val.mul(&cwgt));
+        let denom = Pd::load(ema.denom_).mul(&decay).add(&cwgt);
+        (numer.div(&denom), numer, denom)
+    };
+
+    // adjust and store results
+    val.adjust(expo);
+    ema.val_ = val.v;
+    if let (Some(numer), Some(denom)) = (numer.store(), denom.store()) {
+        ema.numer_ = numer;
+        ema.denom_ = denom;
+    }
+}
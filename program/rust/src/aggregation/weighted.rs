@@ -0,0 +1,237 @@
+//! Weighted aggregation with optional outlier rejection, used instead of `upd_aggregate` for the
+//! price accounts with `PriceAccountFlags::STAKE_WEIGHTED` or an enabled `OutlierFilter`. Unlike
+//! `upd_aggregate`, it only has a Rust implementation.
+
+use {
+    super::{
+        diagnostics::{
+            check_quote,
+            max_latency,
+            record_outliers,
+        },
+        outliers::reject_outliers,
+    },
+    crate::{
+        accounts::{
+            AggregateConfidence,
+            AggregationDiagnostics,
+            OutlierFilter,
+            PriceAccount,
+        },
+        c_oracle_header::{
+            PC_NUM_COMP,
+            PC_STATUS_TRADING,
+            PC_STATUS_UNKNOWN,
+        },
+    },
+    solana_program::pubkey::Pubkey,
+    std::cmp::max,
+};
+
+/// Same as `upd_aggregate`, except that the quotes of each publisher are weighted by
+/// `weight(publisher)` and that the outliers are rejected by `outlier_filter`, if any, which
+/// records their number. The quotes with a weight of 0 and the rejected quotes are ignored and
+/// don't count towards `min_pub_`. With equal weights and no rejected quotes, this computes the
+/// same aggregate as `upd_aggregate`. The reasons why quotes were left out are recorded in
+/// `diagnostics` and both sides of the confidence interval of a successful aggregate in
+/// `aggregate_confidence`, if any.
+pub fn upd_aggregate_weighted(
+    price_account: &mut PriceAccount,
+    slot: u64,
+    timestamp: i64,
+    weight: impl Fn(&Pubkey) -> u64,
+    outlier_filter: Option<&mut OutlierFilter>,
+    mut diagnostics: Option<&mut AggregationDiagnostics>,
+    aggregate_confidence: Option<&mut AggregateConfidence>,
+) -> bool {
+    // Update the value of the previous price, if it had TRADING status.
+    if price_account.agg_.status_ == PC_STATUS_TRADING {
+        price_account.prev_slot_ = price_account.agg_.pub_slot_;
+        price_account.prev_price_ = price_account.agg_.price_;
+        price_account.prev_conf_ = price_account.agg_.conf_;
+        price_account.prev_timestamp_ = price_account.timestamp_;
+    }
+
+    // update aggregate details ready for next slot
+    price_account.valid_slot_ = price_account.agg_.pub_slot_;
+    price_account.agg_.pub_slot_ = slot;
+    price_account.timestamp_ = timestamp;
+
+    let max_latency = max_latency(price_account);
+    if let Some(diagnostics) = diagnostics.as_deref_mut() {
+        diagnostics.slot = slot;
+        diagnostics.reasons.fill(0);
+    }
+
+    // identify valid quotes with a non-zero weight
+    let mut numv: u32 = 0;
+    let mut nprcs: usize = 0;
+    let mut prcs = [(0i64, 0u64); PC_NUM_COMP as usize * 3];
+    let num_comps = (price_account.num_ as usize).min(PC_NUM_COMP as usize);
+    for (i, comp) in price_account.comp_[..num_comps].iter_mut().enumerate() {
+        comp.agg_ = comp.latest_;
+        let weight = weight(&comp.pub_);
+        let reason = check_quote(&comp.agg_, slot, max_latency, weight);
+        if let Some(diagnostics) = diagnostics.as_deref_mut() {
+            diagnostics.reasons[i] = reason.map_or(0, |reason| reason as u8);
+        }
+        if reason.is_none() {
+            let price = comp.agg_.price_;
+            let conf = comp.agg_.conf_ as i64;
+            numv += 1;
+            prcs[nprcs] = (price - conf, weight);
+            prcs[nprcs + 1] = (price, weight);
+            prcs[nprcs + 2] = (price + conf, weight);
+            nprcs += 3;
+        }
+    }
+
+    if let Some(outlier_filter) = outlier_filter {
+        // The previous aggregate was just copied to prev_*, its confidence is 0 if there is none
+        let prev_aggregate = (price_account.prev_conf_ != 0)
+            .then_some((price_account.prev_price_, price_account.prev_conf_));
+        let num_rejected = reject_outliers(&mut prcs[..nprcs], outlier_filter, prev_aggregate);
+        outlier_filter.num_rejected = num_rejected as u32;
+        numv -= num_rejected as u32;
+        nprcs -= 3 * num_rejected;
+        if let Some(diagnostics) = diagnostics.filter(|_| num_rejected != 0) {
+            record_outliers(price_account, &prcs[..nprcs], diagnostics);
+        }
+    }
+
+    // too few valid quotes
+    price_account.num_qt_ = numv;
+    if numv == 0 || numv < u32::from(price_account.min_pub_) {
+        price_account.agg_.status_ = PC_STATUS_UNKNOWN;
+        return false;
+    }
+
+    let (agg_p25, agg_price, agg_p75) = weighted_price_model_core(&mut prcs[..nprcs]);
+
+    // use the larger of the left and right confidences
+    let agg_conf_left = agg_price.wrapping_sub(agg_p25);
+    let agg_conf_right = agg_p75.wrapping_sub(agg_price);
+    let agg_conf = max(agg_conf_left, agg_conf_right);
+    if agg_conf <= 0 {
+        price_account.agg_.status_ = PC_STATUS_UNKNOWN;
+        return false;
+    }
+    if let Some(aggregate_confidence) = aggregate_confidence {
+        *aggregate_confidence = AggregateConfidence {
+            pub_slot:   slot,
+            conf_left:  agg_conf_left as u64,
+            conf_right: agg_conf_right as u64,
+        };
+    }
+
+    price_account.agg_.status_ = PC_STATUS_TRADING;
+    price_account.last_slot_ = slot;
+    price_account.agg_.price_ = agg_price;
+    price_account.agg_.conf_ = agg_conf as u64;
+
+    true
+}
+
+/// Weighted version of `price_model_core`: sorts the `(value, weight)` pairs of `quotes` by
+/// value and returns their weighted p25, p50 and p75. A quantile is the first value at which the
+/// cumulative weight exceeds that fraction of the total weight, and the p50 is the midpoint of
+/// the values on either side when the cumulative weight reaches exactly half of the total. With
+/// equal weights, this is the same as `price_model_core`.
+/// `quotes` must not be empty and its weights must not all be 0.
+pub fn weighted_price_model_core(quotes: &mut [(i64, u64)]) -> (i64, i64, i64) {
+    quotes.sort_unstable_by_key(|(value, _)| *value);
+
+    let total_weight: u128 = quotes.iter().map(|(_, weight)| u128::from(*weight)).sum();
+    let p25 = weighted_quantile(quotes.iter(), total_weight, 4, true);
+    let p50 = avg_2_int64(
+        weighted_quantile(quotes.iter(), total_weight, 2, false),
+        weighted_quantile(quotes.iter(), total_weight, 2, true),
+    );
+    // The p75 is the mirror image of the p25
+    let p75 = weighted_quantile(quotes.iter().rev(), total_weight, 4, true);
+
+    (p25, p50, p75)
+}
+
+/// The first value of `quotes` at which the cumulative weight exceeds `total_weight / divisor`,
+/// or reaches it if `strict` is false.
+fn weighted_quantile<'a>(
+    mut quotes: impl Iterator<Item = &'a (i64, u64)>,
+    total_weight: u128,
+    divisor: u128,
+    strict: bool,
+) -> i64 {
+    let mut cumulative_weight: u128 = 0;
+    quotes
+        .find(|(_, weight)| {
+            cumulative_weight += u128::from(*weight);
+            let scaled_weight = divisor * cumulative_weight;
+            scaled_weight > total_weight || (!strict && scaled_weight == total_weight)
+        })
+        .map_or(0, |(value, _)| *value)
+}
+
+/// Computes `floor((x + y) / 2)` without intermediate overflow.
+fn avg_2_int64(x: i64, y: i64) -> i64 {
+    ((i128::from(x) + i128::from(y)) >> 1) as i64
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        crate::aggregation::price_model::price_model_core,
+        quickcheck_macros::quickcheck,
+    };
+
+    #[quickcheck]
+    fn test_weighted_price_model_core_equal_weights(quotes: Vec<i64>, weight: u64) {
+        if quotes.is_empty() {
+            return;
+        }
+        let weight = weight.max(1);
+        let mut weighted_quotes: Vec<(i64, u64)> =
+            quotes.iter().map(|value| (*value, weight)).collect();
+        let mut quotes = quotes;
+        assert_eq!(
+            weighted_price_model_core(&mut weighted_quotes),
+            price_model_core(&mut quotes)
+        );
+    }
+
+    // Quotes with a weight of 0 don't change the result
+    #[quickcheck]
+    fn test_weighted_price_model_core_zero_weights(quotes: Vec<(i64, u8)>, ignored: Vec<i64>) {
+        let mut quotes: Vec<(i64, u64)> = quotes
+            .into_iter()
+            .map(|(value, weight)| (value, u64::from(weight)))
+            .collect();
+        if quotes.iter().all(|(_, weight)| *weight == 0) {
+            return;
+        }
+        let mut quotes_with_ignored = quotes.clone();
+        quotes_with_ignored.extend(ignored.into_iter().map(|value| (value, 0)));
+        assert_eq!(
+            weighted_price_model_core(&mut quotes_with_ignored),
+            weighted_price_model_core(&mut quotes)
+        );
+    }
+
+    #[test]
+    fn test_weighted_price_model_core() {
+        // The heaviest quote is the median
+        assert_eq!(
+            weighted_price_model_core(&mut [(3, 1), (1, 1), (2, 5)]),
+            (2, 2, 2)
+        );
+        // The p50 is the midpoint when the weight is split in two halves
+        assert_eq!(
+            weighted_price_model_core(&mut [(10, 3), (20, 1), (30, 2)]),
+            (10, 15, 30)
+        );
+        assert_eq!(
+            weighted_price_model_core(&mut [(i64::MAX, u64::MAX), (i64::MIN, u64::MAX)]),
+            (i64::MIN, -1, i64::MAX)
+        );
+    }
+}
@@ -9,9 +9,11 @@ use {
         utils::pyth_assert,
     },
     bytemuck::{
+        try_cast_slice,
         try_from_bytes,
         try_from_bytes_mut,
         Pod,
+        PodCastError,
     },
     solana_program::{
         account_info::AccountInfo,
@@ -48,6 +50,19 @@ pub fn load_mut<T: Pod>(data: &mut [u8]) -> Result<&mut T, OracleError> {
     .map_err(|_| OracleError::InstructionDataSliceMisaligned)
 }
 
+/// Interpret the bytes in `data` as a slice of values of type `T`
+/// This will fail if :
+/// - the length of `data` is not a multiple of the size of `T`
+/// - `data` is not aligned for T
+pub fn load_slice<T: Pod>(data: &[u8]) -> Result<&[T], OracleError> {
+    try_cast_slice(data).map_err(|err| match err {
+        PodCastError::TargetAlignmentGreaterAndInputNotAligned => {
+            OracleError::InstructionDataSliceMisaligned
+        }
+        _ => OracleError::InvalidInstructionDataLength,
+    })
+}
+
 /// Get the data stored in `account` as a value of type `T`.
 /// WARNING : Use `load_checked` to load initialized Pyth accounts
 pub fn load_account_as<'a, T: Pod>(account: &'a AccountInfo) -> Result<Ref<'a, T>, ProgramError> {
@@ -92,3 +107,58 @@ pub fn load_checked<'a, T: PythAccount>(
 
     load_account_as_mut::<T>(account)
 }
+
+/// Read the value of type `T` stored at offset `start` of the data of `account`, e.g. an
+/// extension appended after the account struct, or `None` if the account is too small to store
+/// it. The value doesn't need to be aligned.
+pub fn load_extension<T: Pod>(
+    account: &AccountInfo,
+    start: usize,
+) -> Result<Option<T>, ProgramError> {
+    Ok(read_extension(&account.try_borrow_data()?, start))
+}
+
+/// Same as `load_extension`, reading from the data of the account.
+pub fn read_extension<T: Pod>(data: &[u8], start: usize) -> Option<T> {
+    data.get(start..start + size_of::<T>())
+        .map(bytemuck::pod_read_unaligned)
+}
+
+/// Mutably borrow the value of type `T` stored at offset `start` of `data`, or `None` if `data`
+/// is too small to store it.
+pub fn extension_mut<T: Pod>(data: &mut [u8], start: usize) -> Option<&mut T> {
+    data.get_mut(start..start + size_of::<T>())
+        .map(bytemuck::from_bytes_mut)
+}
+
+/// Same as `load_checked`, also mutably borrowing the data of `account` following the account
+/// struct, where its extensions are stored.
+pub fn load_checked_with_extensions<'a, T: PythAccount>(
+    account: &'a AccountInfo,
+    version: u32,
+) -> Result<(RefMut<'a, T>, RefMut<'a, [u8]>), ProgramError> {
+    drop(load_checked::<T>(account, version)?);
+
+    let (account_data, extensions) = RefMut::map_split(account.try_borrow_mut_data()?, |data| {
+        data.split_at_mut(size_of::<T>())
+    });
+    Ok((
+        RefMut::map(account_data, bytemuck::from_bytes_mut),
+        extensions,
+    ))
+}
+
+/// Mutably borrow the value of type `T` stored at offset `start` of the data of `account`.
+/// Fails if the account is too small to store it.
+pub fn load_extension_mut<'a, T: Pod>(
+    account: &'a AccountInfo,
+    start: usize,
+) -> Result<RefMut<'a, T>, ProgramError> {
+    let end = start + size_of::<T>();
+    if account.data_len() < end {
+        return Err(ProgramError::AccountDataTooSmall);
+    }
+    Ok(RefMut::map(account.try_borrow_mut_data()?, |data| {
+        bytemuck::from_bytes_mut(&mut data[start..end])
+    }))
+}
@@ -56,6 +56,18 @@ pub enum OracleError {
     MaxLastFeedIndexReached        = 621,
     #[error("FeedIndexAlreadyInitialized")]
     FeedIndexAlreadyInitialized    = 622,
+    #[error("InvalidInstructionDataLength")]
+    InvalidInstructionDataLength   = 623,
+    #[error("InvalidPriceAccountFlags")]
+    InvalidPriceAccountFlags       = 624,
+    #[error("InvalidMultisigAuthority")]
+    InvalidMultisigAuthority       = 625,
+    #[error("NoPendingAuthorities")]
+    NoPendingAuthorities           = 626,
+    #[error("AuthorityTransferLocked")]
+    AuthorityTransferLocked        = 627,
+    #[error("AuthorityTransferRequired")]
+    AuthorityTransferRequired      = 628,
 }
 
 impl From<OracleError> for ProgramError {
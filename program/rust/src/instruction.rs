@@ -1,5 +1,10 @@
 use {
     crate::{
+        accounts::{
+            MAX_MULTISIG_SIGNERS,
+            MAX_PUBLISHER_MANAGER_PRODUCTS,
+            NUM_EMA_HORIZONS,
+        },
         c_oracle_header::PC_VERSION,
         deserialize::load,
         error::OracleError,
@@ -16,108 +21,276 @@ use {
     solana_program::pubkey::Pubkey,
 };
 
+#[cfg(any(test, feature = "library"))]
+pub mod builders;
+#[cfg(any(test, feature = "library"))]
+mod decoder;
+
+#[cfg(any(test, feature = "library"))]
+pub use decoder::{
+    decode_instruction,
+    DecodeError,
+    MessageBufferAccounts,
+    OracleInstruction,
+    UpdPriceAccounts,
+};
+
 /// WARNING : NEW COMMANDS SHOULD BE ADDED AT THE END OF THE LIST
 #[repr(i32)]
-#[derive(PartialEq, Eq, FromPrimitive, ToPrimitive)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, FromPrimitive, ToPrimitive)]
 pub enum OracleCommand {
     /// Initialize first mapping list account
     // account[0] funding account       [signer writable]
     // account[1] mapping account       [signer writable]
-    InitMapping           = 0,
+    InitMapping                = 0,
     /// Initialize and add new mapping account
     // account[0] funding account       [signer writable]
     // account[1] tail mapping account  [signer writable]
     // account[2] new mapping account   [signer writable]
-    AddMapping            = 1,
+    AddMapping                 = 1,
     /// Initialize and add new product reference data account
     // account[0] funding account       [signer writable]
     // account[1] mapping account       [signer writable]
     // account[2] new product account   [signer writable]
-    AddProduct            = 2,
+    AddProduct                 = 2,
     /// Update product account
     // account[0] funding account       [signer writable]
     // account[1] product account       [signer writable]
-    UpdProduct            = 3,
+    UpdProduct                 = 3,
     /// Add new price account to a product account
     // account[0] funding account        [signer writable]
     // account[1] product account        [writable]
     // account[2] new price account      [writable]
     // account[3] permissions account    [writable]
-    AddPrice              = 4,
+    AddPrice                   = 4,
     /// Add publisher to symbol account
     // account[0] funding account       [signer writable]
     // account[1] price account         [signer writable]
-    AddPublisher          = 5,
+    AddPublisher               = 5,
     /// Delete publisher from symbol account
     // account[0] funding account       [signer writable]
     // account[1] price account         [signer writable]
-    DelPublisher          = 6,
+    DelPublisher               = 6,
     /// Publish component price
     // account[0] funding account       [signer writable]
     // account[1] price account         [writable]
     // account[2] sysvar_clock account  []
-    UpdPrice              = 7,
+    // account[3..7] message buffer accounts, optional, see `upd_price`
+    // optionally followed by the publisher blocklist account [] and the product account []
+    UpdPrice                   = 7,
     /// Compute aggregate price
-    // account[0] funding account       [signer writable]
-    // account[1] price account         [writable]
-    // account[2] sysvar_clock account  []
-    AggPrice              = 8,
+    // same accounts as `UpdPrice`
+    AggPrice                   = 8,
     /// (Re)initialize price account
     // account[0] funding account       [signer writable]
     // account[1] new price account     [signer writable]
-    InitPrice             = 9,
+    InitPrice                  = 9,
     /// deprecated
-    InitTest              = 10,
+    InitTest                   = 10,
     /// deprecated
-    UpdTest               = 11,
+    UpdTest                    = 11,
     /// Set min publishers
     // account[0] funding account       [signer writable]
     // account[1] price account         [signer writable]
-    SetMinPub             = 12,
+    SetMinPub                  = 12,
     /// Publish component price, never returning an error even if the update failed
     // account[0] funding account       [signer writable]
     // account[1] price account         [writable]
     // account[2] sysvar_clock account  []
-    UpdPriceNoFailOnError = 13,
+    // account[3..7] message buffer accounts, optional, see `upd_price`
+    // optionally followed by the publisher blocklist account [] and the product account []
+    UpdPriceNoFailOnError      = 13,
     /// Resizes a price account so that it fits the Time Machine
     // account[0] funding account       [signer writable]
     // account[1] price account         [signer writable]
     // account[2] system program        []
-    ResizePriceAccount    = 14,
+    ResizePriceAccount         = 14,
     /// Deletes a price account
     // account[0] funding account       [signer writable]
     // account[1] product account       [signer writable]
     // account[2] price account         [signer writable]
-    DelPrice              = 15,
+    DelPrice                   = 15,
     /// Deletes a product account
     // key[0] funding account       [signer writable]
     // key[1] mapping account       [signer writable]
     // key[2] product account       [signer writable]
-    DelProduct            = 16,
-    /// Update authorities
+    DelProduct                 = 16,
+    /// Set the initial authorities, which can then only be changed with `ProposeAuthorities`
     // key[0] upgrade authority         [signer writable]
     // key[1] programdata account       []
     // key[2] permissions account       [writable]
     // key[3] system program            []
-    UpdPermissions        = 17,
+    UpdPermissions             = 17,
     /// Set max latency
     // account[0] funding account       [signer writable]
     // account[1] price account         [signer writable]
-    SetMaxLatency         = 18,
+    SetMaxLatency              = 18,
     /// Init price feed index
     // account[0] funding account        [signer writable]
     // account[1] price account          [writable]
     // account[2] permissions account    [writable]
-    InitPriceFeedIndex    = 19,
+    InitPriceFeedIndex         = 19,
+    /// Publish component prices for several price accounts, never returning an error even if
+    /// some of the updates failed
+    // account[0] funding account       [signer writable]
+    // account[1] sysvar_clock account  []
+    // account[2..] price accounts      [writable]
+    // optionally followed by the publisher blocklist account [] and product accounts []
+    UpdPriceBatch              = 20,
+    /// Set or clear the flags of a price account
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [writable]
+    // account[2] permissions account   []
+    SetPriceFlags              = 21,
+    /// Set the multisig authority of the permissions account, or disable it with a zero
+    /// threshold. Once enabled, the signers of the multisig authority are passed to
+    /// administrative commands as additional signer accounts after their other accounts, this one
+    /// included: replacing or disabling the multisig authority needs its approval.
+    // key[0] upgrade authority         [signer writable]
+    // key[1] programdata account       []
+    // key[2] permissions account       [writable]
+    // key[3] system program            []
+    SetMultisigAuthority       = 22,
+    /// Propose new authorities, with the approval of the multisig authority if there is one. They
+    /// replace the current ones once the proposed master authority accepts them after the delay.
+    /// Proposing the default master authority cancels the pending proposal.
+    // key[0] upgrade authority         [signer writable]
+    // key[1] programdata account       []
+    // key[2] permissions account       [writable]
+    // key[3] system program            []
+    ProposeAuthorities         = 23,
+    /// Accept the proposed authorities
+    // key[0] proposed master authority [signer writable]
+    // key[1] permissions account       [writable]
+    AcceptAuthorities          = 24,
+    /// Initialize or update a publisher manager account
+    // account[0] funding account           [signer writable]
+    // account[1] publisher manager account [writable]
+    // account[2] permissions account       []
+    SetPublisherManager        = 25,
+    /// Set the divisor of the confidence-to-price ratio threshold of a price account, above which
+    /// publishers' prices are ignored. 0 restores the default `MAX_CI_DIVISOR`.
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [writable]
+    // account[2] permissions account   []
+    // account[3] system program        []
+    SetConfThreshold           = 26,
+    /// Set the half-life in slots of the EMAs of a price account. 0 restores the default
+    /// half-life.
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [writable]
+    // account[2] permissions account   []
+    // account[3] system program        []
+    SetEmaHalfLife             = 27,
+    /// Set the half-lives in slots of the additional EMAs of a price account, see `EmaHorizons`.
+    /// A zero half-life disables its horizon.
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [writable]
+    // account[2] permissions account   []
+    // account[3] system program        []
+    SetEmaHorizons             = 28,
+    /// Initialize the price history of a price account, which records its latest aggregates, or
+    /// clear it
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [writable]
+    // account[2] permissions account   []
+    // account[3] system program        []
+    InitPriceHistory           = 29,
+    /// Set the weight of a publisher in the stake-weighted aggregation of a price account, see
+    /// `PublisherWeights`
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [writable]
+    // account[2] permissions account   []
+    // account[3] system program        []
+    SetPublisherWeight         = 30,
+    /// Configure the rejection of the outlier quotes in the aggregation of a price account, see
+    /// `OutlierFilter`
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [writable]
+    // account[2] permissions account   []
+    // account[3] system program        []
+    SetOutlierFilter           = 31,
+    /// Initialize the aggregation diagnostics of a price account, which record why the quotes
+    /// of its components were left out of the last aggregation, or clear them
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [writable]
+    // account[2] permissions account   []
+    // account[3] system program        []
+    InitAggregationDiagnostics = 32,
+    /// Initialize the publisher statistics of a price account, which compare the quotes of its
+    /// publishers to its aggregates, or reset them
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [writable]
+    // account[2] permissions account   []
+    // account[3] system program        []
+    InitPublisherStats         = 33,
+    /// Set the trading hours of the price accounts of a product, see `TradingSchedule::parse` for
+    /// the format of the schedule that follows the header, or remove them with an empty schedule
+    // account[0] funding account       [signer writable]
+    // account[1] product account       [writable]
+    // account[2] permissions account   []
+    // account[3] system program        []
+    SetTradingSchedule         = 34,
+    /// Initialize the record of both sides of the confidence interval of the aggregates of a
+    /// price account, see `AggregateConfidence`, or clear it
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [writable]
+    // account[2] permissions account   []
+    // account[3] system program        []
+    InitAggregateConfidence    = 35,
+    /// Configure the circuit breaker of a price account, see `CircuitBreaker`, which also resets
+    /// it
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [writable]
+    // account[2] permissions account   []
+    // account[3] system program        []
+    SetCircuitBreaker          = 36,
+    /// Set the sanity bounds of the quotes of a price account, see `PriceBand`
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [writable]
+    // account[2] permissions account   []
+    // account[3] system program        []
+    SetPriceBand               = 37,
+    /// Add a key to the `PublisherBlocklistAccount`, whose quotes are then ignored by every price
+    /// account. The blocklist account is created if needed, the funding account paying for the
+    /// rent.
+    // account[0] funding account             [signer writable]
+    // account[1] publisher blocklist account [writable]
+    // account[2] permissions account         []
+    // account[3] system program              []
+    BlockPublisher             = 38,
+    /// Remove a key from the `PublisherBlocklistAccount`
+    // account[0] funding account             [signer writable]
+    // account[1] publisher blocklist account [writable]
+    // account[2] permissions account         []
+    UnblockPublisher           = 39,
+    /// Replace a key of a publisher by a new one in each of the price accounts, keeping its
+    /// quotes, weight and statistics. Signed by the security authority or by both keys.
+    // account[0] funding account       [signer writable]
+    // account[1] permissions account   []
+    // account[2] publisher blocklist   []
+    // account[3] old publisher         [signer]
+    // account[4] new publisher         [signer]
+    // account[5..] price accounts      [writable], `num_price_accounts` of them
+    RotatePublisher            = 40,
 }
 
 #[repr(C)]
-#[derive(Zeroable, Pod, Copy, Clone)]
+#[derive(Zeroable, Pod, Copy, Clone, Debug, PartialEq)]
 pub struct CommandHeader {
     pub version: u32,
     pub command: i32,
 }
 
+impl From<OracleCommand> for CommandHeader {
+    fn from(val: OracleCommand) -> Self {
+        CommandHeader {
+            version: PC_VERSION,
+            command: val as i32,
+        }
+    }
+}
+
 pub fn load_command_header_checked(data: &[u8]) -> Result<OracleCommand, OracleError> {
     let command_header = load::<CommandHeader>(data)?;
 
@@ -128,7 +301,7 @@ pub fn load_command_header_checked(data: &[u8]) -> Result<OracleCommand, OracleE
 }
 
 #[repr(C)]
-#[derive(Zeroable, Pod, Copy, Clone)]
+#[derive(Zeroable, Pod, Copy, Clone, Debug, PartialEq)]
 pub struct AddPriceArgs {
     pub header:     CommandHeader,
     pub exponent:   i32,
@@ -137,16 +310,18 @@ pub struct AddPriceArgs {
 pub type InitPriceArgs = AddPriceArgs;
 
 #[repr(C)]
-#[derive(Zeroable, Pod, Copy, Clone)]
+#[derive(Zeroable, Pod, Copy, Clone, Debug, PartialEq)]
 pub struct AddPublisherArgs {
     pub header:    CommandHeader,
     pub publisher: Pubkey,
 }
 
 pub type DelPublisherArgs = AddPublisherArgs;
+pub type BlockPublisherArgs = AddPublisherArgs;
+pub type UnblockPublisherArgs = AddPublisherArgs;
 
 #[repr(C)]
-#[derive(Zeroable, Clone, Copy, Pod)]
+#[derive(Zeroable, Clone, Copy, Pod, Debug, PartialEq)]
 pub struct SetMinPubArgs {
     pub header:             CommandHeader,
     pub minimum_publishers: u8,
@@ -154,18 +329,38 @@ pub struct SetMinPubArgs {
 }
 
 #[repr(C)]
-#[derive(Zeroable, Pod, Copy, Clone)]
+#[derive(Zeroable, Pod, Copy, Clone, Debug, PartialEq)]
 pub struct UpdPriceArgs {
     pub header:          CommandHeader,
     pub status:          u32,
-    pub unused_:         u32,
+    /// Corporate action status of the quote, e.g. a pending split or dividend, 0 if there is
+    /// none. The codes are up to the publishers of the price account.
+    pub corp_act_status: u32,
     pub price:           i64,
     pub confidence:      u64,
     pub publishing_slot: u64,
 }
 
+/// A component price update of `UpdPriceBatch`. The instruction data is a `CommandHeader`
+/// followed by one `PriceUpdate` per price account, in the order of the accounts.
 #[repr(C)]
-#[derive(Zeroable, Pod, Copy, Clone)]
+#[derive(Zeroable, Pod, Copy, Clone, Debug, PartialEq)]
+pub struct PriceUpdate {
+    pub status:          u32,
+    /// Same as `UpdPriceArgs::corp_act_status`
+    pub corp_act_status: u32,
+    pub price:           i64,
+    pub confidence:      u64,
+    pub publishing_slot: u64,
+}
+
+/// Value of the return data of `UpdPriceBatch` for a price account whose update succeeded
+pub const UPD_PRICE_BATCH_SUCCESS: u8 = 0;
+/// Value of the return data of `UpdPriceBatch` for a price account whose update failed
+pub const UPD_PRICE_BATCH_FAILURE: u8 = 1;
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone, Debug, PartialEq)]
 pub struct UpdPermissionsArgs {
     pub header:                  CommandHeader,
     pub master_authority:        Pubkey,
@@ -174,9 +369,135 @@ pub struct UpdPermissionsArgs {
 }
 
 #[repr(C)]
-#[derive(Zeroable, Clone, Copy, Pod)]
+#[derive(Zeroable, Clone, Copy, Pod, Debug, PartialEq)]
 pub struct SetMaxLatencyArgs {
     pub header:      CommandHeader,
     pub max_latency: u8,
     pub unused_:     [u8; 3],
 }
+
+#[repr(C)]
+#[derive(Zeroable, Clone, Copy, Pod, Debug, PartialEq)]
+pub struct SetPriceFlagsArgs {
+    pub header:      CommandHeader,
+    /// Bits of `PriceAccountFlags` to set
+    pub set_flags:   u8,
+    /// Bits of `PriceAccountFlags` to clear
+    pub clear_flags: u8,
+    pub unused_:     [u8; 2],
+}
+
+#[repr(C)]
+#[derive(Zeroable, Clone, Copy, Pod, Debug, PartialEq)]
+pub struct SetMultisigAuthorityArgs {
+    pub header:      CommandHeader,
+    /// Number of signatures required, 0 disables the multisig authority
+    pub threshold:   u8,
+    pub num_signers: u8,
+    pub unused_:     [u8; 2],
+    /// The first `num_signers` keys are the signers, the others must be zero
+    pub signers:     [Pubkey; MAX_MULTISIG_SIGNERS],
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone, Debug, PartialEq)]
+pub struct ProposeAuthoritiesArgs {
+    pub header:                  CommandHeader,
+    pub master_authority:        Pubkey,
+    pub data_curation_authority: Pubkey,
+    pub security_authority:      Pubkey,
+    /// Number of slots before the proposed authorities can be accepted, at least the stored
+    /// minimum delay
+    pub delay_slots:             u64,
+    /// Minimum delay of the next proposals, which applies once this proposal is accepted
+    pub min_delay_slots:         u64,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone, Debug, PartialEq)]
+pub struct SetPublisherManagerArgs {
+    pub header:          CommandHeader,
+    pub manager:         Pubkey,
+    /// The mapping account of the scope, or the default pubkey to use `products` instead
+    pub mapping_account: Pubkey,
+    pub num_products:    u32,
+    pub unused_:         u32,
+    pub products:        [Pubkey; MAX_PUBLISHER_MANAGER_PRODUCTS],
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone, Debug, PartialEq)]
+pub struct SetConfThresholdArgs {
+    pub header:       CommandHeader,
+    /// A publisher's price is ignored if its confidence is bigger than the absolute value of the
+    /// price divided by `conf_divisor`. 0 restores the default.
+    pub conf_divisor: u64,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone, Debug, PartialEq)]
+pub struct SetEmaHalfLifeArgs {
+    pub header:    CommandHeader,
+    /// Half-life of the EMAs in slots, 0 restores the default
+    pub half_life: u64,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone, Debug, PartialEq)]
+pub struct SetEmaHorizonsArgs {
+    pub header:     CommandHeader,
+    /// Half-life in slots of the EMAs of each horizon, 0 disabling the horizon
+    pub half_lives: [u64; NUM_EMA_HORIZONS],
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone, Debug, PartialEq)]
+pub struct SetPublisherWeightArgs {
+    pub header:    CommandHeader,
+    pub publisher: Pubkey,
+    /// Weight of the publisher's quotes, 0 excluding them from the stake-weighted aggregation
+    pub weight:    u64,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone, Debug, PartialEq)]
+pub struct SetOutlierFilterArgs {
+    pub header: CommandHeader,
+    /// The `OutlierFilterMode`, 0 disables the filter
+    pub mode:   u32,
+    /// Maximum distance to the reference, in multiples of the scale of the mode
+    pub k:      u32,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone, Debug, PartialEq)]
+pub struct SetCircuitBreakerArgs {
+    pub header:                 CommandHeader,
+    /// Maximum move from the previous aggregate price in basis points, 0 disables the breaker
+    pub max_move_bps:           u32,
+    /// Number of consecutive aggregates at the new level needed to trade again
+    pub required_confirmations: u32,
+    /// Maximum number of slots since the previous aggregate for a move to trip the breaker
+    pub max_slots:              u64,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone, Debug, PartialEq)]
+pub struct SetPriceBandArgs {
+    pub header:    CommandHeader,
+    /// Lowest valid price, in the exponent of the price account
+    pub min_price: i64,
+    /// Highest valid price, in the exponent of the price account. Both bounds at 0 remove the
+    /// band.
+    pub max_price: i64,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone, Debug, PartialEq)]
+pub struct RotatePublisherArgs {
+    pub header:             CommandHeader,
+    /// Number of price accounts following the publishers, before the additional signers of the
+    /// multisig authority
+    pub num_price_accounts: u32,
+    pub unused_:            u32,
+}
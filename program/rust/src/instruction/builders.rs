@@ -0,0 +1,1052 @@
+//! Builders for the instructions of the oracle program. Each function returns an `Instruction`
+//! with the account list expected by the corresponding processor. Deprecated commands that are
+//! rejected by the program (`AddMapping`, `InitTest`, `UpdTest` and `ResizePriceAccount`) don't
+//! have a builder.
+
+use {
+    super::{
+        AddPriceArgs,
+        AddPublisherArgs,
+        BlockPublisherArgs,
+        CommandHeader,
+        DelPublisherArgs,
+        InitPriceArgs,
+        OracleCommand,
+        PriceUpdate,
+        ProposeAuthoritiesArgs,
+        RotatePublisherArgs,
+        SetCircuitBreakerArgs,
+        SetConfThresholdArgs,
+        SetEmaHalfLifeArgs,
+        SetEmaHorizonsArgs,
+        SetMaxLatencyArgs,
+        SetMinPubArgs,
+        SetMultisigAuthorityArgs,
+        SetOutlierFilterArgs,
+        SetPriceBandArgs,
+        SetPriceFlagsArgs,
+        SetPublisherManagerArgs,
+        SetPublisherWeightArgs,
+        UnblockPublisherArgs,
+        UpdPermissionsArgs,
+        UpdPriceArgs,
+    },
+    crate::accounts::{
+        create_pc_str_t,
+        OutlierFilterMode,
+        PriceAccountFlags,
+        MAX_MULTISIG_SIGNERS,
+        MAX_PUBLISHER_MANAGER_PRODUCTS,
+        NUM_EMA_HORIZONS,
+        PERMISSIONS_SEED,
+        PUBLISHER_BLOCKLIST_SEED,
+        UPD_PRICE_WRITE_SEED,
+    },
+    bytemuck::{
+        bytes_of,
+        Pod,
+    },
+    solana_program::{
+        bpf_loader_upgradeable,
+        instruction::{
+            AccountMeta,
+            Instruction,
+        },
+        pubkey::Pubkey,
+        system_program,
+        sysvar::clock,
+    },
+};
+
+/// Address of the permissions account of the oracle program `program_id`.
+pub fn permissions_pubkey(program_id: &Pubkey) -> Pubkey {
+    let (permissions_pubkey, _) =
+        Pubkey::find_program_address(&[PERMISSIONS_SEED.as_bytes()], program_id);
+    permissions_pubkey
+}
+
+/// Address of the publisher blocklist account of the program `program_id`.
+pub fn publisher_blocklist_pubkey(program_id: &Pubkey) -> Pubkey {
+    let (publisher_blocklist_pubkey, _) =
+        Pubkey::find_program_address(&[PUBLISHER_BLOCKLIST_SEED.as_bytes()], program_id);
+    publisher_blocklist_pubkey
+}
+
+/// Address of the programdata account of the upgradeable program `program_id`.
+pub fn programdata_pubkey(program_id: &Pubkey) -> Pubkey {
+    let (programdata_pubkey, _) =
+        Pubkey::find_program_address(&[&program_id.to_bytes()], &bpf_loader_upgradeable::id());
+    programdata_pubkey
+}
+
+fn build<T: Pod>(program_id: &Pubkey, args: &T, accounts: Vec<AccountMeta>) -> Instruction {
+    Instruction::new_with_bytes(*program_id, bytes_of(args), accounts)
+}
+
+/// Append `signers` to the accounts of an administrative instruction, so that it can be approved
+/// by the multisig authority of the permissions account.
+pub fn with_additional_signers(mut instruction: Instruction, signers: &[Pubkey]) -> Instruction {
+    instruction.accounts.extend(
+        signers
+            .iter()
+            .map(|signer| AccountMeta::new_readonly(*signer, true)),
+    );
+    instruction
+}
+
+/// Address of the PDA of the oracle program `program_id` signing the messages it sends to
+/// `message_buffer_program`.
+pub fn oracle_auth_pubkey(program_id: &Pubkey, message_buffer_program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            UPD_PRICE_WRITE_SEED.as_bytes(),
+            &message_buffer_program.to_bytes(),
+        ],
+        program_id,
+    )
+    .0
+}
+
+/// Append the message buffer accounts to a price update instruction, so that the price messages
+/// of the aggregation are sent to `message_buffer_program`. Must be called before
+/// `with_publisher_blocklist`.
+pub fn with_message_buffer(
+    mut instruction: Instruction,
+    message_buffer_program: &Pubkey,
+    whitelist: &Pubkey,
+    message_buffer_data: &Pubkey,
+) -> Instruction {
+    let oracle_auth_pda = oracle_auth_pubkey(&instruction.program_id, message_buffer_program);
+    instruction.accounts.extend([
+        AccountMeta::new_readonly(*message_buffer_program, false),
+        AccountMeta::new_readonly(*whitelist, false),
+        AccountMeta::new_readonly(oracle_auth_pda, false),
+        AccountMeta::new(*message_buffer_data, false),
+    ]);
+    instruction
+}
+
+/// Append the publisher blocklist account to a price update instruction, so that it fails for a
+/// blocked publisher and the aggregation ignores the quotes of the blocked publishers.
+pub fn with_publisher_blocklist(mut instruction: Instruction, program_id: &Pubkey) -> Instruction {
+    instruction.accounts.push(AccountMeta::new_readonly(
+        publisher_blocklist_pubkey(program_id),
+        false,
+    ));
+    instruction
+}
+
+/// Append product accounts to a price update instruction already passed to
+/// `with_publisher_blocklist`, so that the aggregation applies their `TradingSchedule`. A single
+/// product account is expected by `upd_price`, the product of its price account.
+pub fn with_product_accounts(
+    mut instruction: Instruction,
+    product_accounts: &[Pubkey],
+) -> Instruction {
+    instruction.accounts.extend(
+        product_accounts
+            .iter()
+            .map(|product_account| AccountMeta::new_readonly(*product_account, false)),
+    );
+    instruction
+}
+
+/// Initialize the first mapping account
+pub fn init_mapping(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    mapping_account: &Pubkey,
+) -> Instruction {
+    let cmd: CommandHeader = OracleCommand::InitMapping.into();
+    build(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta::new(*funding_account, true),
+            AccountMeta::new(*mapping_account, false),
+            AccountMeta::new_readonly(permissions_pubkey(program_id), false),
+        ],
+    )
+}
+
+/// Initialize a new product account and add it to the tail mapping account
+pub fn add_product(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    tail_mapping_account: &Pubkey,
+    product_account: &Pubkey,
+) -> Instruction {
+    let cmd: CommandHeader = OracleCommand::AddProduct.into();
+    build(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta::new(*funding_account, true),
+            AccountMeta::new(*tail_mapping_account, false),
+            AccountMeta::new(*product_account, false),
+            AccountMeta::new_readonly(permissions_pubkey(program_id), false),
+        ],
+    )
+}
+
+/// Overwrite the metadata of a product account with the provided key-value pairs
+pub fn upd_product(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    product_account: &Pubkey,
+    metadata: &[(&str, &str)],
+) -> Instruction {
+    let cmd: CommandHeader = OracleCommand::UpdProduct.into();
+    let mut data = bytes_of(&cmd).to_vec();
+    for (key, value) in metadata {
+        data.extend(create_pc_str_t(key));
+        data.extend(create_pc_str_t(value));
+    }
+    Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new(*funding_account, true),
+            AccountMeta::new(*product_account, false),
+            AccountMeta::new_readonly(permissions_pubkey(program_id), false),
+        ],
+    )
+}
+
+/// Initialize a new price account and add it to a product account
+pub fn add_price(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    product_account: &Pubkey,
+    price_account: &Pubkey,
+    exponent: i32,
+    price_type: u32,
+) -> Instruction {
+    let cmd = AddPriceArgs {
+        header: OracleCommand::AddPrice.into(),
+        exponent,
+        price_type,
+    };
+    build(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta::new(*funding_account, true),
+            AccountMeta::new(*product_account, false),
+            AccountMeta::new(*price_account, false),
+            AccountMeta::new(permissions_pubkey(program_id), false),
+        ],
+    )
+}
+
+/// Add a publisher to a price account
+pub fn add_publisher(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    price_account: &Pubkey,
+    publisher: &Pubkey,
+) -> Instruction {
+    let cmd = AddPublisherArgs {
+        header:    OracleCommand::AddPublisher.into(),
+        publisher: *publisher,
+    };
+    build(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta::new(*funding_account, true),
+            AccountMeta::new(*price_account, false),
+            AccountMeta::new_readonly(permissions_pubkey(program_id), false),
+        ],
+    )
+}
+
+/// Remove a publisher from a price account
+pub fn del_publisher(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    price_account: &Pubkey,
+    publisher: &Pubkey,
+) -> Instruction {
+    let cmd = DelPublisherArgs {
+        header:    OracleCommand::DelPublisher.into(),
+        publisher: *publisher,
+    };
+    build(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta::new(*funding_account, true),
+            AccountMeta::new(*price_account, false),
+            AccountMeta::new_readonly(permissions_pubkey(program_id), false),
+        ],
+    )
+}
+
+fn upd_price_instruction(
+    program_id: &Pubkey,
+    publisher: &Pubkey,
+    price_account: &Pubkey,
+    cmd: &UpdPriceArgs,
+) -> Instruction {
+    build(
+        program_id,
+        cmd,
+        vec![
+            AccountMeta::new(*publisher, true),
+            AccountMeta::new(*price_account, false),
+            AccountMeta::new_readonly(clock::id(), false),
+        ],
+    )
+}
+
+/// Publish a component price
+pub fn upd_price(
+    program_id: &Pubkey,
+    publisher: &Pubkey,
+    price_account: &Pubkey,
+    status: u32,
+    price: i64,
+    confidence: u64,
+    publishing_slot: u64,
+) -> Instruction {
+    let cmd = UpdPriceArgs {
+        header: OracleCommand::UpdPrice.into(),
+        status,
+        corp_act_status: 0,
+        price,
+        confidence,
+        publishing_slot,
+    };
+    upd_price_instruction(program_id, publisher, price_account, &cmd)
+}
+
+/// Publish a component price along with the corporate action status of the quote
+#[allow(clippy::too_many_arguments)]
+pub fn upd_price_with_corp_act_status(
+    program_id: &Pubkey,
+    publisher: &Pubkey,
+    price_account: &Pubkey,
+    status: u32,
+    corp_act_status: u32,
+    price: i64,
+    confidence: u64,
+    publishing_slot: u64,
+) -> Instruction {
+    let cmd = UpdPriceArgs {
+        header: OracleCommand::UpdPrice.into(),
+        status,
+        corp_act_status,
+        price,
+        confidence,
+        publishing_slot,
+    };
+    upd_price_instruction(program_id, publisher, price_account, &cmd)
+}
+
+/// Publish a component price, never returning an error even if the update failed
+pub fn upd_price_no_fail_on_error(
+    program_id: &Pubkey,
+    publisher: &Pubkey,
+    price_account: &Pubkey,
+    status: u32,
+    price: i64,
+    confidence: u64,
+    publishing_slot: u64,
+) -> Instruction {
+    let cmd = UpdPriceArgs {
+        header: OracleCommand::UpdPriceNoFailOnError.into(),
+        status,
+        corp_act_status: 0,
+        price,
+        confidence,
+        publishing_slot,
+    };
+    upd_price_instruction(program_id, publisher, price_account, &cmd)
+}
+
+/// Compute the aggregate price without publishing a component price. `publisher` must be one
+/// of the publishers of the price account.
+pub fn agg_price(program_id: &Pubkey, publisher: &Pubkey, price_account: &Pubkey) -> Instruction {
+    let cmd = UpdPriceArgs {
+        header:          OracleCommand::AggPrice.into(),
+        status:          0,
+        corp_act_status: 0,
+        price:           0,
+        confidence:      0,
+        publishing_slot: 0,
+    };
+    upd_price_instruction(program_id, publisher, price_account, &cmd)
+}
+
+/// (Re)initialize a price account
+pub fn init_price(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    price_account: &Pubkey,
+    exponent: i32,
+    price_type: u32,
+) -> Instruction {
+    let cmd = InitPriceArgs {
+        header: OracleCommand::InitPrice.into(),
+        exponent,
+        price_type,
+    };
+    build(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta::new(*funding_account, true),
+            AccountMeta::new(*price_account, false),
+            AccountMeta::new_readonly(permissions_pubkey(program_id), false),
+        ],
+    )
+}
+
+/// Set the minimum number of publishers required for a valid aggregate price
+pub fn set_min_pub(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    price_account: &Pubkey,
+    minimum_publishers: u8,
+) -> Instruction {
+    let cmd = SetMinPubArgs {
+        header: OracleCommand::SetMinPub.into(),
+        minimum_publishers,
+        unused_: [0; 3],
+    };
+    build(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta::new(*funding_account, true),
+            AccountMeta::new(*price_account, false),
+            AccountMeta::new_readonly(permissions_pubkey(program_id), false),
+        ],
+    )
+}
+
+/// Delete a price account from a product account
+pub fn del_price(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    product_account: &Pubkey,
+    price_account: &Pubkey,
+) -> Instruction {
+    let cmd: CommandHeader = OracleCommand::DelPrice.into();
+    build(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta::new(*funding_account, true),
+            AccountMeta::new(*product_account, false),
+            AccountMeta::new(*price_account, false),
+            AccountMeta::new_readonly(permissions_pubkey(program_id), false),
+        ],
+    )
+}
+
+/// Delete a product account from a mapping account
+pub fn del_product(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    mapping_account: &Pubkey,
+    product_account: &Pubkey,
+) -> Instruction {
+    let cmd: CommandHeader = OracleCommand::DelProduct.into();
+    build(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta::new(*funding_account, true),
+            AccountMeta::new(*mapping_account, false),
+            AccountMeta::new(*product_account, false),
+            AccountMeta::new_readonly(permissions_pubkey(program_id), false),
+        ],
+    )
+}
+
+/// Update the authorities stored in the permissions account. Must be signed by the upgrade
+/// authority of the program.
+pub fn upd_permissions(
+    program_id: &Pubkey,
+    upgrade_authority: &Pubkey,
+    master_authority: &Pubkey,
+    data_curation_authority: &Pubkey,
+    security_authority: &Pubkey,
+) -> Instruction {
+    let cmd = UpdPermissionsArgs {
+        header:                  OracleCommand::UpdPermissions.into(),
+        master_authority:        *master_authority,
+        data_curation_authority: *data_curation_authority,
+        security_authority:      *security_authority,
+    };
+    build(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta::new(*upgrade_authority, true),
+            AccountMeta::new_readonly(programdata_pubkey(program_id), false),
+            AccountMeta::new(permissions_pubkey(program_id), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Set the maximum latency (in slots) of the publishers' prices in a price account
+pub fn set_max_latency(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    price_account: &Pubkey,
+    max_latency: u8,
+) -> Instruction {
+    let cmd = SetMaxLatencyArgs {
+        header: OracleCommand::SetMaxLatency.into(),
+        max_latency,
+        unused_: [0; 3],
+    };
+    build(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta::new(*funding_account, true),
+            AccountMeta::new(*price_account, false),
+            AccountMeta::new_readonly(permissions_pubkey(program_id), false),
+        ],
+    )
+}
+
+/// Assign a feed index to a price account that doesn't have one yet
+pub fn init_price_feed_index(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    price_account: &Pubkey,
+) -> Instruction {
+    let cmd: CommandHeader = OracleCommand::InitPriceFeedIndex.into();
+    build(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta::new(*funding_account, true),
+            AccountMeta::new(*price_account, false),
+            AccountMeta::new(permissions_pubkey(program_id), false),
+        ],
+    )
+}
+
+/// Publish component prices for several price accounts. `updates` pairs each price account with
+/// the publisher's new price.
+pub fn upd_price_batch(
+    program_id: &Pubkey,
+    publisher: &Pubkey,
+    updates: &[(Pubkey, PriceUpdate)],
+) -> Instruction {
+    let cmd: CommandHeader = OracleCommand::UpdPriceBatch.into();
+    let mut data = bytes_of(&cmd).to_vec();
+    let mut accounts = vec![
+        AccountMeta::new(*publisher, true),
+        AccountMeta::new_readonly(clock::id(), false),
+    ];
+    for (price_account, update) in updates {
+        data.extend_from_slice(bytes_of(update));
+        accounts.push(AccountMeta::new(*price_account, false));
+    }
+    Instruction::new_with_bytes(*program_id, &data, accounts)
+}
+
+/// Set and clear flags of a price account
+pub fn set_price_flags(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    price_account: &Pubkey,
+    set_flags: PriceAccountFlags,
+    clear_flags: PriceAccountFlags,
+) -> Instruction {
+    let cmd = SetPriceFlagsArgs {
+        header:      OracleCommand::SetPriceFlags.into(),
+        set_flags:   set_flags.bits(),
+        clear_flags: clear_flags.bits(),
+        unused_:     [0; 2],
+    };
+    build(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta::new(*funding_account, true),
+            AccountMeta::new(*price_account, false),
+            AccountMeta::new_readonly(permissions_pubkey(program_id), false),
+        ],
+    )
+}
+
+/// Append the publisher manager account, and the mapping account of its scope if any, to the
+/// accounts of an `add_publisher` or `del_publisher` instruction signed by the publisher manager.
+pub fn with_publisher_manager(
+    mut instruction: Instruction,
+    publisher_manager_account: &Pubkey,
+    mapping_account: Option<&Pubkey>,
+) -> Instruction {
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(*publisher_manager_account, false));
+    if let Some(mapping_account) = mapping_account {
+        instruction
+            .accounts
+            .push(AccountMeta::new_readonly(*mapping_account, false));
+    }
+    instruction
+}
+
+/// Set the multisig authority stored in the permissions account, `threshold` of `signers` being
+/// required to approve administrative instructions. A zero threshold disables it. Must be signed
+/// by the upgrade authority of the program.
+pub fn set_multisig_authority(
+    program_id: &Pubkey,
+    upgrade_authority: &Pubkey,
+    threshold: u8,
+    signers: &[Pubkey],
+) -> Instruction {
+    assert!(signers.len() <= MAX_MULTISIG_SIGNERS);
+    let mut cmd = SetMultisigAuthorityArgs {
+        header: OracleCommand::SetMultisigAuthority.into(),
+        threshold,
+        num_signers: signers.len() as u8,
+        unused_: [0; 2],
+        signers: [Pubkey::default(); MAX_MULTISIG_SIGNERS],
+    };
+    cmd.signers[..signers.len()].copy_from_slice(signers);
+    build(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta::new(*upgrade_authority, true),
+            AccountMeta::new_readonly(programdata_pubkey(program_id), false),
+            AccountMeta::new(permissions_pubkey(program_id), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Propose new authorities for the permissions account, which the proposed master authority can
+/// accept after `delay_slots` slots, and the minimum delay of the next proposals. Must be signed
+/// by the upgrade authority of the program.
+pub fn propose_authorities(
+    program_id: &Pubkey,
+    upgrade_authority: &Pubkey,
+    master_authority: &Pubkey,
+    data_curation_authority: &Pubkey,
+    security_authority: &Pubkey,
+    delay_slots: u64,
+    min_delay_slots: u64,
+) -> Instruction {
+    let cmd = ProposeAuthoritiesArgs {
+        header: OracleCommand::ProposeAuthorities.into(),
+        master_authority: *master_authority,
+        data_curation_authority: *data_curation_authority,
+        security_authority: *security_authority,
+        delay_slots,
+        min_delay_slots,
+    };
+    build(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta::new(*upgrade_authority, true),
+            AccountMeta::new_readonly(programdata_pubkey(program_id), false),
+            AccountMeta::new(permissions_pubkey(program_id), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Accept the proposed authorities. Must be signed by the proposed master authority.
+pub fn accept_authorities(program_id: &Pubkey, master_authority: &Pubkey) -> Instruction {
+    let cmd: CommandHeader = OracleCommand::AcceptAuthorities.into();
+    build(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta::new(*master_authority, true),
+            AccountMeta::new(permissions_pubkey(program_id), false),
+        ],
+    )
+}
+
+/// Initialize or update a publisher manager account, delegating the publishers of the price
+/// accounts of the products of `mapping_account`, or of `products` if it is `None`, to `manager`.
+pub fn set_publisher_manager(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    publisher_manager_account: &Pubkey,
+    manager: &Pubkey,
+    mapping_account: Option<&Pubkey>,
+    products: &[Pubkey],
+) -> Instruction {
+    assert!(products.len() <= MAX_PUBLISHER_MANAGER_PRODUCTS);
+    let mut cmd = SetPublisherManagerArgs {
+        header:          OracleCommand::SetPublisherManager.into(),
+        manager:         *manager,
+        mapping_account: mapping_account.copied().unwrap_or_default(),
+        num_products:    products.len() as u32,
+        unused_:         0,
+        products:        [Pubkey::default(); MAX_PUBLISHER_MANAGER_PRODUCTS],
+    };
+    cmd.products[..products.len()].copy_from_slice(products);
+    build(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta::new(*funding_account, true),
+            AccountMeta::new(*publisher_manager_account, false),
+            AccountMeta::new_readonly(permissions_pubkey(program_id), false),
+        ],
+    )
+}
+
+/// Set the divisor of the confidence-to-price ratio threshold of a price account, 0 restoring the
+/// default
+pub fn set_conf_threshold(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    price_account: &Pubkey,
+    conf_divisor: u64,
+) -> Instruction {
+    let cmd = SetConfThresholdArgs {
+        header: OracleCommand::SetConfThreshold.into(),
+        conf_divisor,
+    };
+    build(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta::new(*funding_account, true),
+            AccountMeta::new(*price_account, false),
+            AccountMeta::new_readonly(permissions_pubkey(program_id), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Set the half-life in slots of the EMAs of a price account, 0 restoring the default
+pub fn set_ema_half_life(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    price_account: &Pubkey,
+    half_life: u64,
+) -> Instruction {
+    let cmd = SetEmaHalfLifeArgs {
+        header: OracleCommand::SetEmaHalfLife.into(),
+        half_life,
+    };
+    build(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta::new(*funding_account, true),
+            AccountMeta::new(*price_account, false),
+            AccountMeta::new_readonly(permissions_pubkey(program_id), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Set the half-lives in slots of the EMA horizons of a price account, 0 disabling a horizon
+pub fn set_ema_horizons(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    price_account: &Pubkey,
+    half_lives: [u64; NUM_EMA_HORIZONS],
+) -> Instruction {
+    let cmd = SetEmaHorizonsArgs {
+        header: OracleCommand::SetEmaHorizons.into(),
+        half_lives,
+    };
+    build(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta::new(*funding_account, true),
+            AccountMeta::new(*price_account, false),
+            AccountMeta::new_readonly(permissions_pubkey(program_id), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Initialize or clear the price history of a price account
+pub fn init_price_history(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    price_account: &Pubkey,
+) -> Instruction {
+    let cmd: CommandHeader = OracleCommand::InitPriceHistory.into();
+    build(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta::new(*funding_account, true),
+            AccountMeta::new(*price_account, false),
+            AccountMeta::new_readonly(permissions_pubkey(program_id), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Set the weight of a publisher in the stake-weighted aggregation of a price account, 0
+/// removing it
+pub fn set_publisher_weight(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    price_account: &Pubkey,
+    publisher: &Pubkey,
+    weight: u64,
+) -> Instruction {
+    let cmd = SetPublisherWeightArgs {
+        header: OracleCommand::SetPublisherWeight.into(),
+        publisher: *publisher,
+        weight,
+    };
+    build(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta::new(*funding_account, true),
+            AccountMeta::new(*price_account, false),
+            AccountMeta::new_readonly(permissions_pubkey(program_id), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Configure the rejection of the outlier quotes of a price account, `None` disabling it
+pub fn set_outlier_filter(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    price_account: &Pubkey,
+    mode: Option<OutlierFilterMode>,
+    k: u32,
+) -> Instruction {
+    let cmd = SetOutlierFilterArgs {
+        header: OracleCommand::SetOutlierFilter.into(),
+        mode: mode.map_or(0, |mode| mode as u32),
+        k,
+    };
+    build(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta::new(*funding_account, true),
+            AccountMeta::new(*price_account, false),
+            AccountMeta::new_readonly(permissions_pubkey(program_id), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Initialize or clear the aggregation diagnostics of a price account
+pub fn init_aggregation_diagnostics(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    price_account: &Pubkey,
+) -> Instruction {
+    let cmd: CommandHeader = OracleCommand::InitAggregationDiagnostics.into();
+    build(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta::new(*funding_account, true),
+            AccountMeta::new(*price_account, false),
+            AccountMeta::new_readonly(permissions_pubkey(program_id), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Initialize or reset the publisher statistics of a price account
+pub fn init_publisher_stats(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    price_account: &Pubkey,
+) -> Instruction {
+    let cmd: CommandHeader = OracleCommand::InitPublisherStats.into();
+    build(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta::new(*funding_account, true),
+            AccountMeta::new(*price_account, false),
+            AccountMeta::new_readonly(permissions_pubkey(program_id), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Set the trading hours of the price accounts of a product, see `TradingSchedule::parse` for the
+/// format of `schedule`, an empty schedule removing them
+pub fn set_trading_schedule(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    product_account: &Pubkey,
+    schedule: &str,
+) -> Instruction {
+    let cmd: CommandHeader = OracleCommand::SetTradingSchedule.into();
+    let mut data = bytes_of(&cmd).to_vec();
+    data.extend_from_slice(schedule.as_bytes());
+    Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new(*funding_account, true),
+            AccountMeta::new(*product_account, false),
+            AccountMeta::new_readonly(permissions_pubkey(program_id), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Initialize or clear the record of both sides of the confidence interval of the aggregates of
+/// a price account
+pub fn init_aggregate_confidence(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    price_account: &Pubkey,
+) -> Instruction {
+    let cmd: CommandHeader = OracleCommand::InitAggregateConfidence.into();
+    build(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta::new(*funding_account, true),
+            AccountMeta::new(*price_account, false),
+            AccountMeta::new_readonly(permissions_pubkey(program_id), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Configure the circuit breaker of a price account, a `max_move_bps` of 0 disabling it
+pub fn set_circuit_breaker(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    price_account: &Pubkey,
+    max_move_bps: u32,
+    required_confirmations: u32,
+    max_slots: u64,
+) -> Instruction {
+    let cmd = SetCircuitBreakerArgs {
+        header: OracleCommand::SetCircuitBreaker.into(),
+        max_move_bps,
+        required_confirmations,
+        max_slots,
+    };
+    build(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta::new(*funding_account, true),
+            AccountMeta::new(*price_account, false),
+            AccountMeta::new_readonly(permissions_pubkey(program_id), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Set the sanity bounds of the quotes of a price account, in its exponent, `(0, 0)` removing
+/// them
+pub fn set_price_band(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    price_account: &Pubkey,
+    min_price: i64,
+    max_price: i64,
+) -> Instruction {
+    let cmd = SetPriceBandArgs {
+        header: OracleCommand::SetPriceBand.into(),
+        min_price,
+        max_price,
+    };
+    build(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta::new(*funding_account, true),
+            AccountMeta::new(*price_account, false),
+            AccountMeta::new_readonly(permissions_pubkey(program_id), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Add a publisher to the publisher blocklist, creating it if needed
+pub fn block_publisher(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    publisher: &Pubkey,
+) -> Instruction {
+    let cmd = BlockPublisherArgs {
+        header:    OracleCommand::BlockPublisher.into(),
+        publisher: *publisher,
+    };
+    build(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta::new(*funding_account, true),
+            AccountMeta::new(publisher_blocklist_pubkey(program_id), false),
+            AccountMeta::new_readonly(permissions_pubkey(program_id), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Remove a publisher from the publisher blocklist
+pub fn unblock_publisher(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    publisher: &Pubkey,
+) -> Instruction {
+    let cmd = UnblockPublisherArgs {
+        header:    OracleCommand::UnblockPublisher.into(),
+        publisher: *publisher,
+    };
+    build(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta::new(*funding_account, true),
+            AccountMeta::new(publisher_blocklist_pubkey(program_id), false),
+            AccountMeta::new_readonly(permissions_pubkey(program_id), false),
+        ],
+    )
+}
+
+/// Replace `old_publisher` by `new_publisher` in the `price_accounts`. The publishers sign the
+/// instruction if `signed_by_publishers`, otherwise the funding account must be the security
+/// authority, see `with_additional_signers` for a multisig authority.
+pub fn rotate_publisher(
+    program_id: &Pubkey,
+    funding_account: &Pubkey,
+    old_publisher: &Pubkey,
+    new_publisher: &Pubkey,
+    price_accounts: &[Pubkey],
+    signed_by_publishers: bool,
+) -> Instruction {
+    let cmd = RotatePublisherArgs {
+        header:             OracleCommand::RotatePublisher.into(),
+        num_price_accounts: price_accounts.len() as u32,
+        unused_:            0,
+    };
+    let mut accounts = vec![
+        AccountMeta::new(*funding_account, true),
+        AccountMeta::new_readonly(permissions_pubkey(program_id), false),
+        AccountMeta::new_readonly(publisher_blocklist_pubkey(program_id), false),
+        AccountMeta::new_readonly(*old_publisher, signed_by_publishers),
+        AccountMeta::new_readonly(*new_publisher, signed_by_publishers),
+    ];
+    accounts.extend(
+        price_accounts
+            .iter()
+            .map(|price_account| AccountMeta::new(*price_account, false)),
+    );
+    build(program_id, &cmd, accounts)
+}
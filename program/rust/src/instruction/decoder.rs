@@ -0,0 +1,1065 @@
+//! Decoding of the instructions of the oracle program, e.g. for indexing transactions.
+
+use {
+    super::{
+        load_command_header_checked,
+        AddPriceArgs,
+        AddPublisherArgs,
+        BlockPublisherArgs,
+        CommandHeader,
+        DelPublisherArgs,
+        InitPriceArgs,
+        OracleCommand,
+        PriceUpdate,
+        ProposeAuthoritiesArgs,
+        RotatePublisherArgs,
+        SetCircuitBreakerArgs,
+        SetConfThresholdArgs,
+        SetEmaHalfLifeArgs,
+        SetEmaHorizonsArgs,
+        SetMaxLatencyArgs,
+        SetMinPubArgs,
+        SetMultisigAuthorityArgs,
+        SetOutlierFilterArgs,
+        SetPriceBandArgs,
+        SetPriceFlagsArgs,
+        SetPublisherManagerArgs,
+        SetPublisherWeightArgs,
+        UnblockPublisherArgs,
+        UpdPermissionsArgs,
+        UpdPriceArgs,
+    },
+    crate::{
+        accounts::read_pc_str_t,
+        deserialize::load,
+        error::OracleError,
+    },
+    bytemuck::{
+        bytes_of,
+        pod_read_unaligned,
+        Pod,
+    },
+    solana_program::{
+        instruction::AccountMeta,
+        pubkey::Pubkey,
+        sysvar::clock,
+    },
+    std::mem::size_of,
+    thiserror::Error,
+};
+
+/// Errors that may be returned when decoding an instruction
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum DecodeError {
+    #[error("instruction data too short: expected at least {expected} bytes, got {actual}")]
+    InstructionDataTooShort { expected: usize, actual: usize },
+    #[error("invalid instruction version {0}")]
+    InvalidVersion(u32),
+    #[error("unknown command {0}")]
+    UnknownCommand(i32),
+    #[error("invalid number of accounts for {command:?}: expected {expected:?}, got {actual}")]
+    InvalidNumberOfAccounts {
+        command:  OracleCommand,
+        expected: &'static [usize],
+        actual:   usize,
+    },
+    #[error("invalid product metadata at byte {0}")]
+    InvalidProductMetadata(usize),
+    #[error("invalid length {0} of the price updates of a batch")]
+    InvalidPriceUpdatesLength(usize),
+    #[error("trading schedule is not valid UTF-8")]
+    InvalidTradingSchedule,
+    #[error("invalid number of accounts for a batch: expected {expected}, got {actual}")]
+    InvalidNumberOfBatchAccounts { expected: usize, actual: usize },
+    #[error("invalid number of price accounts: expected at least {expected}, got {actual}")]
+    InvalidNumberOfPriceAccounts { expected: usize, actual: usize },
+}
+
+/// The optional accounts of the price update instructions, used to send the price messages to
+/// the message buffer program
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MessageBufferAccounts {
+    pub program_id:          Pubkey,
+    pub whitelist:           Pubkey,
+    pub oracle_auth_pda:     Pubkey,
+    pub message_buffer_data: Pubkey,
+}
+
+/// The accounts of the price update instructions
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UpdPriceAccounts {
+    pub funding_account:     Pubkey,
+    pub price_account:       Pubkey,
+    pub clock_account:       Pubkey,
+    pub message_buffer:      Option<MessageBufferAccounts>,
+    pub publisher_blocklist: Option<Pubkey>,
+    pub product_account:     Option<Pubkey>,
+}
+
+/// A decoded instruction of the oracle program, with one variant per `OracleCommand`.
+/// Deprecated commands are rejected by the program, so their accounts are not decoded.
+/// The `additional_signers` of the administrative commands are the accounts following their other
+/// accounts, signers of the multisig authority.
+#[derive(Clone, Debug, PartialEq)]
+pub enum OracleInstruction {
+    InitMapping {
+        funding_account:     Pubkey,
+        mapping_account:     Pubkey,
+        permissions_account: Pubkey,
+        additional_signers:  Vec<Pubkey>,
+    },
+    AddMapping,
+    AddProduct {
+        funding_account:      Pubkey,
+        tail_mapping_account: Pubkey,
+        product_account:      Pubkey,
+        permissions_account:  Pubkey,
+        additional_signers:   Vec<Pubkey>,
+    },
+    UpdProduct {
+        funding_account:     Pubkey,
+        product_account:     Pubkey,
+        permissions_account: Pubkey,
+        /// Key-value pairs of the new product metadata
+        metadata:            Vec<(String, String)>,
+        additional_signers:  Vec<Pubkey>,
+    },
+    AddPrice {
+        args:                AddPriceArgs,
+        funding_account:     Pubkey,
+        product_account:     Pubkey,
+        price_account:       Pubkey,
+        permissions_account: Pubkey,
+        additional_signers:  Vec<Pubkey>,
+    },
+    AddPublisher {
+        args:                      AddPublisherArgs,
+        funding_account:           Pubkey,
+        price_account:             Pubkey,
+        permissions_account:       Pubkey,
+        /// The publisher manager account, if the funding account is its manager
+        publisher_manager_account: Option<Pubkey>,
+        /// The mapping account of the scope of the publisher manager, if it has one
+        scope_mapping_account:     Option<Pubkey>,
+        additional_signers:        Vec<Pubkey>,
+    },
+    DelPublisher {
+        args:                      DelPublisherArgs,
+        funding_account:           Pubkey,
+        price_account:             Pubkey,
+        permissions_account:       Pubkey,
+        /// The publisher manager account, if the funding account is its manager
+        publisher_manager_account: Option<Pubkey>,
+        /// The mapping account of the scope of the publisher manager, if it has one
+        scope_mapping_account:     Option<Pubkey>,
+        additional_signers:        Vec<Pubkey>,
+    },
+    UpdPrice {
+        args:     UpdPriceArgs,
+        accounts: UpdPriceAccounts,
+    },
+    AggPrice {
+        args:     UpdPriceArgs,
+        accounts: UpdPriceAccounts,
+    },
+    InitPrice {
+        args:                InitPriceArgs,
+        funding_account:     Pubkey,
+        price_account:       Pubkey,
+        permissions_account: Pubkey,
+        additional_signers:  Vec<Pubkey>,
+    },
+    InitTest,
+    UpdTest,
+    SetMinPub {
+        args:                SetMinPubArgs,
+        funding_account:     Pubkey,
+        price_account:       Pubkey,
+        permissions_account: Pubkey,
+        additional_signers:  Vec<Pubkey>,
+    },
+    UpdPriceNoFailOnError {
+        args:     UpdPriceArgs,
+        accounts: UpdPriceAccounts,
+    },
+    ResizePriceAccount,
+    DelPrice {
+        funding_account:     Pubkey,
+        product_account:     Pubkey,
+        price_account:       Pubkey,
+        permissions_account: Pubkey,
+        additional_signers:  Vec<Pubkey>,
+    },
+    DelProduct {
+        funding_account:     Pubkey,
+        mapping_account:     Pubkey,
+        product_account:     Pubkey,
+        permissions_account: Pubkey,
+        additional_signers:  Vec<Pubkey>,
+    },
+    UpdPermissions {
+        args:                UpdPermissionsArgs,
+        upgrade_authority:   Pubkey,
+        programdata_account: Pubkey,
+        permissions_account: Pubkey,
+        system_program:      Pubkey,
+    },
+    SetMaxLatency {
+        args:                SetMaxLatencyArgs,
+        funding_account:     Pubkey,
+        price_account:       Pubkey,
+        permissions_account: Pubkey,
+        additional_signers:  Vec<Pubkey>,
+    },
+    InitPriceFeedIndex {
+        funding_account:     Pubkey,
+        price_account:       Pubkey,
+        permissions_account: Pubkey,
+        additional_signers:  Vec<Pubkey>,
+    },
+    UpdPriceBatch {
+        funding_account:     Pubkey,
+        clock_account:       Pubkey,
+        /// The price accounts paired with their update, in the order of the instruction
+        updates:             Vec<(Pubkey, PriceUpdate)>,
+        publisher_blocklist: Option<Pubkey>,
+        product_accounts:    Vec<Pubkey>,
+    },
+    SetPriceFlags {
+        args:                SetPriceFlagsArgs,
+        funding_account:     Pubkey,
+        price_account:       Pubkey,
+        permissions_account: Pubkey,
+        additional_signers:  Vec<Pubkey>,
+    },
+    SetMultisigAuthority {
+        args:                SetMultisigAuthorityArgs,
+        upgrade_authority:   Pubkey,
+        programdata_account: Pubkey,
+        permissions_account: Pubkey,
+        system_program:      Pubkey,
+        additional_signers:  Vec<Pubkey>,
+    },
+    ProposeAuthorities {
+        args:                ProposeAuthoritiesArgs,
+        upgrade_authority:   Pubkey,
+        programdata_account: Pubkey,
+        permissions_account: Pubkey,
+        system_program:      Pubkey,
+        additional_signers:  Vec<Pubkey>,
+    },
+    AcceptAuthorities {
+        master_authority:    Pubkey,
+        permissions_account: Pubkey,
+    },
+    SetPublisherManager {
+        args:                      SetPublisherManagerArgs,
+        funding_account:           Pubkey,
+        publisher_manager_account: Pubkey,
+        permissions_account:       Pubkey,
+        additional_signers:        Vec<Pubkey>,
+    },
+    SetConfThreshold {
+        args:                SetConfThresholdArgs,
+        funding_account:     Pubkey,
+        price_account:       Pubkey,
+        permissions_account: Pubkey,
+        system_program:      Pubkey,
+        additional_signers:  Vec<Pubkey>,
+    },
+    SetEmaHalfLife {
+        args:                SetEmaHalfLifeArgs,
+        funding_account:     Pubkey,
+        price_account:       Pubkey,
+        permissions_account: Pubkey,
+        system_program:      Pubkey,
+        additional_signers:  Vec<Pubkey>,
+    },
+    SetEmaHorizons {
+        args:                SetEmaHorizonsArgs,
+        funding_account:     Pubkey,
+        price_account:       Pubkey,
+        permissions_account: Pubkey,
+        system_program:      Pubkey,
+        additional_signers:  Vec<Pubkey>,
+    },
+    InitPriceHistory {
+        funding_account:     Pubkey,
+        price_account:       Pubkey,
+        permissions_account: Pubkey,
+        system_program:      Pubkey,
+        additional_signers:  Vec<Pubkey>,
+    },
+    SetPublisherWeight {
+        args:                SetPublisherWeightArgs,
+        funding_account:     Pubkey,
+        price_account:       Pubkey,
+        permissions_account: Pubkey,
+        system_program:      Pubkey,
+        additional_signers:  Vec<Pubkey>,
+    },
+    SetOutlierFilter {
+        args:                SetOutlierFilterArgs,
+        funding_account:     Pubkey,
+        price_account:       Pubkey,
+        permissions_account: Pubkey,
+        system_program:      Pubkey,
+        additional_signers:  Vec<Pubkey>,
+    },
+    InitAggregationDiagnostics {
+        funding_account:     Pubkey,
+        price_account:       Pubkey,
+        permissions_account: Pubkey,
+        system_program:      Pubkey,
+        additional_signers:  Vec<Pubkey>,
+    },
+    InitPublisherStats {
+        funding_account:     Pubkey,
+        price_account:       Pubkey,
+        permissions_account: Pubkey,
+        system_program:      Pubkey,
+        additional_signers:  Vec<Pubkey>,
+    },
+    SetTradingSchedule {
+        funding_account:     Pubkey,
+        product_account:     Pubkey,
+        permissions_account: Pubkey,
+        system_program:      Pubkey,
+        /// The schedule in the format of `TradingSchedule::parse`
+        schedule:            String,
+        additional_signers:  Vec<Pubkey>,
+    },
+    InitAggregateConfidence {
+        funding_account:     Pubkey,
+        price_account:       Pubkey,
+        permissions_account: Pubkey,
+        system_program:      Pubkey,
+        additional_signers:  Vec<Pubkey>,
+    },
+    SetCircuitBreaker {
+        args:                SetCircuitBreakerArgs,
+        funding_account:     Pubkey,
+        price_account:       Pubkey,
+        permissions_account: Pubkey,
+        system_program:      Pubkey,
+        additional_signers:  Vec<Pubkey>,
+    },
+    SetPriceBand {
+        args:                SetPriceBandArgs,
+        funding_account:     Pubkey,
+        price_account:       Pubkey,
+        permissions_account: Pubkey,
+        system_program:      Pubkey,
+        additional_signers:  Vec<Pubkey>,
+    },
+    BlockPublisher {
+        args:                        BlockPublisherArgs,
+        funding_account:             Pubkey,
+        publisher_blocklist_account: Pubkey,
+        permissions_account:         Pubkey,
+        system_program:              Pubkey,
+        additional_signers:          Vec<Pubkey>,
+    },
+    UnblockPublisher {
+        args:                        UnblockPublisherArgs,
+        funding_account:             Pubkey,
+        publisher_blocklist_account: Pubkey,
+        permissions_account:         Pubkey,
+        additional_signers:          Vec<Pubkey>,
+    },
+    RotatePublisher {
+        args:                        RotatePublisherArgs,
+        funding_account:             Pubkey,
+        permissions_account:         Pubkey,
+        publisher_blocklist_account: Pubkey,
+        old_publisher:               Pubkey,
+        new_publisher:               Pubkey,
+        price_accounts:              Vec<Pubkey>,
+        additional_signers:          Vec<Pubkey>,
+    },
+}
+
+/// Read a value of type `T` from the beginning of `data`.
+fn load_args<T: Pod>(data: &[u8]) -> Result<T, DecodeError> {
+    match load::<T>(data) {
+        Ok(args) => Ok(*args),
+        // Unlike the program's input, the data given to the decoder may not be aligned
+        Err(OracleError::InstructionDataSliceMisaligned) => {
+            Ok(pod_read_unaligned(&data[..size_of::<T>()]))
+        }
+        Err(_) => Err(DecodeError::InstructionDataTooShort {
+            expected: size_of::<T>(),
+            actual:   data.len(),
+        }),
+    }
+}
+
+/// Check that there are exactly `N` accounts.
+fn accounts_array<const N: usize>(
+    command: OracleCommand,
+    accounts: &[Pubkey],
+) -> Result<[Pubkey; N], DecodeError> {
+    accounts
+        .try_into()
+        .map_err(|_| DecodeError::InvalidNumberOfAccounts {
+            command,
+            expected: &[N],
+            actual: accounts.len(),
+        })
+}
+
+/// Split the `N` accounts of an administrative command from the additional signers of the
+/// multisig authority that follow them.
+fn admin_accounts<const N: usize>(
+    command: OracleCommand,
+    accounts: &[Pubkey],
+) -> Result<([Pubkey; N], Vec<Pubkey>), DecodeError> {
+    if accounts.len() < N {
+        return Err(DecodeError::InvalidNumberOfAccounts {
+            command,
+            expected: &[N],
+            actual: accounts.len(),
+        });
+    }
+    let (accounts, additional_signers) = accounts.split_at(N);
+    Ok((
+        accounts_array(command, accounts)?,
+        additional_signers.to_vec(),
+    ))
+}
+
+/// The accounts following the permissions account of `AddPublisher` and `DelPublisher`
+struct PublisherSetAccounts {
+    publisher_manager_account: Option<Pubkey>,
+    scope_mapping_account:     Option<Pubkey>,
+    additional_signers:        Vec<Pubkey>,
+}
+
+/// Split the accounts of `AddPublisher` and `DelPublisher`. The accounts following the permissions
+/// account are either the publisher manager account and the mapping account of its scope, which
+/// don't sign, or the additional signers of the multisig authority.
+fn publisher_set_accounts(
+    command: OracleCommand,
+    account_metas: &[AccountMeta],
+) -> Result<([Pubkey; 3], PublisherSetAccounts), DecodeError> {
+    let accounts: Vec<Pubkey> = account_metas.iter().map(|meta| meta.pubkey).collect();
+    let (accounts, additional_signers) = admin_accounts(command, &accounts)?;
+    match &account_metas[accounts.len()..] {
+        [publisher_manager_account, scope_accounts @ ..]
+            if !publisher_manager_account.is_signer =>
+        {
+            let scope_mapping_account = match scope_accounts {
+                [] => None,
+                [mapping_account] => Some(mapping_account.pubkey),
+                _ => {
+                    return Err(DecodeError::InvalidNumberOfAccounts {
+                        command,
+                        expected: &[3, 4, 5],
+                        actual: account_metas.len(),
+                    })
+                }
+            };
+            Ok((
+                accounts,
+                PublisherSetAccounts {
+                    publisher_manager_account: Some(publisher_manager_account.pubkey),
+                    scope_mapping_account,
+                    additional_signers: vec![],
+                },
+            ))
+        }
+        _ => Ok((
+            accounts,
+            PublisherSetAccounts {
+                publisher_manager_account: None,
+                scope_mapping_account: None,
+                additional_signers,
+            },
+        )),
+    }
+}
+
+fn upd_price_accounts(
+    command: OracleCommand,
+    accounts: &[Pubkey],
+) -> Result<UpdPriceAccounts, DecodeError> {
+    match *accounts {
+        [funding_account, price_account, clock_account] => Ok(UpdPriceAccounts {
+            funding_account,
+            price_account,
+            clock_account,
+            message_buffer: None,
+            publisher_blocklist: None,
+            product_account: None,
+        }),
+        [funding_account, price_account, clock_account, ref e @ ..]
+            if e.len() <= 2 && clock::check_id(&clock_account) =>
+        {
+            Ok(UpdPriceAccounts {
+                funding_account,
+                price_account,
+                clock_account,
+                message_buffer: None,
+                publisher_blocklist: e.first().copied(),
+                product_account: e.get(1).copied(),
+            })
+        }
+        // Legacy layout with a superfluous account, see `upd_price`
+        [funding_account, price_account, _, clock_account] => Ok(UpdPriceAccounts {
+            funding_account,
+            price_account,
+            clock_account,
+            message_buffer: None,
+            publisher_blocklist: None,
+            product_account: None,
+        }),
+        [x, y, z, a, b, c, d, ref e @ ..] if e.len() <= 2 => Ok(UpdPriceAccounts {
+            funding_account:     x,
+            price_account:       y,
+            clock_account:       z,
+            message_buffer:      Some(MessageBufferAccounts {
+                program_id:          a,
+                whitelist:           b,
+                oracle_auth_pda:     c,
+                message_buffer_data: d,
+            }),
+            publisher_blocklist: e.first().copied(),
+            product_account:     e.get(1).copied(),
+        }),
+        _ => Err(DecodeError::InvalidNumberOfAccounts {
+            command,
+            expected: &[3, 4, 5, 7, 8, 9],
+            actual: accounts.len(),
+        }),
+    }
+}
+
+/// Parse the list of `pc_str_t` key-value pairs following the header of `UpdProduct`.
+fn product_metadata(data: &[u8]) -> Result<Vec<(String, String)>, DecodeError> {
+    let read_string = |idx: usize| -> Result<(String, usize), DecodeError> {
+        let pc_str =
+            read_pc_str_t(&data[idx..]).map_err(|_| DecodeError::InvalidProductMetadata(idx))?;
+        let string = String::from_utf8(pc_str[1..].to_vec())
+            .map_err(|_| DecodeError::InvalidProductMetadata(idx))?;
+        Ok((string, idx + pc_str.len()))
+    };
+
+    let mut metadata = vec![];
+    let mut idx = 0;
+    while idx < data.len() {
+        let (key, value_idx) = read_string(idx)?;
+        let (value, next_idx) = read_string(value_idx)?;
+        metadata.push((key, value));
+        idx = next_idx;
+    }
+    Ok(metadata)
+}
+
+/// Decode `UpdPriceBatch` from the price updates following its header, paired with the price
+/// accounts following the funding and clock accounts, which may be followed by the publisher
+/// blocklist account and product accounts.
+fn upd_price_batch(data: &[u8], accounts: &[Pubkey]) -> Result<OracleInstruction, DecodeError> {
+    let chunks = data.chunks_exact(size_of::<PriceUpdate>());
+    if !chunks.remainder().is_empty() {
+        return Err(DecodeError::InvalidPriceUpdatesLength(data.len()));
+    }
+    let updates: Vec<PriceUpdate> = chunks.map(pod_read_unaligned).collect();
+
+    match *accounts {
+        [funding_account, clock_account, ref remaining_accounts @ ..]
+            if remaining_accounts.len() >= updates.len() =>
+        {
+            let (price_accounts, optional_accounts) = remaining_accounts.split_at(updates.len());
+            Ok(OracleInstruction::UpdPriceBatch {
+                funding_account,
+                clock_account,
+                updates: price_accounts.iter().copied().zip(updates).collect(),
+                publisher_blocklist: optional_accounts.first().copied(),
+                product_accounts: optional_accounts.iter().skip(1).copied().collect(),
+            })
+        }
+        _ => Err(DecodeError::InvalidNumberOfBatchAccounts {
+            expected: updates.len() + 2,
+            actual:   accounts.len(),
+        }),
+    }
+}
+
+/// Decode an instruction of the oracle program from its data and its accounts. Only the signer
+/// flags of the trailing accounts of `AddPublisher` and `DelPublisher` are used, see
+/// `publisher_set_accounts`.
+pub fn decode_instruction(
+    data: &[u8],
+    account_metas: &[AccountMeta],
+) -> Result<OracleInstruction, DecodeError> {
+    let accounts: Vec<Pubkey> = account_metas.iter().map(|meta| meta.pubkey).collect();
+    let accounts = accounts.as_slice();
+    let header = load_args::<CommandHeader>(data)?;
+    let command = load_command_header_checked(bytes_of(&header)).map_err(|err| match err {
+        OracleError::InvalidInstructionVersion => DecodeError::InvalidVersion(header.version),
+        // The header is aligned and long enough, so this is the only other possible error.
+        _ => DecodeError::UnknownCommand(header.command),
+    })?;
+
+    let instruction = match command {
+        OracleCommand::InitMapping => {
+            let ([funding_account, mapping_account, permissions_account], additional_signers) =
+                admin_accounts(command, accounts)?;
+            OracleInstruction::InitMapping {
+                funding_account,
+                mapping_account,
+                permissions_account,
+                additional_signers,
+            }
+        }
+        OracleCommand::AddMapping => OracleInstruction::AddMapping,
+        OracleCommand::AddProduct => {
+            let (
+                [funding_account, tail_mapping_account, product_account, permissions_account],
+                additional_signers,
+            ) = admin_accounts(command, accounts)?;
+            OracleInstruction::AddProduct {
+                funding_account,
+                tail_mapping_account,
+                product_account,
+                permissions_account,
+                additional_signers,
+            }
+        }
+        OracleCommand::UpdProduct => {
+            let ([funding_account, product_account, permissions_account], additional_signers) =
+                admin_accounts(command, accounts)?;
+            OracleInstruction::UpdProduct {
+                funding_account,
+                product_account,
+                permissions_account,
+                metadata: product_metadata(&data[size_of::<CommandHeader>()..])?,
+                additional_signers,
+            }
+        }
+        OracleCommand::AddPrice => {
+            let (
+                [funding_account, product_account, price_account, permissions_account],
+                additional_signers,
+            ) = admin_accounts(command, accounts)?;
+            OracleInstruction::AddPrice {
+                args: load_args(data)?,
+                funding_account,
+                product_account,
+                price_account,
+                permissions_account,
+                additional_signers,
+            }
+        }
+        OracleCommand::AddPublisher => {
+            let (
+                [funding_account, price_account, permissions_account],
+                PublisherSetAccounts {
+                    publisher_manager_account,
+                    scope_mapping_account,
+                    additional_signers,
+                },
+            ) = publisher_set_accounts(command, account_metas)?;
+            OracleInstruction::AddPublisher {
+                args: load_args(data)?,
+                funding_account,
+                price_account,
+                permissions_account,
+                publisher_manager_account,
+                scope_mapping_account,
+                additional_signers,
+            }
+        }
+        OracleCommand::DelPublisher => {
+            let (
+                [funding_account, price_account, permissions_account],
+                PublisherSetAccounts {
+                    publisher_manager_account,
+                    scope_mapping_account,
+                    additional_signers,
+                },
+            ) = publisher_set_accounts(command, account_metas)?;
+            OracleInstruction::DelPublisher {
+                args: load_args(data)?,
+                funding_account,
+                price_account,
+                permissions_account,
+                publisher_manager_account,
+                scope_mapping_account,
+                additional_signers,
+            }
+        }
+        OracleCommand::UpdPrice => OracleInstruction::UpdPrice {
+            args:     load_args(data)?,
+            accounts: upd_price_accounts(command, accounts)?,
+        },
+        OracleCommand::AggPrice => OracleInstruction::AggPrice {
+            args:     load_args(data)?,
+            accounts: upd_price_accounts(command, accounts)?,
+        },
+        OracleCommand::InitPrice => {
+            let ([funding_account, price_account, permissions_account], additional_signers) =
+                admin_accounts(command, accounts)?;
+            OracleInstruction::InitPrice {
+                args: load_args(data)?,
+                funding_account,
+                price_account,
+                permissions_account,
+                additional_signers,
+            }
+        }
+        OracleCommand::InitTest => OracleInstruction::InitTest,
+        OracleCommand::UpdTest => OracleInstruction::UpdTest,
+        OracleCommand::SetMinPub => {
+            let ([funding_account, price_account, permissions_account], additional_signers) =
+                admin_accounts(command, accounts)?;
+            OracleInstruction::SetMinPub {
+                args: load_args(data)?,
+                funding_account,
+                price_account,
+                permissions_account,
+                additional_signers,
+            }
+        }
+        OracleCommand::UpdPriceNoFailOnError => OracleInstruction::UpdPriceNoFailOnError {
+            args:     load_args(data)?,
+            accounts: upd_price_accounts(command, accounts)?,
+        },
+        OracleCommand::ResizePriceAccount => OracleInstruction::ResizePriceAccount,
+        OracleCommand::DelPrice => {
+            let (
+                [funding_account, product_account, price_account, permissions_account],
+                additional_signers,
+            ) = admin_accounts(command, accounts)?;
+            OracleInstruction::DelPrice {
+                funding_account,
+                product_account,
+                price_account,
+                permissions_account,
+                additional_signers,
+            }
+        }
+        OracleCommand::DelProduct => {
+            let (
+                [funding_account, mapping_account, product_account, permissions_account],
+                additional_signers,
+            ) = admin_accounts(command, accounts)?;
+            OracleInstruction::DelProduct {
+                funding_account,
+                mapping_account,
+                product_account,
+                permissions_account,
+                additional_signers,
+            }
+        }
+        OracleCommand::UpdPermissions => {
+            let [upgrade_authority, programdata_account, permissions_account, system_program] =
+                accounts_array(command, accounts)?;
+            OracleInstruction::UpdPermissions {
+                args: load_args(data)?,
+                upgrade_authority,
+                programdata_account,
+                permissions_account,
+                system_program,
+            }
+        }
+        OracleCommand::SetMaxLatency => {
+            let ([funding_account, price_account, permissions_account], additional_signers) =
+                admin_accounts(command, accounts)?;
+            OracleInstruction::SetMaxLatency {
+                args: load_args(data)?,
+                funding_account,
+                price_account,
+                permissions_account,
+                additional_signers,
+            }
+        }
+        OracleCommand::InitPriceFeedIndex => {
+            let ([funding_account, price_account, permissions_account], additional_signers) =
+                admin_accounts(command, accounts)?;
+            OracleInstruction::InitPriceFeedIndex {
+                funding_account,
+                price_account,
+                permissions_account,
+                additional_signers,
+            }
+        }
+        OracleCommand::UpdPriceBatch => {
+            upd_price_batch(&data[size_of::<CommandHeader>()..], accounts)?
+        }
+        OracleCommand::SetPriceFlags => {
+            let ([funding_account, price_account, permissions_account], additional_signers) =
+                admin_accounts(command, accounts)?;
+            OracleInstruction::SetPriceFlags {
+                args: load_args(data)?,
+                funding_account,
+                price_account,
+                permissions_account,
+                additional_signers,
+            }
+        }
+        OracleCommand::SetMultisigAuthority => {
+            let (
+                [upgrade_authority, programdata_account, permissions_account, system_program],
+                additional_signers,
+            ) = admin_accounts(command, accounts)?;
+            OracleInstruction::SetMultisigAuthority {
+                args: load_args(data)?,
+                upgrade_authority,
+                programdata_account,
+                permissions_account,
+                system_program,
+                additional_signers,
+            }
+        }
+        OracleCommand::ProposeAuthorities => {
+            let (
+                [upgrade_authority, programdata_account, permissions_account, system_program],
+                additional_signers,
+            ) = admin_accounts(command, accounts)?;
+            OracleInstruction::ProposeAuthorities {
+                args: load_args(data)?,
+                upgrade_authority,
+                programdata_account,
+                permissions_account,
+                system_program,
+                additional_signers,
+            }
+        }
+        OracleCommand::AcceptAuthorities => {
+            let [master_authority, permissions_account] = accounts_array(command, accounts)?;
+            OracleInstruction::AcceptAuthorities {
+                master_authority,
+                permissions_account,
+            }
+        }
+        OracleCommand::SetPublisherManager => {
+            let (
+                [funding_account, publisher_manager_account, permissions_account],
+                additional_signers,
+            ) = admin_accounts(command, accounts)?;
+            OracleInstruction::SetPublisherManager {
+                args: load_args(data)?,
+                funding_account,
+                publisher_manager_account,
+                permissions_account,
+                additional_signers,
+            }
+        }
+        OracleCommand::SetConfThreshold => {
+            let (
+                [funding_account, price_account, permissions_account, system_program],
+                additional_signers,
+            ) = admin_accounts(command, accounts)?;
+            OracleInstruction::SetConfThreshold {
+                args: load_args(data)?,
+                funding_account,
+                price_account,
+                permissions_account,
+                system_program,
+                additional_signers,
+            }
+        }
+        OracleCommand::SetEmaHalfLife => {
+            let (
+                [funding_account, price_account, permissions_account, system_program],
+                additional_signers,
+            ) = admin_accounts(command, accounts)?;
+            OracleInstruction::SetEmaHalfLife {
+                args: load_args(data)?,
+                funding_account,
+                price_account,
+                permissions_account,
+                system_program,
+                additional_signers,
+            }
+        }
+        OracleCommand::SetEmaHorizons => {
+            let (
+                [funding_account, price_account, permissions_account, system_program],
+                additional_signers,
+            ) = admin_accounts(command, accounts)?;
+            OracleInstruction::SetEmaHorizons {
+                args: load_args(data)?,
+                funding_account,
+                price_account,
+                permissions_account,
+                system_program,
+                additional_signers,
+            }
+        }
+        OracleCommand::InitPriceHistory => {
+            let (
+                [funding_account, price_account, permissions_account, system_program],
+                additional_signers,
+            ) = admin_accounts(command, accounts)?;
+            OracleInstruction::InitPriceHistory {
+                funding_account,
+                price_account,
+                permissions_account,
+                system_program,
+                additional_signers,
+            }
+        }
+        OracleCommand::SetPublisherWeight => {
+            let (
+                [funding_account, price_account, permissions_account, system_program],
+                additional_signers,
+            ) = admin_accounts(command, accounts)?;
+            OracleInstruction::SetPublisherWeight {
+                args: load_args(data)?,
+                funding_account,
+                price_account,
+                permissions_account,
+                system_program,
+                additional_signers,
+            }
+        }
+        OracleCommand::SetOutlierFilter => {
+            let (
+                [funding_account, price_account, permissions_account, system_program],
+                additional_signers,
+            ) = admin_accounts(command, accounts)?;
+            OracleInstruction::SetOutlierFilter {
+                args: load_args(data)?,
+                funding_account,
+                price_account,
+                permissions_account,
+                system_program,
+                additional_signers,
+            }
+        }
+        OracleCommand::InitAggregationDiagnostics => {
+            let (
+                [funding_account, price_account, permissions_account, system_program],
+                additional_signers,
+            ) = admin_accounts(command, accounts)?;
+            OracleInstruction::InitAggregationDiagnostics {
+                funding_account,
+                price_account,
+                permissions_account,
+                system_program,
+                additional_signers,
+            }
+        }
+        OracleCommand::InitPublisherStats => {
+            let (
+                [funding_account, price_account, permissions_account, system_program],
+                additional_signers,
+            ) = admin_accounts(command, accounts)?;
+            OracleInstruction::InitPublisherStats {
+                funding_account,
+                price_account,
+                permissions_account,
+                system_program,
+                additional_signers,
+            }
+        }
+        OracleCommand::SetTradingSchedule => {
+            let (
+                [funding_account, product_account, permissions_account, system_program],
+                additional_signers,
+            ) = admin_accounts(command, accounts)?;
+            OracleInstruction::SetTradingSchedule {
+                funding_account,
+                product_account,
+                permissions_account,
+                system_program,
+                schedule: String::from_utf8(data[size_of::<CommandHeader>()..].to_vec())
+                    .map_err(|_| DecodeError::InvalidTradingSchedule)?,
+                additional_signers,
+            }
+        }
+        OracleCommand::InitAggregateConfidence => {
+            let (
+                [funding_account, price_account, permissions_account, system_program],
+                additional_signers,
+            ) = admin_accounts(command, accounts)?;
+            OracleInstruction::InitAggregateConfidence {
+                funding_account,
+                price_account,
+                permissions_account,
+                system_program,
+                additional_signers,
+            }
+        }
+        OracleCommand::SetCircuitBreaker => {
+            let (
+                [funding_account, price_account, permissions_account, system_program],
+                additional_signers,
+            ) = admin_accounts(command, accounts)?;
+            OracleInstruction::SetCircuitBreaker {
+                args: load_args(data)?,
+                funding_account,
+                price_account,
+                permissions_account,
+                system_program,
+                additional_signers,
+            }
+        }
+        OracleCommand::SetPriceBand => {
+            let (
+                [funding_account, price_account, permissions_account, system_program],
+                additional_signers,
+            ) = admin_accounts(command, accounts)?;
+            OracleInstruction::SetPriceBand {
+                args: load_args(data)?,
+                funding_account,
+                price_account,
+                permissions_account,
+                system_program,
+                additional_signers,
+            }
+        }
+        OracleCommand::BlockPublisher => {
+            let (
+                [funding_account, publisher_blocklist_account, permissions_account, system_program],
+                additional_signers,
+            ) = admin_accounts(command, accounts)?;
+            OracleInstruction::BlockPublisher {
+                args: load_args(data)?,
+                funding_account,
+                publisher_blocklist_account,
+                permissions_account,
+                system_program,
+                additional_signers,
+            }
+        }
+        OracleCommand::UnblockPublisher => {
+            let (
+                [funding_account, publisher_blocklist_account, permissions_account],
+                additional_signers,
+            ) = admin_accounts(command, accounts)?;
+            OracleInstruction::UnblockPublisher {
+                args: load_args(data)?,
+                funding_account,
+                publisher_blocklist_account,
+                permissions_account,
+                additional_signers,
+            }
+        }
+        OracleCommand::RotatePublisher => {
+            let args: RotatePublisherArgs = load_args(data)?;
+            let ([x, p, b, o, n], mut price_accounts) = admin_accounts(command, accounts)?;
+            let num_price_accounts = args.num_price_accounts as usize;
+            if price_accounts.len() < num_price_accounts {
+                return Err(DecodeError::InvalidNumberOfPriceAccounts {
+                    expected: num_price_accounts,
+                    actual:   price_accounts.len(),
+                });
+            }
+            let additional_signers = price_accounts.split_off(num_price_accounts);
+            OracleInstruction::RotatePublisher {
+                args,
+                funding_account: x,
+                permissions_account: p,
+                publisher_blocklist_account: b,
+                old_publisher: o,
+                new_publisher: n,
+                price_accounts,
+                additional_signers,
+            }
+        }
+    };
+    Ok(instruction)
+}
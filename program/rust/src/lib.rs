@@ -2,13 +2,19 @@
 #![allow(non_upper_case_globals)]
 
 mod accounts;
+mod aggregation;
 mod c_oracle_header;
 mod deserialize;
 mod error;
+#[cfg(not(feature = "library"))]
 mod instruction;
 mod processor;
 mod utils;
 
+#[cfg(feature = "library")]
+pub mod instruction;
+#[cfg(any(test, feature = "library"))]
+pub mod twap;
 #[cfg(any(test, feature = "library"))]
 pub mod validator;
 
@@ -34,21 +40,45 @@ pub use accounts::MessageType;
 #[cfg(feature = "library")]
 pub use accounts::{
     AccountHeader,
+    AggregateConfidence,
+    AggregateConfidenceMessage,
+    AggregationDiagnostics,
+    CircuitBreaker,
+    CorpActStatusMessage,
+    ExclusionReason,
     MappingAccount,
+    OutlierFilter,
+    OutlierFilterMode,
     PermissionAccount,
     PriceAccount,
     PriceAccountFlags,
+    PriceBand,
     PriceComponent,
     PriceEma,
+    PriceHistory,
+    PriceHistoryEntry,
     PriceInfo,
     ProductAccount,
+    PublisherBlocklistAccount,
+    PublisherStat,
+    PublisherStats,
+    PublisherStatsMessage,
+    PublisherWeight,
+    PublisherWeights,
     PythAccount,
     PythOracleSerialize,
+    TradingInterval,
+    TradingSchedule,
+    PUBLISHER_DEVIATION_SCALE,
 };
 #[cfg(feature = "library")]
 pub use {
     processor::find_publisher_index,
-    utils::get_status_for_conf_price_ratio,
+    utils::{
+        get_status_for_conf_divisor,
+        get_status_for_conf_price_ratio,
+        get_status_for_price_band,
+    },
 };
 use {
     processor::process_instruction,
@@ -66,4 +96,9 @@ use {
 // We also generate bindings for the constants in oracle.h (as well as other things
 // included in bindings.h).
 
+// The aggregation logic (`upd_aggregate.h`) also has a pure Rust implementation in
+// `aggregation/rust.rs`. Enabling the `rust-aggregation` feature uses it instead of the C
+// code, in which case the program doesn't link the C aggregation, which the native build still
+// links for the tests comparing both implementations.
+
 entrypoint!(process_instruction);
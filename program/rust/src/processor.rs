@@ -3,66 +3,115 @@ use {
         accounts::{
             AccountHeader,
             PermissionAccount,
+            PriceAccount,
+            PriceExtensionFlags,
             PythAccount,
             MAX_FEED_INDEX,
         },
-        deserialize::load_account_as_mut,
+        deserialize::{
+            load_account_as_mut,
+            load_checked,
+            load_extension_mut,
+        },
         error::OracleError,
         instruction::{
             load_command_header_checked,
+            CommandHeader,
             OracleCommand,
         },
         utils::{
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            get_rent,
             pyth_assert,
+            send_lamports,
             try_convert,
         },
     },
+    bytemuck::Pod,
     solana_program::{
         entrypoint::ProgramResult,
         pubkey::Pubkey,
+        system_program::check_id,
         sysvar::slot_history::AccountInfo,
     },
+    std::mem::size_of,
 };
 
+mod accept_authorities;
 mod add_price;
 mod add_product;
 mod add_publisher;
+mod block_publisher;
 mod del_price;
 mod del_product;
 mod del_publisher;
+mod init_aggregate_confidence;
+mod init_aggregation_diagnostics;
 mod init_mapping;
 mod init_price;
 mod init_price_feed_index;
+mod init_price_history;
+mod init_publisher_stats;
+mod propose_authorities;
+mod rotate_publisher;
+mod set_circuit_breaker;
+mod set_conf_threshold;
+mod set_ema_half_life;
+mod set_ema_horizons;
 mod set_max_latency;
 mod set_min_pub;
+mod set_multisig_authority;
+mod set_outlier_filter;
+mod set_price_band;
+mod set_price_flags;
+mod set_publisher_manager;
+mod set_publisher_weight;
+mod set_trading_schedule;
+mod unblock_publisher;
 mod upd_permissions;
 mod upd_price;
+mod upd_price_batch;
 mod upd_product;
 
-#[cfg(test)]
-pub use add_publisher::{
-    DISABLE_ACCUMULATOR_V2,
-    ENABLE_ACCUMULATOR_V2,
-};
 pub use {
+    accept_authorities::accept_authorities,
     add_price::add_price,
     add_product::add_product,
     add_publisher::add_publisher,
+    block_publisher::block_publisher,
     del_price::del_price,
     del_product::del_product,
     del_publisher::del_publisher,
+    init_aggregate_confidence::init_aggregate_confidence,
+    init_aggregation_diagnostics::init_aggregation_diagnostics,
     init_mapping::init_mapping,
     init_price::init_price,
+    init_price_history::init_price_history,
+    init_publisher_stats::init_publisher_stats,
+    propose_authorities::propose_authorities,
+    rotate_publisher::rotate_publisher,
+    set_circuit_breaker::set_circuit_breaker,
+    set_conf_threshold::set_conf_threshold,
+    set_ema_half_life::set_ema_half_life,
+    set_ema_horizons::set_ema_horizons,
     set_max_latency::set_max_latency,
     set_min_pub::set_min_pub,
+    set_multisig_authority::set_multisig_authority,
+    set_outlier_filter::set_outlier_filter,
+    set_price_band::set_price_band,
+    set_price_flags::set_price_flags,
+    set_publisher_manager::set_publisher_manager,
+    set_publisher_weight::set_publisher_weight,
+    set_trading_schedule::set_trading_schedule,
+    unblock_publisher::unblock_publisher,
     upd_permissions::upd_permissions,
     upd_price::{
-        c_upd_aggregate,
-        c_upd_twap,
         find_publisher_index,
         upd_price,
         upd_price_no_fail_on_error,
     },
+    upd_price_batch::upd_price_batch,
     upd_product::upd_product,
 };
 use {
@@ -106,6 +155,31 @@ pub fn process_instruction(
         UpdPermissions => upd_permissions(program_id, accounts, instruction_data),
         SetMaxLatency => set_max_latency(program_id, accounts, instruction_data),
         InitPriceFeedIndex => init_price_feed_index(program_id, accounts, instruction_data),
+        UpdPriceBatch => upd_price_batch(program_id, accounts, instruction_data),
+        SetPriceFlags => set_price_flags(program_id, accounts, instruction_data),
+        SetMultisigAuthority => set_multisig_authority(program_id, accounts, instruction_data),
+        ProposeAuthorities => propose_authorities(program_id, accounts, instruction_data),
+        AcceptAuthorities => accept_authorities(program_id, accounts, instruction_data),
+        SetPublisherManager => set_publisher_manager(program_id, accounts, instruction_data),
+        SetConfThreshold => set_conf_threshold(program_id, accounts, instruction_data),
+        SetEmaHalfLife => set_ema_half_life(program_id, accounts, instruction_data),
+        SetEmaHorizons => set_ema_horizons(program_id, accounts, instruction_data),
+        InitPriceHistory => init_price_history(program_id, accounts, instruction_data),
+        SetPublisherWeight => set_publisher_weight(program_id, accounts, instruction_data),
+        SetOutlierFilter => set_outlier_filter(program_id, accounts, instruction_data),
+        InitAggregationDiagnostics => {
+            init_aggregation_diagnostics(program_id, accounts, instruction_data)
+        }
+        InitPublisherStats => init_publisher_stats(program_id, accounts, instruction_data),
+        SetTradingSchedule => set_trading_schedule(program_id, accounts, instruction_data),
+        InitAggregateConfidence => {
+            init_aggregate_confidence(program_id, accounts, instruction_data)
+        }
+        SetCircuitBreaker => set_circuit_breaker(program_id, accounts, instruction_data),
+        SetPriceBand => set_price_band(program_id, accounts, instruction_data),
+        BlockPublisher => block_publisher(program_id, accounts, instruction_data),
+        UnblockPublisher => unblock_publisher(program_id, accounts, instruction_data),
+        RotatePublisher => rotate_publisher(program_id, accounts, instruction_data),
     }
 }
 
@@ -131,3 +205,93 @@ fn reserve_new_price_feed_index(permissions_account: &AccountInfo) -> Result<u32
     )?;
     Ok(*last_feed_index)
 }
+
+/// Grow the permissions account to `new_size` bytes, zeroing the new bytes, if it is smaller.
+/// The funding account pays for the additional rent.
+fn resize_permissions_account<'a>(
+    permissions_account: &AccountInfo<'a>,
+    funding_account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    new_size: usize,
+) -> Result<(), ProgramError> {
+    if permissions_account.data_len() < new_size {
+        resize_account(
+            permissions_account,
+            funding_account,
+            system_program,
+            new_size,
+        )?;
+        let mut header = load_account_as_mut::<AccountHeader>(permissions_account)?;
+        header.size = try_convert(new_size)?;
+    }
+    Ok(())
+}
+
+/// Grow `account` to `new_size` bytes, zeroing the new bytes, if it is smaller. The funding
+/// account pays for the additional rent. Unlike `resize_permissions_account`, the size in the
+/// header of the account is left unchanged.
+fn resize_account<'a>(
+    account: &AccountInfo<'a>,
+    funding_account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    new_size: usize,
+) -> Result<(), ProgramError> {
+    if account.data_len() < new_size {
+        let new_minimum_balance = get_rent()?.minimum_balance(new_size);
+        if account.lamports() < new_minimum_balance {
+            send_lamports(
+                funding_account,
+                account,
+                system_program,
+                new_minimum_balance - account.lamports(),
+            )?;
+        }
+        account.realloc(new_size, true)?;
+    }
+    Ok(())
+}
+
+/// Update the extension of type `T` of a price account with `write`. The price account is resized
+/// to store the extension if needed, the funding account paying for the additional rent, and
+/// `extension` is enabled at its offset, see `PriceAccount::enable_extension`. This is shared by the instructions configuring the extensions
+/// of price accounts, whose accounts are:
+// account[0] funding account       [signer writable]
+// account[1] price account         [writable]
+// account[2] permissions account   []
+// account[3] system program        []
+// account[4..] additional signers  [signer]
+fn update_price_extension<T: Pod>(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    cmd: &CommandHeader,
+    extension: PriceExtensionFlags,
+    write: impl FnOnce(&mut T) -> ProgramResult,
+) -> ProgramResult {
+    let (funding_account, price_account, permissions_account, system_program, additional_signers) =
+        match accounts {
+            [x, y, p, s, signers @ ..] => Ok((x, y, p, s, signers)),
+            _ => Err(OracleError::InvalidNumberOfAccounts),
+        }?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        price_account,
+        funding_account,
+        permissions_account,
+        additional_signers,
+        cmd,
+    )?;
+    pyth_assert(
+        check_id(system_program.key),
+        OracleError::InvalidSystemAccount.into(),
+    )?;
+
+    load_checked::<PriceAccount>(price_account, cmd.version)?;
+    let space =
+        PriceAccount::extension_space(&price_account.try_borrow_data()?, extension, size_of::<T>());
+    resize_account(price_account, funding_account, system_program, space)?;
+    let offset = PriceAccount::enable_extension(price_account, extension, size_of::<T>())?;
+    let mut extension = load_extension_mut::<T>(price_account, offset)?;
+    write(&mut *extension)
+}
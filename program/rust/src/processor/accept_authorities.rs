@@ -0,0 +1,76 @@
+use {
+    crate::{
+        accounts::{
+            PendingAuthorities,
+            PermissionAccount,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::CommandHeader,
+        utils::{
+            check_valid_funding_account,
+            check_valid_permissions_account,
+            check_valid_writable_account,
+            pyth_assert,
+        },
+        OracleError,
+    },
+    bytemuck::Zeroable,
+    solana_program::{
+        account_info::AccountInfo,
+        clock::Clock,
+        entrypoint::ProgramResult,
+        pubkey::Pubkey,
+        sysvar::Sysvar,
+    },
+};
+
+/// Replace the authorities of the permissions account with the authorities proposed by
+/// `propose_authorities`, along with the minimum delay of the next proposals. Must be signed by the
+/// proposed master authority once the delay of the proposal has passed.
+// key[0] proposed master authority [signer writable]
+// key[1] permissions account       [writable]
+pub fn accept_authorities(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let [funding_account, permissions_account] = match accounts {
+        [x, y] => Ok([x, y]),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    let cmd_args = load::<CommandHeader>(instruction_data)?;
+
+    check_valid_funding_account(funding_account)?;
+    check_valid_permissions_account(program_id, permissions_account)?;
+    check_valid_writable_account(program_id, permissions_account)?;
+
+    let pending_authorities = PermissionAccount::load_pending_authorities(permissions_account)?
+        .ok_or(OracleError::NoPendingAuthorities)?;
+    pyth_assert(
+        pending_authorities.master_authority == *funding_account.key,
+        OracleError::PermissionViolation.into(),
+    )?;
+    pyth_assert(
+        Clock::get()?.slot >= pending_authorities.accept_slot,
+        OracleError::AuthorityTransferLocked.into(),
+    )?;
+
+    {
+        let mut permissions_account_data =
+            load_checked::<PermissionAccount>(permissions_account, cmd_args.version)?;
+        permissions_account_data.master_authority = pending_authorities.master_authority;
+        permissions_account_data.data_curation_authority =
+            pending_authorities.data_curation_authority;
+        permissions_account_data.security_authority = pending_authorities.security_authority;
+    }
+    *PermissionAccount::load_pending_authorities_mut(permissions_account)? = PendingAuthorities {
+        min_delay_slots: pending_authorities.proposed_min_delay_slots,
+        ..PendingAuthorities::zeroed()
+    };
+
+    Ok(())
+}
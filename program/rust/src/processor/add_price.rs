@@ -52,10 +52,11 @@ pub fn add_price(
     )?;
 
 
-    let (funding_account, product_account, price_account, permissions_account) = match accounts {
-        [x, y, z, p] => Ok((x, y, z, p)),
-        _ => Err(OracleError::InvalidNumberOfAccounts),
-    }?;
+    let (funding_account, product_account, price_account, permissions_account, additional_signers) =
+        match accounts {
+            [x, y, z, p, signers @ ..] => Ok((x, y, z, p, signers)),
+            _ => Err(OracleError::InvalidNumberOfAccounts),
+        }?;
 
     check_valid_funding_account(funding_account)?;
     check_permissioned_funding_account(
@@ -63,6 +64,7 @@ pub fn add_price(
         product_account,
         funding_account,
         permissions_account,
+        additional_signers,
         &cmd_args.header,
     )?;
     check_permissioned_funding_account(
@@ -70,6 +72,7 @@ pub fn add_price(
         price_account,
         funding_account,
         permissions_account,
+        additional_signers,
         &cmd_args.header,
     )?;
     check_valid_writable_account(program_id, permissions_account)?;
@@ -41,11 +41,16 @@ pub fn add_product(
     accounts: &[AccountInfo],
     instruction_data: &[u8],
 ) -> ProgramResult {
-    let (funding_account, tail_mapping_account, new_product_account, permissions_account) =
-        match accounts {
-            [x, y, z, p] => Ok((x, y, z, p)),
-            _ => Err(OracleError::InvalidNumberOfAccounts),
-        }?;
+    let (
+        funding_account,
+        tail_mapping_account,
+        new_product_account,
+        permissions_account,
+        additional_signers,
+    ) = match accounts {
+        [x, y, z, p, signers @ ..] => Ok((x, y, z, p, signers)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
 
     let hdr = load::<CommandHeader>(instruction_data)?;
 
@@ -55,6 +60,7 @@ pub fn add_product(
         tail_mapping_account,
         funding_account,
         permissions_account,
+        additional_signers,
         hdr,
     )?;
     check_permissioned_funding_account(
@@ -62,6 +68,7 @@ pub fn add_product(
         new_product_account,
         funding_account,
         permissions_account,
+        additional_signers,
         hdr,
     )?;
 
@@ -2,7 +2,6 @@ use {
     crate::{
         accounts::{
             PriceAccount,
-            PriceAccountFlags,
             PriceComponent,
             PythAccount,
         },
@@ -13,7 +12,7 @@ use {
         },
         instruction::AddPublisherArgs,
         utils::{
-            check_permissioned_funding_account,
+            check_publisher_set_authority,
             check_valid_funding_account,
             pyth_assert,
             try_convert,
@@ -34,16 +33,13 @@ use {
     std::mem::size_of,
 };
 
-pub const ENABLE_ACCUMULATOR_V2: [u8; 32] = [
-    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1,
-];
-pub const DISABLE_ACCUMULATOR_V2: [u8; 32] = [
-    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2,
-];
-
-/// Add publisher to symbol account
+/// Add publisher to symbol account.
+/// The funding account is either permissioned by the permissions account or the publisher manager
+/// of the price account, in which case the publisher manager account and the mapping account of
+/// its scope, if any, follow the permissions account.
 // account[0] funding account       [signer writable]
 // account[1] price account         [signer writable]
+// account[2] permissions account   []
 pub fn add_publisher(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -56,36 +52,23 @@ pub fn add_publisher(
         ProgramError::InvalidArgument,
     )?;
 
-    let (funding_account, price_account, permissions_account) = match accounts {
-        [x, y, p] => Ok((x, y, p)),
+    let (funding_account, price_account, permissions_account, remaining_accounts) = match accounts {
+        [x, y, p, remaining_accounts @ ..] => Ok((x, y, p, remaining_accounts)),
         _ => Err(OracleError::InvalidNumberOfAccounts),
     }?;
 
     check_valid_funding_account(funding_account)?;
-    check_permissioned_funding_account(
+    check_publisher_set_authority(
         program_id,
         price_account,
         funding_account,
         permissions_account,
+        remaining_accounts,
         &cmd_args.header,
     )?;
 
     let mut price_data = load_checked::<PriceAccount>(price_account, cmd_args.header.version)?;
 
-    if cmd_args.publisher == Pubkey::from(ENABLE_ACCUMULATOR_V2) {
-        // Hack: we use add_publisher instruction to configure the `ACCUMULATOR_V2` flag. Using a new
-        // instruction would be cleaner but it would require more work in the tooling.
-        // These special cases can be removed along with the v1 aggregation code once the transition
-        // is complete.
-        price_data.flags.insert(PriceAccountFlags::ACCUMULATOR_V2);
-        return Ok(());
-    } else if cmd_args.publisher == Pubkey::from(DISABLE_ACCUMULATOR_V2) {
-        price_data
-            .flags
-            .remove(PriceAccountFlags::ACCUMULATOR_V2 | PriceAccountFlags::MESSAGE_BUFFER_CLEARED);
-        return Ok(());
-    }
-
     if price_data.num_ >= PC_NUM_COMP {
         return Err(ProgramError::InvalidArgument);
     }
@@ -165,7 +148,10 @@ fn heapsort(v: &mut [(Pubkey, usize)]) {
 ///
 /// num_publishers is the number of publishers in the list that should be sorted. It is explicitly
 /// passed to avoid callers mistake of passing the full slice which may contain uninitialized values.
-fn sort_price_comps(comps: &mut [PriceComponent], num_comps: usize) -> Result<(), ProgramError> {
+pub fn sort_price_comps(
+    comps: &mut [PriceComponent],
+    num_comps: usize,
+) -> Result<(), ProgramError> {
     let comps = comps
         .get_mut(..num_comps)
         .ok_or(ProgramError::InvalidArgument)?;
@@ -0,0 +1,108 @@
+use {
+    crate::{
+        accounts::{
+            PublisherBlocklistAccount,
+            PythAccount,
+            MAX_BLOCKED_PUBLISHERS,
+            PUBLISHER_BLOCKLIST_SEED,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::BlockPublisherArgs,
+        utils::{
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            pyth_assert,
+            try_convert,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+        system_program::check_id,
+    },
+    std::mem::size_of,
+};
+
+/// Add a publisher to the publisher blocklist, creating the blocklist account if it doesn't
+/// exist yet. Fails if the publisher is already blocked or if the blocklist is full.
+// account[0] funding account             [signer writable]
+// account[1] publisher blocklist account [writable]
+// account[2] permissions account         []
+// account[3] system program              []
+pub fn block_publisher(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd_args = load::<BlockPublisherArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<BlockPublisherArgs>()
+            && cmd_args.publisher != Pubkey::default(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (
+        funding_account,
+        publisher_blocklist_account,
+        permissions_account,
+        system_program,
+        additional_signers,
+    ) = match accounts {
+        [x, y, p, s, signers @ ..] => Ok((x, y, p, s, signers)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    check_valid_funding_account(funding_account)?;
+    let (publisher_blocklist_pda_address, bump_seed) =
+        Pubkey::find_program_address(&[PUBLISHER_BLOCKLIST_SEED.as_bytes()], program_id);
+    pyth_assert(
+        publisher_blocklist_pda_address == *publisher_blocklist_account.key,
+        OracleError::InvalidPda.into(),
+    )?;
+    pyth_assert(
+        check_id(system_program.key),
+        OracleError::InvalidSystemAccount.into(),
+    )?;
+
+    // Create the blocklist account if it doesn't exist, the permission check below reverts the
+    // creation if the funding account isn't authorized
+    PublisherBlocklistAccount::initialize_pda(
+        publisher_blocklist_account,
+        funding_account,
+        system_program,
+        program_id,
+        &[PUBLISHER_BLOCKLIST_SEED.as_bytes(), &[bump_seed]],
+        cmd_args.header.version,
+    )?;
+    check_permissioned_funding_account(
+        program_id,
+        publisher_blocklist_account,
+        funding_account,
+        permissions_account,
+        additional_signers,
+        &cmd_args.header,
+    )?;
+
+    let mut publisher_blocklist = load_checked::<PublisherBlocklistAccount>(
+        publisher_blocklist_account,
+        cmd_args.header.version,
+    )?;
+    let num_publishers: usize = try_convert(publisher_blocklist.num_publishers)?;
+    pyth_assert(
+        num_publishers < MAX_BLOCKED_PUBLISHERS
+            && !publisher_blocklist.is_blocked(&cmd_args.publisher),
+        ProgramError::InvalidArgument,
+    )?;
+    publisher_blocklist.publishers[num_publishers] = cmd_args.publisher;
+    publisher_blocklist.num_publishers += 1;
+    publisher_blocklist.bump = bump_seed;
+
+    Ok(())
+}
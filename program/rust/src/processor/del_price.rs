@@ -37,10 +37,11 @@ pub fn del_price(
     accounts: &[AccountInfo],
     instruction_data: &[u8],
 ) -> ProgramResult {
-    let (funding_account, product_account, price_account, permissions_account) = match accounts {
-        [w, x, y, p] => Ok((w, x, y, p)),
-        _ => Err(OracleError::InvalidNumberOfAccounts),
-    }?;
+    let (funding_account, product_account, price_account, permissions_account, additional_signers) =
+        match accounts {
+            [w, x, y, p, signers @ ..] => Ok((w, x, y, p, signers)),
+            _ => Err(OracleError::InvalidNumberOfAccounts),
+        }?;
 
     let cmd_args = load::<CommandHeader>(instruction_data)?;
 
@@ -50,6 +51,7 @@ pub fn del_price(
         product_account,
         funding_account,
         permissions_account,
+        additional_signers,
         cmd_args,
     )?;
     check_permissioned_funding_account(
@@ -57,6 +59,7 @@ pub fn del_price(
         price_account,
         funding_account,
         permissions_account,
+        additional_signers,
         cmd_args,
     )?;
 
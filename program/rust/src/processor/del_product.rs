@@ -44,8 +44,14 @@ pub fn del_product(
     accounts: &[AccountInfo],
     instruction_data: &[u8],
 ) -> ProgramResult {
-    let (funding_account, mapping_account, product_account, permissions_account) = match accounts {
-        [w, x, y, p] => Ok((w, x, y, p)),
+    let (
+        funding_account,
+        mapping_account,
+        product_account,
+        permissions_account,
+        additional_signers,
+    ) = match accounts {
+        [w, x, y, p, signers @ ..] => Ok((w, x, y, p, signers)),
         _ => Err(OracleError::InvalidNumberOfAccounts),
     }?;
 
@@ -57,6 +63,7 @@ pub fn del_product(
         mapping_account,
         funding_account,
         permissions_account,
+        additional_signers,
         cmd_args,
     )?;
 
@@ -65,6 +72,7 @@ pub fn del_product(
         product_account,
         funding_account,
         permissions_account,
+        additional_signers,
         cmd_args,
     )?;
 
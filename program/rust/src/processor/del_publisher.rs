@@ -11,7 +11,7 @@ use {
         },
         instruction::DelPublisherArgs,
         utils::{
-            check_permissioned_funding_account,
+            check_publisher_set_authority,
             check_valid_funding_account,
             pyth_assert,
             try_convert,
@@ -29,9 +29,13 @@ use {
     std::mem::size_of,
 };
 
-/// Delete publisher from symbol account
+/// Delete publisher from symbol account.
+/// The funding account is either permissioned by the permissions account or the publisher manager
+/// of the price account, in which case the publisher manager account and the mapping account of
+/// its scope, if any, follow the permissions account.
 // account[0] funding account       [signer writable]
 // account[1] price account         [signer writable]
+// account[2] permissions account   []
 pub fn del_publisher(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -45,17 +49,18 @@ pub fn del_publisher(
         ProgramError::InvalidArgument,
     )?;
 
-    let (funding_account, price_account, permissions_account) = match accounts {
-        [x, y, p] => Ok((x, y, p)),
+    let (funding_account, price_account, permissions_account, remaining_accounts) = match accounts {
+        [x, y, p, remaining_accounts @ ..] => Ok((x, y, p, remaining_accounts)),
         _ => Err(OracleError::InvalidNumberOfAccounts),
     }?;
 
     check_valid_funding_account(funding_account)?;
-    check_permissioned_funding_account(
+    check_publisher_set_authority(
         program_id,
         price_account,
         funding_account,
         permissions_account,
+        remaining_accounts,
         &cmd_args.header,
     )?;
 
@@ -0,0 +1,38 @@
+use {
+    super::update_price_extension,
+    crate::{
+        accounts::{
+            AggregateConfidence,
+            PriceExtensionFlags,
+        },
+        deserialize::load,
+        instruction::CommandHeader,
+    },
+    bytemuck::Zeroable,
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        pubkey::Pubkey,
+    },
+};
+
+/// Start recording both sides of the confidence interval of the aggregates of a price account, or
+/// clear them if it already records them, see `update_price_extension` for the accounts.
+pub fn init_aggregate_confidence(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<CommandHeader>(instruction_data)?;
+
+    update_price_extension(
+        program_id,
+        accounts,
+        cmd,
+        PriceExtensionFlags::AGGREGATE_CONFIDENCE,
+        |extension: &mut AggregateConfidence| {
+            *extension = AggregateConfidence::zeroed();
+            Ok(())
+        },
+    )
+}
@@ -0,0 +1,38 @@
+use {
+    super::update_price_extension,
+    crate::{
+        accounts::{
+            AggregationDiagnostics,
+            PriceExtensionFlags,
+        },
+        deserialize::load,
+        instruction::CommandHeader,
+    },
+    bytemuck::Zeroable,
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        pubkey::Pubkey,
+    },
+};
+
+/// Initialize the aggregation diagnostics of a price account, or clear them if it already has
+/// some. They are filled by the next aggregation. See `update_price_extension` for the accounts.
+pub fn init_aggregation_diagnostics(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<CommandHeader>(instruction_data)?;
+
+    update_price_extension(
+        program_id,
+        accounts,
+        cmd,
+        PriceExtensionFlags::AGGREGATION_DIAGNOSTICS,
+        |extension: &mut AggregationDiagnostics| {
+            *extension = AggregationDiagnostics::zeroed();
+            Ok(())
+        },
+    )
+}
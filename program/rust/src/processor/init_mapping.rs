@@ -27,10 +27,11 @@ pub fn init_mapping(
     accounts: &[AccountInfo],
     instruction_data: &[u8],
 ) -> ProgramResult {
-    let (funding_account, fresh_mapping_account, permissions_account) = match accounts {
-        [x, y, p] => Ok((x, y, p)),
-        _ => Err(OracleError::InvalidNumberOfAccounts),
-    }?;
+    let (funding_account, fresh_mapping_account, permissions_account, additional_signers) =
+        match accounts {
+            [x, y, p, signers @ ..] => Ok((x, y, p, signers)),
+            _ => Err(OracleError::InvalidNumberOfAccounts),
+        }?;
 
     let hdr = load::<CommandHeader>(instruction_data)?;
 
@@ -40,6 +41,7 @@ pub fn init_mapping(
         fresh_mapping_account,
         funding_account,
         permissions_account,
+        additional_signers,
         hdr,
     )?;
 
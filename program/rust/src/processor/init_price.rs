@@ -41,8 +41,8 @@ pub fn init_price(
 
     check_exponent_range(cmd_args.exponent)?;
 
-    let (funding_account, price_account, permissions_account) = match accounts {
-        [x, y, p] => Ok((x, y, p)),
+    let (funding_account, price_account, permissions_account, additional_signers) = match accounts {
+        [x, y, p, signers @ ..] => Ok((x, y, p, signers)),
         _ => Err(OracleError::InvalidNumberOfAccounts),
     }?;
 
@@ -52,6 +52,7 @@ pub fn init_price(
         price_account,
         funding_account,
         permissions_account,
+        additional_signers,
         &cmd_args.header,
     )?;
 
@@ -40,8 +40,8 @@ pub fn init_price_feed_index(
         ProgramError::InvalidArgument,
     )?;
 
-    let (funding_account, price_account, permissions_account) = match accounts {
-        [x, y, p] => Ok((x, y, p)),
+    let (funding_account, price_account, permissions_account, additional_signers) = match accounts {
+        [x, y, p, signers @ ..] => Ok((x, y, p, signers)),
         _ => Err(OracleError::InvalidNumberOfAccounts),
     }?;
 
@@ -51,6 +51,7 @@ pub fn init_price_feed_index(
         price_account,
         funding_account,
         permissions_account,
+        additional_signers,
         cmd,
     )?;
     check_valid_writable_account(program_id, permissions_account)?;
@@ -0,0 +1,38 @@
+use {
+    super::update_price_extension,
+    crate::{
+        accounts::{
+            PriceExtensionFlags,
+            PriceHistory,
+        },
+        deserialize::load,
+        instruction::CommandHeader,
+    },
+    bytemuck::Zeroable,
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        pubkey::Pubkey,
+    },
+};
+
+/// Initialize the price history of a price account, or clear it if it already has one, see
+/// `update_price_extension` for the accounts.
+pub fn init_price_history(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<CommandHeader>(instruction_data)?;
+
+    update_price_extension(
+        program_id,
+        accounts,
+        cmd,
+        PriceExtensionFlags::PRICE_HISTORY,
+        |price_history: &mut PriceHistory| {
+            *price_history = PriceHistory::zeroed();
+            Ok(())
+        },
+    )
+}
@@ -0,0 +1,38 @@
+use {
+    super::update_price_extension,
+    crate::{
+        accounts::{
+            PriceExtensionFlags,
+            PublisherStats,
+        },
+        deserialize::load,
+        instruction::CommandHeader,
+    },
+    bytemuck::Zeroable,
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        pubkey::Pubkey,
+    },
+};
+
+/// Initialize the publisher statistics of a price account, or reset them if it already has
+/// some, see `update_price_extension` for the accounts.
+pub fn init_publisher_stats(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<CommandHeader>(instruction_data)?;
+
+    update_price_extension(
+        program_id,
+        accounts,
+        cmd,
+        PriceExtensionFlags::PUBLISHER_STATS,
+        |extension: &mut PublisherStats| {
+            *extension = PublisherStats::zeroed();
+            Ok(())
+        },
+    )
+}
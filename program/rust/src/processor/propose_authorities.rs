@@ -0,0 +1,111 @@
+use {
+    super::resize_permissions_account,
+    crate::{
+        accounts::{
+            PendingAuthorities,
+            PermissionAccount,
+            PERMISSIONS_SEED,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::ProposeAuthoritiesArgs,
+        utils::{
+            check_is_upgrade_authority_for_program,
+            check_multisig_approval,
+            check_valid_funding_account,
+            check_valid_writable_account,
+            pyth_assert,
+        },
+        OracleError,
+    },
+    bytemuck::Zeroable,
+    solana_program::{
+        account_info::AccountInfo,
+        clock::Clock,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+        system_program::check_id,
+        sysvar::Sysvar,
+    },
+};
+
+/// Propose new authorities for the permissions account. The current authorities remain in place
+/// until the proposed master authority accepts the proposal with `accept_authorities`, which can't
+/// happen before `delay_slots` slots have passed. `delay_slots` must be at least the minimum delay
+/// stored in the permissions account, which the proposal replaces with `min_delay_slots` once it
+/// is accepted. A new proposal replaces the pending one and proposing the default master authority
+/// cancels it. Once the permissions account has a multisig authority, the proposal must also be
+/// approved by it: its signers follow the other accounts.
+// key[0] upgrade authority         [signer writable]
+// key[1] programdata account       []
+// key[2] permissions account       [writable]
+// key[3] system program            []
+pub fn propose_authorities(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (
+        funding_account,
+        programdata_account,
+        permissions_account,
+        system_program,
+        additional_signers,
+    ) = match accounts {
+        [w, x, y, z, signers @ ..] => Ok((w, x, y, z, signers)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    let cmd_args = load::<ProposeAuthoritiesArgs>(instruction_data)?;
+
+    check_valid_funding_account(funding_account)?;
+    check_is_upgrade_authority_for_program(funding_account, programdata_account, program_id)?;
+
+    let (permission_pda_address, _bump_seed) =
+        Pubkey::find_program_address(&[PERMISSIONS_SEED.as_bytes()], program_id);
+    pyth_assert(
+        permission_pda_address == *permissions_account.key,
+        OracleError::InvalidPda.into(),
+    )?;
+    pyth_assert(
+        check_id(system_program.key),
+        OracleError::InvalidSystemAccount.into(),
+    )?;
+    check_valid_writable_account(program_id, permissions_account)?;
+    load_checked::<PermissionAccount>(permissions_account, cmd_args.header.version)?;
+    check_multisig_approval(permissions_account, funding_account, additional_signers)?;
+
+    resize_permissions_account(
+        permissions_account,
+        funding_account,
+        system_program,
+        PermissionAccount::PENDING_AUTHORITIES_SPACE,
+    )?;
+    let mut pending_authorities =
+        PermissionAccount::load_pending_authorities_mut(permissions_account)?;
+    let min_delay_slots = pending_authorities.min_delay_slots;
+    *pending_authorities = if cmd_args.master_authority == Pubkey::default() {
+        PendingAuthorities {
+            min_delay_slots,
+            ..PendingAuthorities::zeroed()
+        }
+    } else {
+        pyth_assert(
+            cmd_args.delay_slots >= min_delay_slots,
+            ProgramError::InvalidArgument,
+        )?;
+        PendingAuthorities {
+            master_authority: cmd_args.master_authority,
+            data_curation_authority: cmd_args.data_curation_authority,
+            security_authority: cmd_args.security_authority,
+            accept_slot: Clock::get()?.slot.saturating_add(cmd_args.delay_slots),
+            min_delay_slots,
+            proposed_min_delay_slots: cmd_args.min_delay_slots,
+        }
+    };
+
+    Ok(())
+}
@@ -0,0 +1,190 @@
+use {
+    crate::{
+        accounts::{
+            PriceAccount,
+            PublisherStat,
+            PUBLISHER_BLOCKLIST_SEED,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::RotatePublisherArgs,
+        utils::{
+            check_funding_account_permission,
+            check_valid_funding_account,
+            check_valid_writable_account,
+            load_publisher_blocklist,
+            pyth_assert,
+            try_convert,
+        },
+        OracleError,
+    },
+    bytemuck::Zeroable,
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Replace the key of a publisher in each of the price accounts, without deleting and adding the
+/// publisher back: its latest quote, its weight and its statistics are kept. The instruction is
+/// either signed by both the old and the new keys or permissioned by the permissions account, in
+/// which case the publishers don't need to sign but the multisig authority, if there is one, must
+/// approve it. Fails if the old key isn't a publisher of one of
+/// the price accounts, if the new key already is, or if either key is in the
+/// `PublisherBlocklistAccount`.
+// account[0] funding account       [signer writable]
+// account[1] permissions account   []
+// account[2] publisher blocklist   []
+// account[3] old publisher         [signer]
+// account[4] new publisher         [signer]
+// account[5..] price accounts      [writable], `num_price_accounts` of them
+// followed by the additional signers of the multisig authority, if permissioned
+pub fn rotate_publisher(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd_args = load::<RotatePublisherArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<RotatePublisherArgs>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let num_price_accounts: usize = try_convert(cmd_args.num_price_accounts)?;
+    let (
+        funding_account,
+        permissions_account,
+        publisher_blocklist_account,
+        old_publisher,
+        new_publisher,
+        price_accounts,
+        additional_signers,
+    ) = match accounts {
+        [x, p, b, o, n, remaining @ ..] if remaining.len() >= num_price_accounts => {
+            let (price_accounts, additional_signers) = remaining.split_at(num_price_accounts);
+            Ok((x, p, b, o, n, price_accounts, additional_signers))
+        }
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    pyth_assert(
+        *new_publisher.key != Pubkey::default() && new_publisher.key != old_publisher.key,
+        ProgramError::InvalidArgument,
+    )?;
+
+    check_valid_funding_account(funding_account)?;
+    // A blocked key must not hand its quotes over, nor be handed the quotes of another key
+    match load_publisher_blocklist(
+        program_id,
+        publisher_blocklist_account,
+        cmd_args.header.version,
+    )? {
+        Some(publisher_blocklist) => pyth_assert(
+            !publisher_blocklist.is_blocked(old_publisher.key)
+                && !publisher_blocklist.is_blocked(new_publisher.key),
+            OracleError::PermissionViolation.into(),
+        )?,
+        // Unlike in the price updates, the blocklist isn't optional here: an empty account is
+        // only accepted at its PDA, before the blocklist is created
+        None => {
+            let (publisher_blocklist_pda_address, _) =
+                Pubkey::find_program_address(&[PUBLISHER_BLOCKLIST_SEED.as_bytes()], program_id);
+            pyth_assert(
+                publisher_blocklist_pda_address == *publisher_blocklist_account.key,
+                OracleError::InvalidPda.into(),
+            )?;
+        }
+    }
+
+    let signed_by_publishers = old_publisher.is_signer && new_publisher.is_signer;
+    if signed_by_publishers {
+        pyth_assert(
+            additional_signers.is_empty(),
+            OracleError::InvalidNumberOfAccounts.into(),
+        )?;
+    } else {
+        check_funding_account_permission(
+            program_id,
+            funding_account,
+            permissions_account,
+            additional_signers,
+            &cmd_args.header,
+        )?;
+    }
+
+    for price_account in price_accounts {
+        check_valid_writable_account(program_id, price_account)?;
+
+        // The component of the old key gets the new key and moves to keep `comp_` sorted
+        let (from, to) = {
+            let mut price_data =
+                load_checked::<PriceAccount>(price_account, cmd_args.header.version)?;
+            let num_comps = try_convert::<u32, usize>(price_data.num_)?;
+            let comps = &mut price_data.comp_[..num_comps];
+            pyth_assert(
+                !comps.iter().any(|comp| comp.pub_ == *new_publisher.key),
+                ProgramError::InvalidArgument,
+            )?;
+            let from = comps
+                .iter()
+                .position(|comp| comp.pub_ == *old_publisher.key)
+                .ok_or(ProgramError::InvalidArgument)?;
+            let to = comps
+                .iter()
+                .enumerate()
+                .filter(|(i, comp)| *i != from && comp.pub_ < *new_publisher.key)
+                .count();
+            comps[from].pub_ = *new_publisher.key;
+            move_entry(comps, from, to);
+            (from, to)
+        };
+
+        if PriceAccount::publisher_weights(&price_account.try_borrow_data()?).is_some() {
+            let mut publisher_weights = PriceAccount::load_publisher_weights_mut(price_account)?;
+            let weight = publisher_weights.weight(old_publisher.key);
+            if weight != 0 {
+                publisher_weights.set_weight(old_publisher.key, 0);
+                pyth_assert(
+                    publisher_weights.set_weight(new_publisher.key, weight),
+                    ProgramError::InvalidArgument,
+                )?;
+            }
+        }
+
+        // The entries in the order of `comp_` move along with the component
+        if PriceAccount::aggregation_diagnostics(&price_account.try_borrow_data()?).is_some() {
+            let mut diagnostics = PriceAccount::load_aggregation_diagnostics_mut(price_account)?;
+            move_entry(&mut diagnostics.reasons, from, to);
+        }
+
+        if PriceAccount::publisher_stats(&price_account.try_borrow_data()?).is_some() {
+            let mut publisher_stats = PriceAccount::load_publisher_stats_mut(price_account)?;
+            for entry in publisher_stats.entries.iter_mut() {
+                // A stale entry of the new key, from when it was a publisher before, is dropped
+                if entry.publisher == *new_publisher.key {
+                    *entry = PublisherStat::zeroed();
+                } else if entry.publisher == *old_publisher.key {
+                    entry.publisher = *new_publisher.key;
+                }
+            }
+            move_entry(&mut publisher_stats.entries, from, to);
+        }
+    }
+
+    Ok(())
+}
+
+/// Move the entry at index `from` to index `to`, shifting the entries in between by one
+fn move_entry<T>(entries: &mut [T], from: usize, to: usize) {
+    if from < to {
+        entries[from..=to].rotate_left(1);
+    } else {
+        entries[to..=from].rotate_right(1);
+    }
+}
@@ -0,0 +1,53 @@
+use {
+    super::update_price_extension,
+    crate::{
+        accounts::{
+            CircuitBreaker,
+            PriceExtensionFlags,
+        },
+        deserialize::load,
+        instruction::SetCircuitBreakerArgs,
+        utils::pyth_assert,
+    },
+    bytemuck::Zeroable,
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Configure the circuit breaker of a price account, which also resumes trading if it was
+/// tripped. An enabled breaker needs non-zero `required_confirmations` and `max_slots`. See
+/// `update_price_extension` for the accounts.
+pub fn set_circuit_breaker(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<SetCircuitBreakerArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<SetCircuitBreakerArgs>()
+            && (cmd.max_move_bps == 0 || (cmd.required_confirmations != 0 && cmd.max_slots != 0)),
+        ProgramError::InvalidArgument,
+    )?;
+
+    update_price_extension(
+        program_id,
+        accounts,
+        &cmd.header,
+        PriceExtensionFlags::CIRCUIT_BREAKER,
+        |circuit_breaker: &mut CircuitBreaker| {
+            *circuit_breaker = CircuitBreaker {
+                max_move_bps: cmd.max_move_bps,
+                required_confirmations: cmd.required_confirmations,
+                max_slots: cmd.max_slots,
+                ..CircuitBreaker::zeroed()
+            };
+            Ok(())
+        },
+    )
+}
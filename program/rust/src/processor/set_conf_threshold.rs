@@ -0,0 +1,44 @@
+use {
+    super::update_price_extension,
+    crate::{
+        accounts::PriceExtensionFlags,
+        deserialize::load,
+        instruction::SetConfThresholdArgs,
+        utils::pyth_assert,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Set the divisor of the confidence-to-price ratio threshold of a price account, see
+/// `update_price_extension` for the accounts.
+pub fn set_conf_threshold(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<SetConfThresholdArgs>(instruction_data)?;
+
+    // The divisor is converted to an i64 to divide the price
+    pyth_assert(
+        instruction_data.len() == size_of::<SetConfThresholdArgs>()
+            && i64::try_from(cmd.conf_divisor).is_ok(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    update_price_extension(
+        program_id,
+        accounts,
+        &cmd.header,
+        PriceExtensionFlags::CONF_DIVISOR,
+        |conf_divisor: &mut u64| {
+            *conf_divisor = cmd.conf_divisor;
+            Ok(())
+        },
+    )
+}
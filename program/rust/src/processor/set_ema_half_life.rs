@@ -0,0 +1,44 @@
+use {
+    super::update_price_extension,
+    crate::{
+        accounts::PriceExtensionFlags,
+        aggregation::MAX_EMA_HALF_LIFE,
+        deserialize::load,
+        instruction::SetEmaHalfLifeArgs,
+        utils::pyth_assert,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Set the half-life of the EMAs of a price account, see `update_price_extension` for the
+/// accounts.
+pub fn set_ema_half_life(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<SetEmaHalfLifeArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<SetEmaHalfLifeArgs>()
+            && cmd.half_life <= MAX_EMA_HALF_LIFE,
+        ProgramError::InvalidArgument,
+    )?;
+
+    update_price_extension(
+        program_id,
+        accounts,
+        &cmd.header,
+        PriceExtensionFlags::EMA_HALF_LIFE,
+        |half_life: &mut u64| {
+            *half_life = cmd.half_life;
+            Ok(())
+        },
+    )
+}
@@ -0,0 +1,58 @@
+use {
+    super::update_price_extension,
+    crate::{
+        accounts::{
+            EmaHorizons,
+            PriceEma,
+            PriceExtensionFlags,
+        },
+        aggregation::MAX_EMA_HALF_LIFE,
+        deserialize::load,
+        instruction::SetEmaHorizonsArgs,
+        utils::pyth_assert,
+    },
+    bytemuck::Zeroable,
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Set the half-lives of the EMA horizons of a price account. The EMAs of a horizon whose
+/// half-life changes start over. See `update_price_extension` for the accounts.
+pub fn set_ema_horizons(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<SetEmaHorizonsArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<SetEmaHorizonsArgs>()
+            && cmd
+                .half_lives
+                .iter()
+                .all(|half_life| *half_life <= MAX_EMA_HALF_LIFE),
+        ProgramError::InvalidArgument,
+    )?;
+
+    update_price_extension(
+        program_id,
+        accounts,
+        &cmd.header,
+        PriceExtensionFlags::EMA_HORIZONS,
+        |ema_horizons: &mut EmaHorizons| {
+            for (i, half_life) in cmd.half_lives.iter().enumerate() {
+                if ema_horizons.half_lives[i] != *half_life {
+                    ema_horizons.half_lives[i] = *half_life;
+                    ema_horizons.twap[i] = PriceEma::zeroed();
+                    ema_horizons.twac[i] = PriceEma::zeroed();
+                }
+            }
+            Ok(())
+        },
+    )
+}
@@ -37,8 +37,8 @@ pub fn set_max_latency(
         ProgramError::InvalidArgument,
     )?;
 
-    let (funding_account, price_account, permissions_account) = match accounts {
-        [x, y, p] => Ok((x, y, p)),
+    let (funding_account, price_account, permissions_account, additional_signers) = match accounts {
+        [x, y, p, signers @ ..] => Ok((x, y, p, signers)),
         _ => Err(OracleError::InvalidNumberOfAccounts),
     }?;
 
@@ -48,6 +48,7 @@ pub fn set_max_latency(
         price_account,
         funding_account,
         permissions_account,
+        additional_signers,
         &cmd.header,
     )?;
 
@@ -0,0 +1,107 @@
+use {
+    super::resize_permissions_account,
+    crate::{
+        accounts::{
+            MultisigAuthority,
+            PermissionAccount,
+            PERMISSIONS_SEED,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::SetMultisigAuthorityArgs,
+        utils::{
+            check_is_upgrade_authority_for_program,
+            check_multisig_approval,
+            check_valid_funding_account,
+            check_valid_writable_account,
+            pyth_assert,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+        system_program::check_id,
+    },
+    std::mem::size_of,
+};
+
+/// Set the multisig authority of the permissions account. The permissions account must already
+/// exist, it is resized to store the multisig authority if needed, the funding account paying
+/// for the additional rent. A zero threshold disables the multisig authority. Once the
+/// permissions account has a multisig authority, replacing or disabling it must also be approved
+/// by it: its signers follow the other accounts.
+// key[0] upgrade authority         [signer writable]
+// key[1] programdata account       []
+// key[2] permissions account       [writable]
+// key[3] system program            []
+pub fn set_multisig_authority(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (
+        funding_account,
+        programdata_account,
+        permissions_account,
+        system_program,
+        additional_signers,
+    ) = match accounts {
+        [w, x, y, z, signers @ ..] => Ok((w, x, y, z, signers)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    let cmd_args = load::<SetMultisigAuthorityArgs>(instruction_data)?;
+    pyth_assert(
+        instruction_data.len() == size_of::<SetMultisigAuthorityArgs>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    check_valid_funding_account(funding_account)?;
+    check_is_upgrade_authority_for_program(funding_account, programdata_account, program_id)?;
+
+    let (permission_pda_address, _bump_seed) =
+        Pubkey::find_program_address(&[PERMISSIONS_SEED.as_bytes()], program_id);
+    pyth_assert(
+        permission_pda_address == *permissions_account.key,
+        OracleError::InvalidPda.into(),
+    )?;
+    pyth_assert(
+        check_id(system_program.key),
+        OracleError::InvalidSystemAccount.into(),
+    )?;
+    check_valid_writable_account(program_id, permissions_account)?;
+    load_checked::<PermissionAccount>(permissions_account, cmd_args.header.version)?;
+    // Without a multisig authority, there is nothing to disable
+    pyth_assert(
+        cmd_args.threshold != 0
+            || PermissionAccount::load_multisig_authority(permissions_account)?.is_some(),
+        OracleError::InvalidMultisigAuthority.into(),
+    )?;
+    check_multisig_approval(permissions_account, funding_account, additional_signers)?;
+
+    let multisig_authority = MultisigAuthority {
+        threshold:   cmd_args.threshold,
+        num_signers: cmd_args.num_signers,
+        unused_:     [0; 2],
+        signers:     cmd_args.signers,
+    };
+    pyth_assert(
+        multisig_authority.is_valid(),
+        OracleError::InvalidMultisigAuthority.into(),
+    )?;
+
+    resize_permissions_account(
+        permissions_account,
+        funding_account,
+        system_program,
+        PermissionAccount::MULTISIG_AUTHORITY_SPACE,
+    )?;
+    *PermissionAccount::load_multisig_authority_mut(permissions_account)? = multisig_authority;
+
+    Ok(())
+}
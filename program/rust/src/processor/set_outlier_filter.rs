@@ -0,0 +1,53 @@
+use {
+    super::update_price_extension,
+    crate::{
+        accounts::{
+            OutlierFilter,
+            OutlierFilterMode,
+            PriceExtensionFlags,
+        },
+        deserialize::load,
+        instruction::SetOutlierFilterArgs,
+        utils::pyth_assert,
+    },
+    bytemuck::Zeroable,
+    num_traits::FromPrimitive,
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Configure the outlier filter of a price account, which also resets its count of rejected
+/// quotes. An enabled filter needs a non-zero `k`. See `update_price_extension` for the accounts.
+pub fn set_outlier_filter(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<SetOutlierFilterArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<SetOutlierFilterArgs>()
+            && (cmd.mode == 0 || (OutlierFilterMode::from_u32(cmd.mode).is_some() && cmd.k != 0)),
+        ProgramError::InvalidArgument,
+    )?;
+
+    update_price_extension(
+        program_id,
+        accounts,
+        &cmd.header,
+        PriceExtensionFlags::OUTLIER_FILTER,
+        |outlier_filter: &mut OutlierFilter| {
+            *outlier_filter = OutlierFilter {
+                mode: cmd.mode,
+                k: cmd.k,
+                ..OutlierFilter::zeroed()
+            };
+            Ok(())
+        },
+    )
+}
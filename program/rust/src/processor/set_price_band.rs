@@ -0,0 +1,49 @@
+use {
+    super::update_price_extension,
+    crate::{
+        accounts::{
+            PriceBand,
+            PriceExtensionFlags,
+        },
+        deserialize::load,
+        instruction::SetPriceBandArgs,
+        utils::pyth_assert,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Set the sanity bounds of the quotes of a price account, which apply to the quotes published
+/// from then on. `min_price` can't be above `max_price`. See `update_price_extension` for the
+/// accounts.
+pub fn set_price_band(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<SetPriceBandArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<SetPriceBandArgs>() && cmd.min_price <= cmd.max_price,
+        ProgramError::InvalidArgument,
+    )?;
+
+    update_price_extension(
+        program_id,
+        accounts,
+        &cmd.header,
+        PriceExtensionFlags::PRICE_BAND,
+        |price_band: &mut PriceBand| {
+            *price_band = PriceBand {
+                min_price: cmd.min_price,
+                max_price: cmd.max_price,
+            };
+            Ok(())
+        },
+    )
+}
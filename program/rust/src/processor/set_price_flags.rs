@@ -0,0 +1,95 @@
+use {
+    crate::{
+        accounts::{
+            PriceAccount,
+            PriceAccountFlags,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::SetPriceFlagsArgs,
+        utils::{
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            pyth_assert,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Flags that can be set or cleared by `set_price_flags`. The other flags are managed by the
+/// program itself.
+const CONFIGURABLE_FLAGS: PriceAccountFlags = PriceAccountFlags::ACCUMULATOR_V2
+    .union(PriceAccountFlags::STAKE_WEIGHTED)
+    .union(PriceAccountFlags::CORP_ACT_STATUS)
+    .union(PriceAccountFlags::CORP_ACT_ANY);
+
+/// Set or clear the flags of a price account. Fails if a flag is both set and cleared, if a flag
+/// isn't configurable or if `STAKE_WEIGHTED` is set on a price account without `PublisherWeights`.
+// account[0] funding account       [signer writable]
+// account[1] price account         [writable]
+// account[2] permissions account   []
+pub fn set_price_flags(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<SetPriceFlagsArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<SetPriceFlagsArgs>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, price_account, permissions_account, additional_signers) = match accounts {
+        [x, y, p, signers @ ..] => Ok((x, y, p, signers)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        price_account,
+        funding_account,
+        permissions_account,
+        additional_signers,
+        &cmd.header,
+    )?;
+
+    let set_flags =
+        PriceAccountFlags::from_bits(cmd.set_flags).ok_or(OracleError::InvalidPriceAccountFlags)?;
+    let clear_flags = PriceAccountFlags::from_bits(cmd.clear_flags)
+        .ok_or(OracleError::InvalidPriceAccountFlags)?;
+    pyth_assert(
+        CONFIGURABLE_FLAGS.contains(set_flags | clear_flags) && !set_flags.intersects(clear_flags),
+        OracleError::InvalidPriceAccountFlags.into(),
+    )?;
+
+    if set_flags.contains(PriceAccountFlags::STAKE_WEIGHTED) {
+        pyth_assert(
+            PriceAccount::publisher_weights(&price_account.try_borrow_data()?).is_some(),
+            OracleError::InvalidPriceAccountFlags.into(),
+        )?;
+    }
+
+    let mut price_data = load_checked::<PriceAccount>(price_account, cmd.header.version)?;
+    price_data.flags.insert(set_flags);
+    price_data.flags.remove(clear_flags);
+    // The message buffer is only cleared when the V2 accumulator is enabled, so the flag must
+    // be reset for the next time it is enabled.
+    if clear_flags.contains(PriceAccountFlags::ACCUMULATOR_V2) {
+        price_data
+            .flags
+            .remove(PriceAccountFlags::MESSAGE_BUFFER_CLEARED);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use {
+    crate::{
+        accounts::{
+            PublisherManagerAccount,
+            PythAccount,
+            MAX_PUBLISHER_MANAGER_PRODUCTS,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::SetPublisherManagerArgs,
+        utils::{
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            pyth_assert,
+            try_convert,
+            valid_fresh_account,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Initialize a publisher manager account, or update it if it is already initialized. The scope
+/// of the delegation is either a mapping account or a list of products, not both.
+// account[0] funding account           [signer writable]
+// account[1] publisher manager account [writable]
+// account[2] permissions account       []
+pub fn set_publisher_manager(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd_args = load::<SetPublisherManagerArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<SetPublisherManagerArgs>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, publisher_manager_account, permissions_account, additional_signers) =
+        match accounts {
+            [x, y, p, signers @ ..] => Ok((x, y, p, signers)),
+            _ => Err(OracleError::InvalidNumberOfAccounts),
+        }?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        publisher_manager_account,
+        funding_account,
+        permissions_account,
+        additional_signers,
+        &cmd_args.header,
+    )?;
+
+    let num_products: usize = try_convert(cmd_args.num_products)?;
+    pyth_assert(
+        num_products <= MAX_PUBLISHER_MANAGER_PRODUCTS
+            && (cmd_args.mapping_account == Pubkey::default() || num_products == 0),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let mut publisher_manager = if valid_fresh_account(publisher_manager_account) {
+        PublisherManagerAccount::initialize(publisher_manager_account, cmd_args.header.version)?
+    } else {
+        load_checked::<PublisherManagerAccount>(publisher_manager_account, cmd_args.header.version)?
+    };
+    publisher_manager.manager = cmd_args.manager;
+    publisher_manager.mapping_account = cmd_args.mapping_account;
+    publisher_manager.num_products = cmd_args.num_products;
+    publisher_manager.products = [Pubkey::default(); MAX_PUBLISHER_MANAGER_PRODUCTS];
+    publisher_manager.products[..num_products].copy_from_slice(&cmd_args.products[..num_products]);
+
+    Ok(())
+}
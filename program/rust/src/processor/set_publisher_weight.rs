@@ -0,0 +1,49 @@
+use {
+    super::update_price_extension,
+    crate::{
+        accounts::{
+            PriceExtensionFlags,
+            PublisherWeights,
+        },
+        deserialize::load,
+        instruction::SetPublisherWeightArgs,
+        utils::pyth_assert,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Set the weight of a publisher in the stake-weighted aggregation of a price account, a weight
+/// of 0 removing the publisher from its `PublisherWeights`. The publisher doesn't need to be a
+/// publisher of the price account yet. See `update_price_extension` for the accounts.
+pub fn set_publisher_weight(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<SetPublisherWeightArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<SetPublisherWeightArgs>()
+            && cmd.publisher != Pubkey::default(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    update_price_extension(
+        program_id,
+        accounts,
+        &cmd.header,
+        PriceExtensionFlags::PUBLISHER_WEIGHTS,
+        |publisher_weights: &mut PublisherWeights| {
+            pyth_assert(
+                publisher_weights.set_weight(&cmd.publisher, cmd.weight),
+                ProgramError::InvalidArgument,
+            )
+        },
+    )
+}
@@ -0,0 +1,78 @@
+use {
+    super::resize_account,
+    crate::{
+        accounts::{
+            ProductAccount,
+            PythAccount,
+            TradingSchedule,
+        },
+        deserialize::{
+            load,
+            load_checked,
+            load_extension_mut,
+        },
+        instruction::CommandHeader,
+        utils::{
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            pyth_assert,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        pubkey::Pubkey,
+        system_program::check_id,
+    },
+    std::mem::size_of,
+};
+
+/// Set the trading hours of the price accounts of a product from the schedule following the
+/// header in `instruction_data`, which is rejected if it isn't valid. An empty schedule disables
+/// it. The product account is resized to store the schedule if needed, the funding account paying
+/// for the additional rent.
+// account[0] funding account       [signer writable]
+// account[1] product account       [writable]
+// account[2] permissions account   []
+// account[3] system program        []
+// account[4..] additional signers  [signer]
+pub fn set_trading_schedule(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (funding_account, product_account, permissions_account, system_program, additional_signers) =
+        match accounts {
+            [x, y, p, s, signers @ ..] => Ok((x, y, p, s, signers)),
+            _ => Err(OracleError::InvalidNumberOfAccounts),
+        }?;
+
+    let hdr = load::<CommandHeader>(instruction_data)?;
+    let trading_schedule = TradingSchedule::parse(&instruction_data[size_of::<CommandHeader>()..])?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        product_account,
+        funding_account,
+        permissions_account,
+        additional_signers,
+        hdr,
+    )?;
+    pyth_assert(
+        check_id(system_program.key),
+        OracleError::InvalidSystemAccount.into(),
+    )?;
+
+    load_checked::<ProductAccount>(product_account, hdr.version)?;
+    resize_account(
+        product_account,
+        funding_account,
+        system_program,
+        ProductAccount::TRADING_SCHEDULE_SPACE,
+    )?;
+    *load_extension_mut::<TradingSchedule>(product_account, ProductAccount::MINIMUM_SIZE)? =
+        trading_schedule;
+    Ok(())
+}
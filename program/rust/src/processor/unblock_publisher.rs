@@ -0,0 +1,74 @@
+use {
+    crate::{
+        accounts::PublisherBlocklistAccount,
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::UnblockPublisherArgs,
+        utils::{
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            pyth_assert,
+            try_convert,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Remove a publisher from the publisher blocklist. Fails if the publisher isn't blocked.
+// account[0] funding account             [signer writable]
+// account[1] publisher blocklist account [writable]
+// account[2] permissions account         []
+pub fn unblock_publisher(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd_args = load::<UnblockPublisherArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<UnblockPublisherArgs>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, publisher_blocklist_account, permissions_account, additional_signers) =
+        match accounts {
+            [x, y, p, signers @ ..] => Ok((x, y, p, signers)),
+            _ => Err(OracleError::InvalidNumberOfAccounts),
+        }?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        publisher_blocklist_account,
+        funding_account,
+        permissions_account,
+        additional_signers,
+        &cmd_args.header,
+    )?;
+
+    let mut publisher_blocklist = load_checked::<PublisherBlocklistAccount>(
+        publisher_blocklist_account,
+        cmd_args.header.version,
+    )?;
+    let num_publishers: usize = try_convert(publisher_blocklist.num_publishers)?;
+    let index = publisher_blocklist.publishers[..num_publishers]
+        .iter()
+        .position(|key| *key == cmd_args.publisher)
+        .ok_or(ProgramError::InvalidArgument)?;
+    publisher_blocklist
+        .publishers
+        .copy_within(index + 1..num_publishers, index);
+    publisher_blocklist.publishers[num_publishers - 1] = Pubkey::default();
+    publisher_blocklist.num_publishers -= 1;
+
+    Ok(())
+}
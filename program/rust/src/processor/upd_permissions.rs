@@ -21,14 +21,17 @@ use {
     solana_program::{
         account_info::AccountInfo,
         entrypoint::ProgramResult,
+        program_error::ProgramError,
         pubkey::Pubkey,
         system_program::check_id,
     },
 };
 
 /// Updates permissions for the pyth oracle program
-/// This function can create and update the permissions accounts, which stores
-/// several public keys that can execute administrative instructions in the pyth program
+/// This function creates the permissions account, which stores several public keys that can
+/// execute administrative instructions in the pyth program, and sets its initial authorities.
+/// Once they are set, they can only be changed with `propose_authorities` and
+/// `accept_authorities`.
 // key[0] upgrade authority         [signer writable]
 // key[1] programdata account       []
 // key[2] permissions account       [writable]
@@ -45,6 +48,10 @@ pub fn upd_permissions(
     }?;
 
     let cmd_args = load::<UpdPermissionsArgs>(instruction_data)?;
+    pyth_assert(
+        cmd_args.master_authority != Pubkey::default(),
+        ProgramError::InvalidArgument,
+    )?;
 
     check_valid_funding_account(funding_account)?;
     check_is_upgrade_authority_for_program(funding_account, programdata_account, program_id)?;
@@ -76,6 +83,10 @@ pub fn upd_permissions(
 
     let mut permissions_account_data =
         load_checked::<PermissionAccount>(permissions_account, cmd_args.header.version)?;
+    pyth_assert(
+        permissions_account_data.master_authority == Pubkey::default(),
+        OracleError::AuthorityTransferRequired.into(),
+    )?;
     permissions_account_data.master_authority = cmd_args.master_authority;
 
     permissions_account_data.data_curation_authority = cmd_args.data_curation_authority;
@@ -1,13 +1,20 @@
 use {
     crate::{
         accounts::{
+            AggregationExtensions,
             PriceAccount,
             PriceAccountFlags,
+            PriceBand,
             PriceComponent,
-            PriceInfo,
+            PublisherBlocklistAccount,
             PythOracleSerialize,
+            TradingSchedule,
             UPD_PRICE_WRITE_SEED,
         },
+        aggregation::{
+            update_aggregate,
+            EmaParams,
+        },
         deserialize::{
             load,
             load_checked,
@@ -16,8 +23,11 @@ use {
         utils::{
             check_valid_funding_account,
             check_valid_writable_account,
-            get_status_for_conf_price_ratio,
+            get_status_for_conf_divisor,
+            get_status_for_price_band,
             is_component_update,
+            load_publisher_blocklist,
+            load_trading_schedule,
             pyth_assert,
             try_convert,
         },
@@ -35,37 +45,18 @@ use {
         program_error::ProgramError,
         program_memory::sol_memcmp,
         pubkey::Pubkey,
-        sysvar::Sysvar,
+        sysvar::{
+            clock,
+            Sysvar,
+        },
     },
 };
 
-#[cfg(target_arch = "bpf")]
-#[link(name = "cpyth-bpf")]
-extern "C" {
-    pub fn c_upd_aggregate_pythnet(_input: *mut u8, clock_slot: u64, clock_timestamp: i64) -> bool;
-
-    #[allow(unused)]
-    pub fn c_upd_twap(_input: *mut u8, nslots: i64);
-}
-
-#[cfg(not(target_arch = "bpf"))]
-#[link(name = "cpyth-native")]
-extern "C" {
-    pub fn c_upd_aggregate_pythnet(_input: *mut u8, clock_slot: u64, clock_timestamp: i64) -> bool;
-
-    #[allow(unused)]
-    pub fn c_upd_twap(_input: *mut u8, nslots: i64);
-}
-
-#[inline]
-pub unsafe fn c_upd_aggregate(input: *mut u8, clock_slot: u64, clock_timestamp: i64) -> bool {
-    c_upd_aggregate_pythnet(input, clock_slot, clock_timestamp)
-}
-
 /// Publish component price, never returning an error even if the update failed
 // account[0] funding account       [signer writable]
 // account[1] price account         [writable]
 // account[2] sysvar_clock account  []
+// see `upd_price` for the optional accounts
 pub fn upd_price_no_fail_on_error(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -97,6 +88,14 @@ pub fn upd_price_no_fail_on_error(
 ///            which allows the called-into program to authenticate that it is being invoked by the oracle
 ///            program. []
 /// account[6] message buffer data [writable]
+///
+/// The publisher blocklist account [] can follow the other accounts, i.e. as account[3] without
+/// the message buffer accounts and account[7] with them. If provided, the update fails if the
+/// publisher is blocked and the aggregation ignores the quotes of the blocked publishers. The
+/// blocklist account can in turn be followed by the product account of the price account [], in
+/// which case the aggregation applies its `TradingSchedule`. Both are optional so that the
+/// clients predating them keep working: their updates aren't checked against them, but the
+/// validator aggregation always applies them.
 pub fn upd_price(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -105,13 +104,20 @@ pub fn upd_price(
     let cmd_args = load::<UpdPriceArgs>(instruction_data)?;
 
     #[allow(unused_variables)]
-    let (funding_account, price_account, clock_account, maybe_accumulator_accounts) = match accounts
-    {
-        [x, y, z] => Ok((x, y, z, None)),
+    let (
+        funding_account,
+        price_account,
+        clock_account,
+        maybe_accumulator_accounts,
+        optional_accounts,
+    ) = match accounts {
+        [x, y, z] => Ok((x, y, z, None, &[][..])),
+        // The legacy layout below has the clock as its last account instead
+        [x, y, z, e @ ..] if e.len() <= 2 && clock::check_id(z.key) => Ok((x, y, z, None, e)),
         // Note: this version of the instruction exists for backward compatibility when publishers were including a
         // now superfluous account in the instruction.
-        [x, y, _, z] => Ok((x, y, z, None)),
-        [x, y, z, a, b, c, d] => Ok((
+        [x, y, _, z] => Ok((x, y, z, None, &[][..])),
+        [x, y, z, a, b, c, d, e @ ..] if e.len() <= 2 => Ok((
             x,
             y,
             z,
@@ -121,6 +127,7 @@ pub fn upd_price(
                 oracle_auth_pda:     c,
                 message_buffer_data: d,
             }),
+            e,
         )),
         _ => Err(OracleError::InvalidNumberOfAccounts),
     }?;
@@ -129,80 +136,53 @@ pub fn upd_price(
     check_valid_writable_account(program_id, price_account)?;
     // Check clock
     let clock = Clock::from_account_info(clock_account)?;
+    let publisher_blocklist = match optional_accounts.first() {
+        Some(account) => load_publisher_blocklist(program_id, account, cmd_args.header.version)?,
+        None => None,
+    };
 
     let publisher_index: usize;
-    let latest_aggregate_price: PriceInfo;
     let flags: PriceAccountFlags;
+    let trading_schedule: Option<TradingSchedule>;
 
     // The price_data borrow happens in a scope because it must be
-    // dropped before we borrow again as raw data pointer for the C
-    // aggregation logic.
+    // dropped before we borrow the price account mutably below.
     {
         // Verify that symbol account is initialized
         let price_data = load_checked::<PriceAccount>(price_account, cmd_args.header.version)?;
-
-        publisher_index = match find_publisher_index(
-            &price_data.comp_[..try_convert::<u32, usize>(price_data.num_)?],
+        publisher_index = check_publisher_update(
+            &price_data,
             funding_account.key,
-        ) {
-            Some(index) => index,
-            None => {
-                return Err(OracleError::PermissionViolation.into());
-            }
-        };
-
-        latest_aggregate_price = price_data.agg_;
-        let latest_publisher_price = price_data.comp_[publisher_index].latest_;
-
-        // Check that publisher is publishing a more recent price
-        pyth_assert(
-            !is_component_update(cmd_args)?
-                || (cmd_args.publishing_slot > latest_publisher_price.pub_slot_
-                    && cmd_args.publishing_slot <= clock.slot),
-            ProgramError::InvalidArgument,
+            cmd_args,
+            &clock,
+            publisher_blocklist.as_deref(),
         )?;
-
         flags = price_data.flags;
-    }
-
-    if !flags.contains(PriceAccountFlags::ACCUMULATOR_V2) {
-        // Try to update the aggregate
-        #[allow(unused_variables)]
-        if clock.slot > latest_aggregate_price.pub_slot_ {
-            let updated = unsafe {
-                // NOTE: c_upd_aggregate must use a raw pointer to price
-                // data. Solana's `<account>.borrow_*` methods require exclusive
-                // access, i.e. no other borrow can exist for the account.
-                c_upd_aggregate(
-                    price_account.try_borrow_mut_data()?.as_mut_ptr(),
-                    clock.slot,
-                    clock.unix_timestamp,
-                )
-            };
-
-            // If the aggregate was successfully updated, calculate the difference and update TWAP.
-            if updated {
-                let agg_diff = (clock.slot as i64)
-                    - load_checked::<PriceAccount>(price_account, cmd_args.header.version)?
-                        .prev_slot_ as i64;
-                // Encapsulate TWAP update logic in a function to minimize unsafe block scope.
-                unsafe {
-                    c_upd_twap(price_account.try_borrow_mut_data()?.as_mut_ptr(), agg_diff);
-                }
-                let mut price_data =
-                    load_checked::<PriceAccount>(price_account, cmd_args.header.version)?;
-                // We want to send a message every time the aggregate price updates. However, during the migration,
-                // not every publisher will necessarily provide the accumulator accounts. The message_sent_ flag
-                // ensures that after every aggregate update, the next publisher who provides the accumulator accounts
-                // will send the message.
-                price_data.message_sent_ = 0;
-                price_data.update_price_cumulative();
+        trading_schedule = match optional_accounts.get(1) {
+            Some(account) => {
+                load_trading_schedule(program_id, account, &price_data, cmd_args.header.version)?
             }
-        }
+            None => None,
+        };
     }
-
-    // Reload price data as a struct after c_upd_aggregate() borrow is dropped
-    let mut price_data = load_checked::<PriceAccount>(price_account, cmd_args.header.version)?;
+    let conf_divisor = PriceAccount::load_conf_divisor(price_account)?;
+    let price_band = PriceAccount::price_band(&price_account.try_borrow_data()?);
+    let status = get_publisher_status(cmd_args, conf_divisor, price_band.as_ref())?;
+    let ema_params = EmaParams::from_half_life(PriceAccount::ema_half_life(
+        &price_account.try_borrow_data()?,
+    ));
+
+    let (mut price_data, mut extensions_data) =
+        PriceAccount::load_with_extensions_mut(price_account, cmd_args.header.version)?;
+    let mut extensions = AggregationExtensions::new(&mut extensions_data);
+    try_update_aggregate(
+        &mut price_data,
+        &clock,
+        ema_params,
+        &mut extensions,
+        publisher_blocklist.as_deref(),
+        trading_schedule.as_ref(),
+    );
 
     // Feature-gated accumulator-specific code, used only on pythnet/pythtest
     let need_message_buffer_update = if flags.contains(PriceAccountFlags::ACCUMULATOR_V2) {
@@ -248,12 +228,48 @@ pub fn upd_price(
             let message = if flags.contains(PriceAccountFlags::ACCUMULATOR_V2) {
                 vec![]
             } else {
-                vec![
+                let mut message = vec![
                     price_data
                         .as_price_feed_message(price_account.key)
                         .to_bytes(),
                     price_data.as_twap_message(price_account.key).to_bytes(),
-                ]
+                ];
+                if let Some(ema_horizons) = extensions
+                    .ema_horizons
+                    .as_deref()
+                    .filter(|h| h.is_enabled())
+                {
+                    message.push(
+                        price_data
+                            .as_ema_horizons_message(price_account.key, ema_horizons)
+                            .to_bytes(),
+                    );
+                }
+                if flags.contains(PriceAccountFlags::CORP_ACT_STATUS) {
+                    message.push(
+                        price_data
+                            .as_corp_act_status_message(price_account.key)
+                            .to_bytes(),
+                    );
+                }
+                if let Some(publisher_stats) = extensions.publisher_stats.as_deref() {
+                    message.push(
+                        price_data
+                            .as_publisher_stats_message(price_account.key, publisher_stats)
+                            .to_bytes(),
+                    );
+                }
+                if let Some(aggregate_confidence) = extensions.aggregate_confidence.as_deref() {
+                    message.push(
+                        price_data
+                            .as_aggregate_confidence_message(
+                                price_account.key,
+                                aggregate_confidence,
+                            )
+                            .to_bytes(),
+                    );
+                }
+                message
             };
 
             // anchor discriminator for "global:put_all"
@@ -280,26 +296,118 @@ pub fn upd_price(
         }
     }
 
-    // Try to update the publisher's price
-    if is_component_update(cmd_args)? {
-        // IMPORTANT: If the publisher does not meet the price/conf
-        // ratio condition, its price will not count for the next
-        // aggregate.
-        let status: u32 =
-            get_status_for_conf_price_ratio(cmd_args.price, cmd_args.confidence, cmd_args.status)?;
-
-        {
-            let publisher_price = &mut price_data.comp_[publisher_index].latest_;
-            publisher_price.price_ = cmd_args.price;
-            publisher_price.conf_ = cmd_args.confidence;
-            publisher_price.status_ = status;
-            publisher_price.pub_slot_ = cmd_args.publishing_slot;
-        }
-    }
+    update_publisher_price(&mut price_data, publisher_index, cmd_args, status);
 
     Ok(())
 }
 
+/// Find the publisher's component in the price account and check that the publisher isn't in
+/// the `publisher_blocklist`, if any, and is publishing a more recent price. Returns the index of
+/// the publisher's component.
+pub fn check_publisher_update(
+    price_data: &PriceAccount,
+    publisher: &Pubkey,
+    cmd_args: &UpdPriceArgs,
+    clock: &Clock,
+    publisher_blocklist: Option<&PublisherBlocklistAccount>,
+) -> Result<usize, ProgramError> {
+    let publisher_index = find_publisher_index(
+        &price_data.comp_[..try_convert::<u32, usize>(price_data.num_)?],
+        publisher,
+    )
+    .ok_or(OracleError::PermissionViolation)?;
+    pyth_assert(
+        !publisher_blocklist.map_or(false, |publisher_blocklist| {
+            publisher_blocklist.is_blocked(publisher)
+        }),
+        OracleError::PermissionViolation.into(),
+    )?;
+
+    let latest_publisher_price = price_data.comp_[publisher_index].latest_;
+
+    // Check that publisher is publishing a more recent price
+    pyth_assert(
+        !is_component_update(cmd_args)?
+            || (cmd_args.publishing_slot > latest_publisher_price.pub_slot_
+                && cmd_args.publishing_slot <= clock.slot),
+        ProgramError::InvalidArgument,
+    )?;
+
+    Ok(publisher_index)
+}
+
+/// Update the aggregate if this is the first price update of the slot. Accounts using the
+/// V2 accumulator are aggregated by the validator instead.
+pub fn try_update_aggregate(
+    price_data: &mut PriceAccount,
+    clock: &Clock,
+    ema_params: EmaParams,
+    extensions: &mut AggregationExtensions,
+    publisher_blocklist: Option<&PublisherBlocklistAccount>,
+    trading_schedule: Option<&TradingSchedule>,
+) {
+    if !price_data.flags.contains(PriceAccountFlags::ACCUMULATOR_V2)
+        && clock.slot > price_data.agg_.pub_slot_
+    {
+        update_aggregate(
+            price_data,
+            clock.slot,
+            clock.unix_timestamp,
+            ema_params,
+            extensions,
+            publisher_blocklist,
+            trading_schedule,
+        );
+    }
+}
+
+/// The status to store with the publisher's price, or `None` if the instruction only triggers
+/// the aggregation. `conf_divisor` is the divisor of the confidence-to-price ratio threshold of
+/// the price account, see `PriceAccount::load_conf_divisor`, and `price_band` its `PriceBand`,
+/// if any.
+pub fn get_publisher_status(
+    cmd_args: &UpdPriceArgs,
+    conf_divisor: i64,
+    price_band: Option<&PriceBand>,
+) -> Result<Option<u32>, ProgramError> {
+    if !is_component_update(cmd_args)? {
+        return Ok(None);
+    }
+    // IMPORTANT: If the publisher does not meet the price/conf
+    // ratio condition, its price will not count for the next
+    // aggregate.
+    let status: u32 = get_status_for_conf_divisor(
+        cmd_args.price,
+        cmd_args.confidence,
+        cmd_args.status,
+        conf_divisor,
+    )?;
+    // Likewise for prices outside of the sanity bounds of the price account
+    Ok(Some(get_status_for_price_band(
+        cmd_args.price,
+        status,
+        price_band,
+    )))
+}
+
+/// Store the publisher's price in its component with the `status` returned by
+/// `get_publisher_status`, unless it is `None`.
+pub fn update_publisher_price(
+    price_data: &mut PriceAccount,
+    publisher_index: usize,
+    cmd_args: &UpdPriceArgs,
+    status: Option<u32>,
+) {
+    if let Some(status) = status {
+        let publisher_price = &mut price_data.comp_[publisher_index].latest_;
+        publisher_price.price_ = cmd_args.price;
+        publisher_price.conf_ = cmd_args.confidence;
+        publisher_price.status_ = status;
+        publisher_price.corp_act_status_ = cmd_args.corp_act_status;
+        publisher_price.pub_slot_ = cmd_args.publishing_slot;
+    }
+}
+
 /// Find the index of the publisher in the list of components.
 ///
 /// This method first tries to binary search for the publisher's key in the list of components
@@ -0,0 +1,165 @@
+use {
+    super::upd_price::{
+        check_publisher_update,
+        get_publisher_status,
+        try_update_aggregate,
+        update_publisher_price,
+    },
+    crate::{
+        accounts::{
+            AggregationExtensions,
+            PriceAccount,
+            PublisherBlocklistAccount,
+        },
+        aggregation::EmaParams,
+        deserialize::{
+            load,
+            load_slice,
+        },
+        instruction::{
+            CommandHeader,
+            PriceUpdate,
+            UpdPriceArgs,
+            UPD_PRICE_BATCH_FAILURE,
+            UPD_PRICE_BATCH_SUCCESS,
+        },
+        utils::{
+            check_valid_funding_account,
+            check_valid_writable_account,
+            load_publisher_blocklist,
+            load_trading_schedule,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        clock::Clock,
+        entrypoint::ProgramResult,
+        program::set_return_data,
+        pubkey::Pubkey,
+        sysvar::Sysvar,
+    },
+    std::mem::size_of,
+};
+
+/// Publish component prices for several price accounts in a single instruction. Each price
+/// account is updated as by `upd_price`, including triggering the aggregation if this is the
+/// first update of the slot. Like `upd_price_no_fail_on_error`, a failed update doesn't fail the
+/// instruction and leaves its price account unchanged. The outcome of every update is set as the
+/// return data of the instruction: one byte per price account, `UPD_PRICE_BATCH_SUCCESS` or
+/// `UPD_PRICE_BATCH_FAILURE`.
+///
+/// Unlike `upd_price`, this instruction doesn't send price messages to the message buffer
+/// program, since that would take four more accounts per price account. An aggregation
+/// triggered by the batch still resets `message_sent_`, so its messages are sent by the next
+/// `upd_price` of the price account with the message buffer accounts.
+///
+/// account[0] the publisher's account (funds the tx) [signer writable]
+/// account[1] sysvar clock account []
+/// account[2..] the price accounts, one per `PriceUpdate` in the instruction data [writable]
+/// optionally followed by the publisher blocklist account [] and then by product accounts [], see
+/// `upd_price`. The aggregation of a price account applies the `TradingSchedule` of its product if
+/// it is one of them.
+pub fn upd_price_batch(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let header = load::<CommandHeader>(instruction_data)?;
+    let updates = load_slice::<PriceUpdate>(&instruction_data[size_of::<CommandHeader>()..])?;
+
+    let (funding_account, clock_account, price_accounts, optional_accounts) = match accounts {
+        [x, y, remaining_accounts @ ..] if remaining_accounts.len() >= updates.len() => {
+            let (price_accounts, optional_accounts) = remaining_accounts.split_at(updates.len());
+            Ok((x, y, price_accounts, optional_accounts))
+        }
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+    let product_accounts = optional_accounts.get(1..).unwrap_or_default();
+
+    check_valid_funding_account(funding_account)?;
+    // Check clock
+    let clock = Clock::from_account_info(clock_account)?;
+    let publisher_blocklist = match optional_accounts.first() {
+        Some(account) => load_publisher_blocklist(program_id, account, header.version)?,
+        None => None,
+    };
+
+    let results: Vec<u8> = price_accounts
+        .iter()
+        .zip(updates)
+        .map(|(price_account, update)| {
+            let cmd_args = UpdPriceArgs {
+                header:          *header,
+                status:          update.status,
+                corp_act_status: update.corp_act_status,
+                price:           update.price,
+                confidence:      update.confidence,
+                publishing_slot: update.publishing_slot,
+            };
+            match upd_single_price(
+                program_id,
+                funding_account,
+                price_account,
+                &clock,
+                &cmd_args,
+                publisher_blocklist.as_deref(),
+                product_accounts,
+            ) {
+                Ok(()) => UPD_PRICE_BATCH_SUCCESS,
+                Err(_) => UPD_PRICE_BATCH_FAILURE,
+            }
+        })
+        .collect();
+
+    set_return_data(&results);
+    Ok(())
+}
+
+/// Apply one update of the batch. Every fallible step happens before the aggregation, the first
+/// modification of the price account, so that a failed update leaves the account unchanged.
+fn upd_single_price(
+    program_id: &Pubkey,
+    funding_account: &AccountInfo,
+    price_account: &AccountInfo,
+    clock: &Clock,
+    cmd_args: &UpdPriceArgs,
+    publisher_blocklist: Option<&PublisherBlocklistAccount>,
+    product_accounts: &[AccountInfo],
+) -> ProgramResult {
+    check_valid_writable_account(program_id, price_account)?;
+    let conf_divisor = PriceAccount::load_conf_divisor(price_account)?;
+    let price_band = PriceAccount::price_band(&price_account.try_borrow_data()?);
+    let status = get_publisher_status(cmd_args, conf_divisor, price_band.as_ref())?;
+    let ema_params = EmaParams::from_half_life(PriceAccount::ema_half_life(
+        &price_account.try_borrow_data()?,
+    ));
+    let (mut price_data, mut extensions_data) =
+        PriceAccount::load_with_extensions_mut(price_account, cmd_args.header.version)?;
+    let publisher_index = check_publisher_update(
+        &price_data,
+        funding_account.key,
+        cmd_args,
+        clock,
+        publisher_blocklist,
+    )?;
+    let trading_schedule = match product_accounts
+        .iter()
+        .find(|account| *account.key == price_data.product_account)
+    {
+        Some(account) => {
+            load_trading_schedule(program_id, account, &price_data, cmd_args.header.version)?
+        }
+        None => None,
+    };
+    try_update_aggregate(
+        &mut price_data,
+        clock,
+        ema_params,
+        &mut AggregationExtensions::new(&mut extensions_data),
+        publisher_blocklist,
+        trading_schedule.as_ref(),
+    );
+    update_publisher_price(&mut price_data, publisher_index, cmd_args, status);
+    Ok(())
+}
@@ -31,8 +31,9 @@ pub fn upd_product(
     accounts: &[AccountInfo],
     instruction_data: &[u8],
 ) -> ProgramResult {
-    let (funding_account, product_account, permissions_account) = match accounts {
-        [x, y, p] => Ok((x, y, p)),
+    let (funding_account, product_account, permissions_account, additional_signers) = match accounts
+    {
+        [x, y, p, signers @ ..] => Ok((x, y, p, signers)),
         _ => Err(OracleError::InvalidNumberOfAccounts),
     }?;
 
@@ -44,6 +45,7 @@ pub fn upd_product(
         product_account,
         funding_account,
         permissions_account,
+        additional_signers,
         hdr,
     )?;
 
@@ -2,27 +2,50 @@ mod pyth_simulator;
 mod test_add_price;
 mod test_add_product;
 mod test_add_publisher;
+mod test_aggregate_confidence;
 mod test_aggregate_v2;
 mod test_aggregation;
+mod test_aggregation_diagnostics;
+mod test_authority_transfer;
+mod test_builders;
 mod test_c_code;
 mod test_check_valid_signable_account_or_permissioned_funding_account;
+mod test_circuit_breaker;
+mod test_corp_act_status;
+mod test_decode_instruction;
 mod test_del_price;
 mod test_del_product;
 mod test_del_publisher;
 mod test_ema;
+mod test_ema_horizons;
 mod test_full_publisher_set;
 mod test_init_mapping;
 mod test_init_price;
 mod test_message;
+mod test_multisig_authority;
+mod test_outlier_filter;
 mod test_permission_migration;
+mod test_price_band;
+mod test_price_history;
 mod test_publish;
 mod test_publish_batch;
+mod test_publisher_blocklist;
+mod test_publisher_manager;
+mod test_publisher_stats;
+mod test_publisher_weights;
+mod test_rotate_publisher;
+mod test_rust_aggregation;
+mod test_set_conf_threshold;
+mod test_set_ema_half_life;
 mod test_set_max_latency;
 mod test_set_min_pub;
+mod test_set_price_flags;
 mod test_sizes;
+mod test_trading_schedule;
 mod test_upd_aggregate;
 mod test_upd_permissions;
 mod test_upd_price;
+mod test_upd_price_batch;
 mod test_upd_price_no_fail_on_error;
 mod test_upd_price_with_validator;
 mod test_upd_product;
@@ -30,4 +53,5 @@ mod test_utils;
 
 
 mod test_twap;
+mod test_twap_query;
 mod test_upd_price_v2;
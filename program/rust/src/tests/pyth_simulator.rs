@@ -1,27 +1,19 @@
 use {
     crate::{
-        accounts::{
-            MappingAccount,
-            PERMISSIONS_SEED,
-        },
+        accounts::MappingAccount,
         c_oracle_header::{
             PC_PROD_ACC_SIZE,
             PC_PTYPE_PRICE,
         },
         deserialize::load,
+        error::OracleError,
         instruction::{
-            AddPriceArgs,
-            AddPublisherArgs,
-            CommandHeader,
+            builders,
             OracleCommand,
             UpdPermissionsArgs,
-            UpdPriceArgs,
         },
     },
-    bytemuck::{
-        bytes_of,
-        Pod,
-    },
+    bytemuck::Pod,
     serde::{
         Deserialize,
         Serialize,
@@ -33,17 +25,12 @@ use {
         },
         clock::Clock,
         hash::Hash,
-        instruction::{
-            AccountMeta,
-            Instruction,
-        },
+        instruction::Instruction,
         native_token::LAMPORTS_PER_SOL,
         pubkey::Pubkey,
         rent::Rent,
         stake_history::Epoch,
         system_instruction,
-        system_program,
-        sysvar::SysvarId,
     },
     solana_program_test::{
         read_file,
@@ -73,13 +60,12 @@ use {
 /// Simulator for the state of the pyth program on Solana. You can run solana transactions against
 /// this struct to test how pyth instructions execute in the Solana runtime.
 pub struct PythSimulator {
-    program_id:            Pubkey,
+    pub program_id:        Pubkey,
     context:               ProgramTestContext,
     /// Hash used to submit the last transaction. The hash must be advanced for each new
     /// transaction; otherwise, replayed transactions in different states can return stale
     /// results.
     last_blockhash:        Hash,
-    programdata_id:        Pubkey,
     pub upgrade_authority: Keypair,
     pub genesis_keypair:   Keypair,
 }
@@ -90,6 +76,20 @@ pub struct Quote {
     pub status:     u32,
 }
 
+/// A price account set up by `PythSimulator::setup_price_fixture`, e.g. to test the instructions
+/// configuring its extensions.
+pub struct PriceFixture {
+    pub program_id:       Pubkey,
+    /// The genesis account, which is the master authority
+    pub master_authority: Keypair,
+    pub price_keypair:    Keypair,
+    pub price:            Pubkey,
+    /// Funded publishers of the price account
+    pub publishers:       Vec<Keypair>,
+    /// Funded key without any permission
+    pub outsider:         Keypair,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 struct ProductMetadata {
     symbol:         String,
@@ -156,7 +156,6 @@ impl PythSimulator {
             program_id: program_key,
             context,
             last_blockhash,
-            programdata_id: programdata_key,
             upgrade_authority: upgrade_authority_keypair,
             genesis_keypair: copy_keypair(&genesis_keypair),
         };
@@ -210,6 +209,32 @@ impl PythSimulator {
             .await
     }
 
+    /// Process `instruction` signed by `authority`, e.g. to check the permissions of `authority`.
+    /// The genesis account pays for the transaction.
+    pub async fn process_ix_as(
+        &mut self,
+        instruction: Instruction,
+        authority: &Keypair,
+    ) -> Result<(), BanksClientError> {
+        self.process_ix_signed_by(instruction, &vec![authority])
+            .await
+    }
+
+    /// Process `instruction` signed by all the `signers`, e.g. the keys of a multisig authority.
+    /// The genesis account pays for the transaction.
+    pub async fn process_ix_signed_by(
+        &mut self,
+        instruction: Instruction,
+        signers: &Vec<&Keypair>,
+    ) -> Result<(), BanksClientError> {
+        self.process_ixs(
+            &[instruction],
+            signers,
+            &copy_keypair(&self.genesis_keypair),
+        )
+        .await
+    }
+
     /// Create an account owned by the pyth program containing `size` bytes.
     /// The account will be created with enough lamports to be rent-exempt.
     pub async fn create_pyth_account(&mut self, size: usize) -> Keypair {
@@ -239,20 +264,15 @@ impl PythSimulator {
     pub async fn init_mapping(&mut self) -> Result<Keypair, BanksClientError> {
         let mapping_keypair = self.create_pyth_account(size_of::<MappingAccount>()).await;
 
-        let cmd: CommandHeader = OracleCommand::InitMapping.into();
-        let instruction = Instruction::new_with_bytes(
-            self.program_id,
-            bytes_of(&cmd),
-            vec![
-                AccountMeta::new(self.genesis_keypair.pubkey(), true),
-                AccountMeta::new(mapping_keypair.pubkey(), true),
-                AccountMeta::new(self.get_permissions_pubkey(), false),
-            ],
+        let instruction = builders::init_mapping(
+            &self.program_id,
+            &self.genesis_keypair.pubkey(),
+            &mapping_keypair.pubkey(),
         );
 
         self.process_ixs(
             &[instruction],
-            &vec![&mapping_keypair],
+            &vec![],
             &copy_keypair(&self.genesis_keypair),
         )
         .await
@@ -267,21 +287,16 @@ impl PythSimulator {
     ) -> Result<Keypair, BanksClientError> {
         let product_keypair = self.create_pyth_account(PC_PROD_ACC_SIZE as usize).await;
 
-        let cmd: CommandHeader = OracleCommand::AddProduct.into();
-        let instruction = Instruction::new_with_bytes(
-            self.program_id,
-            bytes_of(&cmd),
-            vec![
-                AccountMeta::new(self.genesis_keypair.pubkey(), true),
-                AccountMeta::new(mapping_keypair.pubkey(), true),
-                AccountMeta::new(product_keypair.pubkey(), true),
-                AccountMeta::new(self.get_permissions_pubkey(), false),
-            ],
+        let instruction = builders::add_product(
+            &self.program_id,
+            &self.genesis_keypair.pubkey(),
+            &mapping_keypair.pubkey(),
+            &product_keypair.pubkey(),
         );
 
         self.process_ixs(
             &[instruction],
-            &vec![mapping_keypair, &product_keypair],
+            &vec![],
             &copy_keypair(&self.genesis_keypair),
         )
         .await
@@ -294,21 +309,16 @@ impl PythSimulator {
         mapping_keypair: &Keypair,
         product_keypair: &Keypair,
     ) -> Result<(), BanksClientError> {
-        let cmd: CommandHeader = OracleCommand::DelProduct.into();
-        let instruction = Instruction::new_with_bytes(
-            self.program_id,
-            bytes_of(&cmd),
-            vec![
-                AccountMeta::new(self.genesis_keypair.pubkey(), true),
-                AccountMeta::new(mapping_keypair.pubkey(), true),
-                AccountMeta::new(product_keypair.pubkey(), true),
-                AccountMeta::new(self.get_permissions_pubkey(), false),
-            ],
+        let instruction = builders::del_product(
+            &self.program_id,
+            &self.genesis_keypair.pubkey(),
+            &mapping_keypair.pubkey(),
+            &product_keypair.pubkey(),
         );
 
         self.process_ixs(
             &[instruction],
-            &vec![mapping_keypair, product_keypair],
+            &vec![],
             &copy_keypair(&self.genesis_keypair),
         )
         .await
@@ -325,25 +335,18 @@ impl PythSimulator {
             .create_pyth_account(size_of::<crate::accounts::PriceAccount>())
             .await;
 
-        let cmd = AddPriceArgs {
-            header:     OracleCommand::AddPrice.into(),
-            exponent:   expo,
-            price_type: PC_PTYPE_PRICE,
-        };
-        let instruction = Instruction::new_with_bytes(
-            self.program_id,
-            bytes_of(&cmd),
-            vec![
-                AccountMeta::new(self.genesis_keypair.pubkey(), true),
-                AccountMeta::new(product_keypair.pubkey(), true),
-                AccountMeta::new(price_keypair.pubkey(), true),
-                AccountMeta::new(self.get_permissions_pubkey(), false),
-            ],
+        let instruction = builders::add_price(
+            &self.program_id,
+            &self.genesis_keypair.pubkey(),
+            &product_keypair.pubkey(),
+            &price_keypair.pubkey(),
+            expo,
+            PC_PTYPE_PRICE,
         );
 
         self.process_ixs(
             &[instruction],
-            &vec![product_keypair, &price_keypair],
+            &vec![],
             &copy_keypair(&self.genesis_keypair),
         )
         .await
@@ -356,23 +359,16 @@ impl PythSimulator {
         price_keypair: &Keypair,
         publisher: Pubkey,
     ) -> Result<(), BanksClientError> {
-        let cmd = AddPublisherArgs {
-            header: OracleCommand::AddPublisher.into(),
-            publisher,
-        };
-        let instruction = Instruction::new_with_bytes(
-            self.program_id,
-            bytes_of(&cmd),
-            vec![
-                AccountMeta::new(self.genesis_keypair.pubkey(), true),
-                AccountMeta::new(price_keypair.pubkey(), true),
-                AccountMeta::new(self.get_permissions_pubkey(), false),
-            ],
+        let instruction = builders::add_publisher(
+            &self.program_id,
+            &self.genesis_keypair.pubkey(),
+            &price_keypair.pubkey(),
+            &publisher,
         );
 
         self.process_ixs(
             &[instruction],
-            &vec![price_keypair],
+            &vec![],
             &copy_keypair(&self.genesis_keypair),
         )
         .await
@@ -404,22 +400,14 @@ impl PythSimulator {
         let mut instructions: Vec<Instruction> = vec![];
 
         for (key, price_account) in price_accounts {
-            let cmd = UpdPriceArgs {
-                header:          OracleCommand::UpdPrice.into(),
-                status:          quotes[key].status,
-                unused_:         0,
-                price:           quotes[key].price,
-                confidence:      quotes[key].confidence,
-                publishing_slot: slot,
-            };
-            instructions.push(Instruction::new_with_bytes(
-                self.program_id,
-                bytes_of(&cmd),
-                vec![
-                    AccountMeta::new(publisher.pubkey(), true),
-                    AccountMeta::new(*price_account, false),
-                    AccountMeta::new(Clock::id(), false),
-                ],
+            instructions.push(builders::upd_price(
+                &self.program_id,
+                &publisher.pubkey(),
+                price_account,
+                quotes[key].status,
+                quotes[key].price,
+                quotes[key].confidence,
+                slot,
             ));
         }
 
@@ -433,21 +421,16 @@ impl PythSimulator {
         product_keypair: &Keypair,
         price_keypair: &Keypair,
     ) -> Result<(), BanksClientError> {
-        let cmd: CommandHeader = OracleCommand::DelPrice.into();
-        let instruction = Instruction::new_with_bytes(
-            self.program_id,
-            bytes_of(&cmd),
-            vec![
-                AccountMeta::new(self.genesis_keypair.pubkey(), true),
-                AccountMeta::new(product_keypair.pubkey(), true),
-                AccountMeta::new(price_keypair.pubkey(), true),
-                AccountMeta::new(self.get_permissions_pubkey(), false),
-            ],
+        let instruction = builders::del_price(
+            &self.program_id,
+            &self.genesis_keypair.pubkey(),
+            &product_keypair.pubkey(),
+            &price_keypair.pubkey(),
         );
 
         self.process_ixs(
             &[instruction],
-            &vec![product_keypair, price_keypair],
+            &vec![],
             &copy_keypair(&self.genesis_keypair),
         )
         .await
@@ -462,15 +445,12 @@ impl PythSimulator {
     ) -> Result<Pubkey, BanksClientError> {
         let permissions_pubkey = self.get_permissions_pubkey();
 
-        let instruction = Instruction::new_with_bytes(
-            self.program_id,
-            bytes_of(&cmd_args),
-            vec![
-                AccountMeta::new(payer.pubkey(), true),
-                AccountMeta::new_readonly(self.programdata_id, false),
-                AccountMeta::new(permissions_pubkey, false),
-                AccountMeta::new_readonly(system_program::id(), false),
-            ],
+        let instruction = builders::upd_permissions(
+            &self.program_id,
+            &payer.pubkey(),
+            &cmd_args.master_authority,
+            &cmd_args.data_curation_authority,
+            &cmd_args.security_authority,
         );
 
         self.process_ixs(&[instruction], &vec![], payer)
@@ -478,6 +458,61 @@ impl PythSimulator {
             .map(|_| permissions_pubkey)
     }
 
+    /// Set the multisig authority of the permissions account (using the set_multisig_authority
+    /// instruction). The upgrade authority signs and pays for the transaction.
+    pub async fn set_multisig_authority(
+        &mut self,
+        threshold: u8,
+        signers: &[Pubkey],
+    ) -> Result<(), BanksClientError> {
+        let upgrade_authority = copy_keypair(&self.upgrade_authority);
+        let instruction = builders::set_multisig_authority(
+            &self.program_id,
+            &upgrade_authority.pubkey(),
+            threshold,
+            signers,
+        );
+
+        self.process_ixs(&[instruction], &vec![], &upgrade_authority)
+            .await
+    }
+
+    /// Propose new authorities (using the propose_authorities instruction). The upgrade authority
+    /// signs and pays for the transaction.
+    pub async fn propose_authorities(
+        &mut self,
+        cmd_args: UpdPermissionsArgs,
+        delay_slots: u64,
+        min_delay_slots: u64,
+    ) -> Result<(), BanksClientError> {
+        let upgrade_authority = copy_keypair(&self.upgrade_authority);
+        let instruction = builders::propose_authorities(
+            &self.program_id,
+            &upgrade_authority.pubkey(),
+            &cmd_args.master_authority,
+            &cmd_args.data_curation_authority,
+            &cmd_args.security_authority,
+            delay_slots,
+            min_delay_slots,
+        );
+
+        self.process_ixs(&[instruction], &vec![], &upgrade_authority)
+            .await
+    }
+
+    /// Replace the authorities right away, proposing them without delay and accepting them as
+    /// `master_authority`, which must be the proposed master authority.
+    pub async fn transfer_authorities(
+        &mut self,
+        cmd_args: UpdPermissionsArgs,
+        master_authority: &Keypair,
+    ) -> Result<(), BanksClientError> {
+        self.propose_authorities(cmd_args, 0, 0).await?;
+        let instruction =
+            builders::accept_authorities(&self.program_id, &master_authority.pubkey());
+        self.process_ix_as(instruction, master_authority).await
+    }
+
     /// Get the account at `key`. Returns `None` if no such account exists.
     pub async fn get_account(&mut self, key: Pubkey) -> Option<Account> {
         self.context.banks_client.get_account(key).await.unwrap()
@@ -510,9 +545,7 @@ impl PythSimulator {
     }
 
     pub fn get_permissions_pubkey(&self) -> Pubkey {
-        let (permissions_pubkey, __bump) =
-            Pubkey::find_program_address(&[PERMISSIONS_SEED.as_bytes()], &self.program_id);
-        permissions_pubkey
+        builders::permissions_pubkey(&self.program_id)
     }
 
     /// Setup 3 product accounts with 1 price account each and add a publisher to all of them.
@@ -554,6 +587,61 @@ impl PythSimulator {
         price_accounts
     }
 
+    /// Setup a price account with `num_publishers` publishers under new mapping and product
+    /// accounts.
+    pub async fn setup_price_fixture(&mut self, num_publishers: usize) -> PriceFixture {
+        let mapping_keypair = self.init_mapping().await.unwrap();
+        let product_keypair = self.add_product(&mapping_keypair).await.unwrap();
+        let price_keypair = self.add_price(&product_keypair, -8).await.unwrap();
+
+        let publishers: Vec<Keypair> = (0..num_publishers).map(|_| Keypair::new()).collect();
+        let outsider = Keypair::new();
+        for keypair in publishers.iter().chain(once(&outsider)) {
+            self.airdrop(&keypair.pubkey(), LAMPORTS_PER_SOL)
+                .await
+                .unwrap();
+        }
+        for publisher in publishers.iter() {
+            self.add_publisher(&price_keypair, publisher.pubkey())
+                .await
+                .unwrap();
+        }
+
+        PriceFixture {
+            program_id: self.program_id,
+            master_authority: copy_keypair(&self.genesis_keypair),
+            price: price_keypair.pubkey(),
+            price_keypair,
+            publishers,
+            outsider,
+        }
+    }
+
+    /// Check that `instruction`, signed by `unauthorized`, fails because it requires the
+    /// permission of an authority.
+    pub async fn assert_permission_violation(
+        &mut self,
+        instruction: Instruction,
+        unauthorized: &Keypair,
+    ) {
+        assert_eq!(
+            self.process_ix_as(instruction, unauthorized)
+                .await
+                .unwrap_err()
+                .unwrap(),
+            OracleError::PermissionViolation.into()
+        );
+    }
+
+    /// Get the price account at `price`, checking that it has been resized to `space` bytes and
+    /// is still rent-exempt.
+    pub async fn get_resized_account(&mut self, price: Pubkey, space: usize) -> Account {
+        let price_account = self.get_account(price).await.unwrap();
+        assert_eq!(price_account.data.len(), space);
+        assert!(Rent::default().is_exempt(price_account.lamports, space));
+        price_account
+    }
+
     /// Advance clock to slot `slot`.
     pub async fn warp_to_slot(&mut self, slot: u64) -> Result<(), ProgramTestError> {
         self.context.warp_to_slot(slot)
@@ -0,0 +1,187 @@
+use {
+    crate::{
+        accounts::{
+            AggregateConfidence,
+            AggregateConfidenceMessage,
+            PriceAccount,
+            PriceAccountFlags,
+        },
+        c_oracle_header::{
+            PC_STATUS_TRADING,
+            PC_STATUS_UNKNOWN,
+        },
+        instruction::builders,
+        tests::pyth_simulator::{
+            PriceFixture,
+            PythSimulator,
+            Quote,
+        },
+    },
+    bytemuck::Zeroable,
+    solana_program::pubkey::Pubkey,
+    solana_sdk::signer::Signer,
+    std::mem::size_of,
+};
+
+#[test]
+fn test_aggregate_confidence_message() {
+    let key = Pubkey::new_unique();
+    let mut price_account = PriceAccount::zeroed();
+    price_account.exponent = -8;
+    price_account.agg_.status_ = PC_STATUS_TRADING;
+    price_account.agg_.price_ = 110;
+    price_account.agg_.conf_ = 80;
+    price_account.agg_.pub_slot_ = 20;
+    price_account.timestamp_ = 1000;
+    price_account.prev_price_ = 100;
+    price_account.prev_conf_ = 5;
+    price_account.prev_slot_ = 10;
+    price_account.prev_timestamp_ = 990;
+
+    let aggregate_confidence = AggregateConfidence {
+        pub_slot:   20,
+        conf_left:  10,
+        conf_right: 80,
+    };
+    assert_eq!(
+        price_account.as_aggregate_confidence_message(&key, &aggregate_confidence),
+        AggregateConfidenceMessage {
+            feed_id:           key.to_bytes(),
+            price:             110,
+            conf_left:         10,
+            conf_right:        80,
+            exponent:          -8,
+            publish_time:      1000,
+            prev_publish_time: 990,
+        }
+    );
+
+    // The previous aggregate predates the extension, both sides are its confidence
+    price_account.agg_.status_ = PC_STATUS_UNKNOWN;
+    assert_eq!(
+        price_account.as_aggregate_confidence_message(&key, &aggregate_confidence),
+        AggregateConfidenceMessage {
+            feed_id:           key.to_bytes(),
+            price:             100,
+            conf_left:         5,
+            conf_right:        5,
+            exponent:          -8,
+            publish_time:      990,
+            prev_publish_time: 990,
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_aggregate_confidence() {
+    let mut sim = PythSimulator::new().await;
+    let PriceFixture {
+        program_id,
+        master_authority,
+        price,
+        publishers,
+        outsider,
+        ..
+    } = sim.setup_price_fixture(3).await;
+    sim.process_ix_as(
+        builders::set_min_pub(&program_id, &master_authority.pubkey(), &price, 1),
+        &master_authority,
+    )
+    .await
+    .unwrap();
+
+    sim.assert_permission_violation(
+        builders::init_aggregate_confidence(&program_id, &outsider.pubkey(), &price),
+        &outsider,
+    )
+    .await;
+    sim.process_ix_as(
+        builders::init_aggregate_confidence(&program_id, &master_authority.pubkey(), &price),
+        &master_authority,
+    )
+    .await
+    .unwrap();
+    sim.get_resized_account(
+        price,
+        PriceAccount::EXTENSIONS_HEADER_SPACE + size_of::<AggregateConfidence>(),
+    )
+    .await;
+
+    // Each round aggregates the quotes of the previous one, with the stake-weighted aggregation
+    // in the last round
+    let expected_aggregates = [
+        AggregateConfidence::zeroed(),
+        AggregateConfidence {
+            pub_slot:   20,
+            conf_left:  10,
+            conf_right: 80,
+        },
+        AggregateConfidence {
+            pub_slot:   30,
+            conf_left:  10,
+            conf_right: 10,
+        },
+    ];
+    for (round, expected_aggregate) in expected_aggregates.into_iter().enumerate() {
+        if round == 2 {
+            for (publisher, weight) in publishers.iter().zip([1, 1, 10]) {
+                sim.process_ix_as(
+                    builders::set_publisher_weight(
+                        &program_id,
+                        &master_authority.pubkey(),
+                        &price,
+                        &publisher.pubkey(),
+                        weight,
+                    ),
+                    &master_authority,
+                )
+                .await
+                .unwrap();
+            }
+            sim.process_ix_as(
+                builders::set_price_flags(
+                    &program_id,
+                    &master_authority.pubkey(),
+                    &price,
+                    PriceAccountFlags::STAKE_WEIGHTED,
+                    PriceAccountFlags::empty(),
+                ),
+                &master_authority,
+            )
+            .await
+            .unwrap();
+        }
+
+        sim.warp_to_slot(10 * (round as u64 + 1)).await.unwrap();
+        for (publisher, price_value) in publishers.iter().zip([100, 110, 200]) {
+            sim.upd_price(
+                publisher,
+                price,
+                Quote {
+                    price:      price_value,
+                    confidence: 10,
+                    status:     PC_STATUS_TRADING,
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        let price_account = sim.get_account(price).await.unwrap();
+        let aggregate_confidence = PriceAccount::aggregate_confidence(&price_account.data).unwrap();
+        assert_eq!(aggregate_confidence, expected_aggregate);
+        if round != 0 {
+            let price_data = sim
+                .get_account_data_as::<PriceAccount>(price)
+                .await
+                .unwrap();
+            assert_eq!(price_data.agg_.status_, PC_STATUS_TRADING);
+            assert_eq!(
+                price_data.agg_.conf_,
+                expected_aggregate
+                    .conf_left
+                    .max(expected_aggregate.conf_right)
+            );
+        }
+    }
+}
@@ -17,13 +17,10 @@ use {
         instruction::{
             AddPublisherArgs,
             OracleCommand,
+            SetPriceFlagsArgs,
             UpdPriceArgs,
         },
-        processor::{
-            process_instruction,
-            DISABLE_ACCUMULATOR_V2,
-            ENABLE_ACCUMULATOR_V2,
-        },
+        processor::process_instruction,
         tests::test_utils::{
             update_clock_slot,
             AccountSetup,
@@ -75,10 +72,10 @@ impl Accounts {
     }
 }
 
-fn add_publisher(accounts: &mut Accounts, publisher: Option<Pubkey>) {
+fn add_publisher(accounts: &mut Accounts) {
     let args = AddPublisherArgs {
         header:    OracleCommand::AddPublisher.into(),
-        publisher: publisher.unwrap_or(*accounts.publisher_account.as_account_info().key),
+        publisher: *accounts.publisher_account.as_account_info().key,
     };
 
     assert!(process_instruction(
@@ -93,6 +90,30 @@ fn add_publisher(accounts: &mut Accounts, publisher: Option<Pubkey>) {
     .is_ok());
 }
 
+fn set_price_flags(
+    accounts: &mut Accounts,
+    set_flags: PriceAccountFlags,
+    clear_flags: PriceAccountFlags,
+) {
+    let args = SetPriceFlagsArgs {
+        header:      OracleCommand::SetPriceFlags.into(),
+        set_flags:   set_flags.bits(),
+        clear_flags: clear_flags.bits(),
+        unused_:     [0; 2],
+    };
+
+    assert!(process_instruction(
+        &accounts.program_id,
+        &[
+            accounts.funding_account.as_account_info(),
+            accounts.price_account.as_account_info(),
+            accounts.permissions_account.as_account_info(),
+        ],
+        bytes_of::<SetPriceFlagsArgs>(&args)
+    )
+    .is_ok());
+}
+
 fn update_price(accounts: &mut Accounts, price: i64, conf: u64, slot: u64) {
     let instruction_data = &mut [0u8; size_of::<UpdPriceArgs>()];
     let mut cmd = load_mut::<UpdPriceArgs>(instruction_data).unwrap();
@@ -101,7 +122,7 @@ fn update_price(accounts: &mut Accounts, price: i64, conf: u64, slot: u64) {
     cmd.price = price;
     cmd.confidence = conf;
     cmd.publishing_slot = slot;
-    cmd.unused_ = 0;
+    cmd.corp_act_status = 0;
 
     let mut clock = accounts.clock_account.as_account_info();
     clock.is_signer = false;
@@ -124,7 +145,7 @@ fn test_aggregate_v2_toggle() {
     let accounts = &mut Accounts::new();
 
     // Add an initial Publisher to test with.
-    add_publisher(accounts, None);
+    add_publisher(accounts);
 
     // Update the price, no aggregation will happen on the first slot.
     {
@@ -147,7 +168,11 @@ fn test_aggregate_v2_toggle() {
     }
 
     // Enable v2 Aggregation
-    add_publisher(accounts, Some(ENABLE_ACCUMULATOR_V2.into()));
+    set_price_flags(
+        accounts,
+        PriceAccountFlags::ACCUMULATOR_V2,
+        PriceAccountFlags::empty(),
+    );
 
     // Update again, with accumulator bit set, aggregation should not have
     // happened, as its now the validators job.
@@ -160,7 +185,12 @@ fn test_aggregate_v2_toggle() {
         assert!(price_data.flags.contains(PriceAccountFlags::ACCUMULATOR_V2));
     }
 
-    add_publisher(accounts, Some(DISABLE_ACCUMULATOR_V2.into()));
+    // Disable v2 Aggregation
+    set_price_flags(
+        accounts,
+        PriceAccountFlags::empty(),
+        PriceAccountFlags::ACCUMULATOR_V2,
+    );
 
     // Confirm disabling v2 Aggregation re-enables the aggregation flow.
     {
@@ -1,13 +1,22 @@
 use {
     crate::{
-        accounts::PriceAccount,
-        processor::c_upd_aggregate,
+        accounts::{
+            OutlierFilter,
+            PriceAccount,
+            PublisherWeights,
+        },
+        aggregation::{
+            upd_aggregate,
+            weighted::upd_aggregate_weighted,
+        },
+        c_oracle_header::PC_STATUS_TRADING,
     },
     bytemuck::Zeroable,
     serde::{
         Deserialize,
         Serialize,
     },
+    solana_program::pubkey::Pubkey,
     std::fs::File,
 };
 extern crate test_generator;
@@ -18,42 +27,100 @@ use test_generator::test_resources;
 fn test_quote_set(input_path_raw: &str) {
     // For some reason these tests have a different working directory than the macro.
     let input_path = input_path_raw.replace("program/rust/", "");
-    let result_path = input_path.replace(".json", ".result");
+    let quote_set = read_quote_set(&input_path);
 
-    let file = File::open(input_path).expect("Test file not found");
-    let quote_set: QuoteSet = serde_json::from_reader(&file).expect("Unable to parse JSON");
-
-    let result_file = File::open(result_path).expect("Test file not found");
-    let expected_result: QuoteSetResult =
-        serde_json::from_reader(&result_file).expect("Unable to parse JSON");
+    let mut price_account = price_account_from_quote_set(&quote_set);
+    upd_aggregate(&mut price_account, CURRENT_SLOT + 1, CURRENT_TIMESTAMP);
 
-    let current_slot = 1000; // arbitrary
-    let current_timestamp = 1234; // also arbitrary
-    let mut price_account: PriceAccount = PriceAccount::zeroed();
+    assert_eq!(read_result(&input_path), quote_set_result(&price_account));
+}
 
-    price_account.last_slot_ = current_slot;
-    price_account.agg_.pub_slot_ = current_slot;
-    price_account.exponent = quote_set.exponent;
-    price_account.num_ = quote_set.quotes.len() as u32;
-    for quote_idx in 0..quote_set.quotes.len() {
-        let mut current_component = &mut price_account.comp_[quote_idx];
-        let quote = &quote_set.quotes[quote_idx];
-        current_component.latest_.status_ = quote.status;
-        current_component.latest_.price_ = quote.price;
-        current_component.latest_.conf_ = quote.conf;
-        let slot_diff = quote.slot_diff.unwrap_or(0);
-        assert!(slot_diff > -(current_slot as i64));
-        current_component.latest_.pub_slot_ = ((current_slot as i64) + slot_diff) as u64;
+// The stake-weighted aggregation gives the same results when every publisher has the same
+// weight.
+#[test_resources("program/rust/test_data/aggregation/*.json")]
+fn test_quote_set_equal_weights(input_path_raw: &str) {
+    let input_path = input_path_raw.replace("program/rust/", "");
+    let mut quote_set = read_quote_set(&input_path);
+    for quote in quote_set.quotes.iter_mut() {
+        quote.weight = Some(7);
     }
 
-    unsafe {
-        c_upd_aggregate(
-            (&mut price_account as *mut PriceAccount) as *mut u8,
-            current_slot + 1,
-            current_timestamp,
-        );
-    }
+    let mut price_account = price_account_from_quote_set(&quote_set);
+    let publisher_weights = publisher_weights_from_quote_set(&quote_set);
+    upd_aggregate_weighted(
+        &mut price_account,
+        CURRENT_SLOT + 1,
+        CURRENT_TIMESTAMP,
+        |publisher| publisher_weights.weight(publisher),
+        None,
+        None,
+        None,
+    );
+
+    assert_eq!(read_result(&input_path), quote_set_result(&price_account));
+}
+
+#[test_resources("program/rust/test_data/aggregation_weighted/*.json")]
+fn test_weighted_quote_set(input_path_raw: &str) {
+    let input_path = input_path_raw.replace("program/rust/", "");
+    let quote_set = read_quote_set(&input_path);
 
+    let mut price_account = price_account_from_quote_set(&quote_set);
+    let publisher_weights = publisher_weights_from_quote_set(&quote_set);
+    upd_aggregate_weighted(
+        &mut price_account,
+        CURRENT_SLOT + 1,
+        CURRENT_TIMESTAMP,
+        |publisher| publisher_weights.weight(publisher),
+        None,
+        None,
+        None,
+    );
+
+    assert_eq!(read_result(&input_path), quote_set_result(&price_account));
+}
+
+#[test_resources("program/rust/test_data/aggregation_outliers/*.json")]
+fn test_outlier_quote_set(input_path_raw: &str) {
+    let input_path = input_path_raw.replace("program/rust/", "");
+    let quote_set = read_quote_set(&input_path);
+    let outlier_filter_config = quote_set
+        .outlier_filter
+        .as_ref()
+        .expect("Outlier filter not found");
+
+    let mut price_account = price_account_from_quote_set(&quote_set);
+    let mut outlier_filter = OutlierFilter {
+        mode: outlier_filter_config.mode,
+        k: outlier_filter_config.k,
+        ..OutlierFilter::zeroed()
+    };
+    upd_aggregate_weighted(
+        &mut price_account,
+        CURRENT_SLOT + 1,
+        CURRENT_TIMESTAMP,
+        |_| 1,
+        Some(&mut outlier_filter),
+        None,
+        None,
+    );
+
+    assert_eq!(
+        read_result(&input_path),
+        QuoteSetResult {
+            num_rejected: Some(outlier_filter.num_rejected),
+            ..quote_set_result(&price_account)
+        }
+    );
+}
+
+fn read_result(input_path: &str) -> QuoteSetResult {
+    let result_file =
+        File::open(input_path.replace(".json", ".result")).expect("Test file not found");
+    serde_json::from_reader(&result_file).expect("Unable to parse JSON")
+}
+
+fn quote_set_result(price_account: &PriceAccount) -> QuoteSetResult {
     // For some idiotic reason the status in the input is a number and the output is a string.
     let result_status: String = match price_account.agg_.status_ {
         0 => "unknown",
@@ -65,14 +132,85 @@ fn test_quote_set(input_path_raw: &str) {
     }
     .into();
 
-    let actual_result = QuoteSetResult {
-        exponent: price_account.exponent,
-        price:    price_account.agg_.price_,
-        conf:     price_account.agg_.conf_,
-        status:   result_status,
+    QuoteSetResult {
+        exponent:     price_account.exponent,
+        price:        price_account.agg_.price_,
+        conf:         price_account.agg_.conf_,
+        status:       result_status,
+        num_rejected: None,
+    }
+}
+
+// The Rust port of the aggregation logic must produce exactly the same price account as the C code.
+#[cfg(not(feature = "rust-aggregation"))]
+#[test_resources("program/rust/test_data/aggregation/*.json")]
+fn test_quote_set_rust_matches_c(input_path_raw: &str) {
+    use crate::aggregation::{
+        c,
+        rust,
     };
 
-    assert_eq!(expected_result, actual_result);
+    let input_path = input_path_raw.replace("program/rust/", "");
+    let quote_set = read_quote_set(&input_path);
+
+    let mut c_price_account = price_account_from_quote_set(&quote_set);
+    let mut rust_price_account = price_account_from_quote_set(&quote_set);
+    assert_eq!(
+        c::upd_aggregate(&mut c_price_account, CURRENT_SLOT + 1, CURRENT_TIMESTAMP),
+        rust::upd_aggregate(&mut rust_price_account, CURRENT_SLOT + 1, CURRENT_TIMESTAMP)
+    );
+    assert_eq!(
+        bytemuck::bytes_of(&c_price_account),
+        bytemuck::bytes_of(&rust_price_account)
+    );
+}
+
+const CURRENT_SLOT: u64 = 1000; // arbitrary
+const CURRENT_TIMESTAMP: i64 = 1234; // also arbitrary
+
+fn read_quote_set(input_path: &str) -> QuoteSet {
+    let file = File::open(input_path).expect("Test file not found");
+    serde_json::from_reader(&file).expect("Unable to parse JSON")
+}
+
+fn price_account_from_quote_set(quote_set: &QuoteSet) -> PriceAccount {
+    let mut price_account: PriceAccount = PriceAccount::zeroed();
+
+    price_account.last_slot_ = CURRENT_SLOT;
+    price_account.agg_.pub_slot_ = CURRENT_SLOT;
+    price_account.exponent = quote_set.exponent;
+    price_account.num_ = quote_set.quotes.len() as u32;
+    if let Some(prev_aggregate) = &quote_set.prev_aggregate {
+        price_account.agg_.status_ = PC_STATUS_TRADING;
+        price_account.agg_.price_ = prev_aggregate.price;
+        price_account.agg_.conf_ = prev_aggregate.conf;
+    }
+    for quote_idx in 0..quote_set.quotes.len() {
+        let mut current_component = &mut price_account.comp_[quote_idx];
+        let quote = &quote_set.quotes[quote_idx];
+        current_component.pub_ = publisher(quote_idx);
+        current_component.latest_.status_ = quote.status;
+        current_component.latest_.price_ = quote.price;
+        current_component.latest_.conf_ = quote.conf;
+        let slot_diff = quote.slot_diff.unwrap_or(0);
+        assert!(slot_diff > -(CURRENT_SLOT as i64));
+        current_component.latest_.pub_slot_ = ((CURRENT_SLOT as i64) + slot_diff) as u64;
+    }
+
+    price_account
+}
+
+/// The weights of the quotes of `quote_set`, a missing weight being 0
+fn publisher_weights_from_quote_set(quote_set: &QuoteSet) -> PublisherWeights {
+    let mut publisher_weights = PublisherWeights::zeroed();
+    for (quote_idx, quote) in quote_set.quotes.iter().enumerate() {
+        assert!(publisher_weights.set_weight(&publisher(quote_idx), quote.weight.unwrap_or(0)));
+    }
+    publisher_weights
+}
+
+fn publisher(quote_idx: usize) -> Pubkey {
+    Pubkey::new_from_array([quote_idx as u8 + 1; 32])
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -81,18 +219,38 @@ struct Quote {
     conf:      u64,
     status:    u32,
     slot_diff: Option<i64>,
+    /// Weight of the publisher in the stake-weighted aggregation
+    weight:    Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 struct QuoteSet {
-    exponent: i32,
-    quotes:   Vec<Quote>,
+    exponent:       i32,
+    quotes:         Vec<Quote>,
+    /// Outlier filter applied before the aggregation
+    outlier_filter: Option<OutlierFilterConfig>,
+    /// Aggregate of the previous slot, which has TRADING status
+    prev_aggregate: Option<PrevAggregate>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct OutlierFilterConfig {
+    mode: u32,
+    k:    u32,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct PrevAggregate {
+    price: i64,
+    conf:  u64,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
 struct QuoteSetResult {
-    exponent: i32,
-    price:    i64,
-    conf:     u64,
-    status:   String,
+    exponent:     i32,
+    price:        i64,
+    conf:         u64,
+    status:       String,
+    /// Number of quotes rejected by the outlier filter
+    num_rejected: Option<u32>,
 }
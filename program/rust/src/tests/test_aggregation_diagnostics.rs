@@ -0,0 +1,247 @@
+use {
+    crate::{
+        accounts::{
+            AggregationDiagnostics,
+            ExclusionReason,
+            OutlierFilterMode,
+            PriceAccount,
+        },
+        c_oracle_header::{
+            PC_STATUS_TRADING,
+            PC_STATUS_UNKNOWN,
+        },
+        instruction::builders,
+        tests::pyth_simulator::{
+            PriceFixture,
+            PythSimulator,
+            Quote,
+        },
+    },
+    bytemuck::Zeroable,
+    solana_program::pubkey::Pubkey,
+    solana_sdk::{
+        signature::Keypair,
+        signer::Signer,
+    },
+    std::mem::size_of,
+};
+
+#[test]
+fn test_exclusion_reason() {
+    let mut diagnostics = AggregationDiagnostics::zeroed();
+    diagnostics.reasons[1] = ExclusionReason::Stale as u8;
+    diagnostics.reasons[2] = 200;
+    assert_eq!(diagnostics.exclusion_reason(0), None);
+    assert_eq!(
+        diagnostics.exclusion_reason(1),
+        Some(ExclusionReason::Stale)
+    );
+    assert_eq!(diagnostics.exclusion_reason(2), None);
+    assert_eq!(
+        diagnostics.exclusion_reason(diagnostics.reasons.len()),
+        None
+    );
+}
+
+#[tokio::test]
+async fn test_aggregation_diagnostics() {
+    let mut sim = PythSimulator::new().await;
+    let PriceFixture {
+        program_id,
+        master_authority,
+        price,
+        publishers,
+        outsider,
+        ..
+    } = sim.setup_price_fixture(5).await;
+    sim.process_ix_as(
+        builders::set_min_pub(&program_id, &master_authority.pubkey(), &price, 1),
+        &master_authority,
+    )
+    .await
+    .unwrap();
+
+    // Only the authorities can initialize the diagnostics
+    sim.assert_permission_violation(
+        builders::init_aggregation_diagnostics(&program_id, &outsider.pubkey(), &price),
+        &outsider,
+    )
+    .await;
+
+    sim.process_ix_as(
+        builders::init_aggregation_diagnostics(&program_id, &master_authority.pubkey(), &price),
+        &master_authority,
+    )
+    .await
+    .unwrap();
+    let price_account = sim
+        .get_resized_account(
+            price,
+            PriceAccount::EXTENSIONS_HEADER_SPACE + size_of::<AggregationDiagnostics>(),
+        )
+        .await;
+    assert_eq!(
+        PriceAccount::aggregation_diagnostics(&price_account.data)
+            .unwrap()
+            .slot,
+        0
+    );
+
+    // The last publisher only publishes once, its quote is stale by the last aggregation
+    sim.warp_to_slot(10).await.unwrap();
+    sim.upd_price(&publishers[4], price, quote((100, 1, PC_STATUS_TRADING)))
+        .await
+        .unwrap();
+
+    let quotes = [
+        (100, 1, PC_STATUS_TRADING),
+        (100, 1, PC_STATUS_UNKNOWN),
+        // Too wide for the confidence-to-price ratio threshold
+        (100, 90, PC_STATUS_TRADING),
+        (100, 0, PC_STATUS_TRADING),
+    ];
+    for slot in [40, 41] {
+        sim.warp_to_slot(slot).await.unwrap();
+        for (publisher, quote_values) in publishers.iter().zip(quotes.iter()) {
+            sim.upd_price(publisher, price, quote(*quote_values))
+                .await
+                .unwrap();
+        }
+    }
+
+    let expected_reasons = [
+        None,
+        Some(ExclusionReason::Status),
+        Some(ExclusionReason::ConfidenceRatio),
+        Some(ExclusionReason::InvalidConfidence),
+        Some(ExclusionReason::Stale),
+    ];
+    assert_eq!(
+        exclusion_reasons(&mut sim, price, &publishers).await,
+        (41, expected_reasons.to_vec())
+    );
+
+    // The outlier filter runs the Rust aggregation, which records the rejected quotes
+    sim.process_ix_as(
+        builders::set_outlier_filter(
+            &program_id,
+            &master_authority.pubkey(),
+            &price,
+            Some(OutlierFilterMode::Median),
+            3,
+        ),
+        &master_authority,
+    )
+    .await
+    .unwrap();
+    let quotes = [100, 101, 99, 10000, 100].map(|price| (price, 1, PC_STATUS_TRADING));
+    for slot in [50, 51] {
+        sim.warp_to_slot(slot).await.unwrap();
+        for (publisher, quote_values) in publishers.iter().zip(quotes.iter()) {
+            sim.upd_price(publisher, price, quote(*quote_values))
+                .await
+                .unwrap();
+        }
+    }
+
+    let expected_reasons = [None, None, None, Some(ExclusionReason::Outlier), None];
+    assert_eq!(
+        exclusion_reasons(&mut sim, price, &publishers).await,
+        (51, expected_reasons.to_vec())
+    );
+
+    // The quote of a blocked publisher is left out of the aggregations given the blocklist, but
+    // it is stored unchanged
+    sim.process_ix_as(
+        builders::block_publisher(
+            &program_id,
+            &master_authority.pubkey(),
+            &publishers[1].pubkey(),
+        ),
+        &master_authority,
+    )
+    .await
+    .unwrap();
+    for slot in [60, 61] {
+        sim.warp_to_slot(slot).await.unwrap();
+        for (publisher, (price_value, conf, status)) in publishers.iter().zip(quotes.iter()) {
+            let upd_price = builders::upd_price(
+                &program_id,
+                &publisher.pubkey(),
+                &price,
+                *status,
+                *price_value,
+                *conf,
+                slot,
+            );
+            if publisher.pubkey() == publishers[1].pubkey() {
+                sim.process_ix_as(upd_price, publisher).await.unwrap();
+            } else {
+                sim.process_ix_as(
+                    builders::with_publisher_blocklist(upd_price, &program_id),
+                    publisher,
+                )
+                .await
+                .unwrap();
+            }
+        }
+    }
+
+    let expected_reasons = [
+        None,
+        Some(ExclusionReason::Blocked),
+        None,
+        Some(ExclusionReason::Outlier),
+        None,
+    ];
+    assert_eq!(
+        exclusion_reasons(&mut sim, price, &publishers).await,
+        (61, expected_reasons.to_vec())
+    );
+    let price_data = sim
+        .get_account_data_as::<PriceAccount>(price)
+        .await
+        .unwrap();
+    let blocked_comp = price_data
+        .comp_
+        .iter()
+        .find(|comp| comp.pub_ == publishers[1].pubkey())
+        .unwrap();
+    assert_eq!(blocked_comp.latest_.status_, PC_STATUS_TRADING);
+}
+
+fn quote((price, confidence, status): (i64, u64, u32)) -> Quote {
+    Quote {
+        price,
+        confidence,
+        status,
+    }
+}
+
+/// The slot of the last aggregation and the exclusion reasons of the quotes of `publishers`
+async fn exclusion_reasons(
+    sim: &mut PythSimulator,
+    price: Pubkey,
+    publishers: &[Keypair],
+) -> (u64, Vec<Option<ExclusionReason>>) {
+    let price_account = sim.get_account(price).await.unwrap();
+    let diagnostics = PriceAccount::aggregation_diagnostics(&price_account.data).unwrap();
+    let price_data = sim
+        .get_account_data_as::<PriceAccount>(price)
+        .await
+        .unwrap();
+    let comps = &price_data.comp_[..price_data.num_ as usize];
+    (
+        diagnostics.slot,
+        publishers
+            .iter()
+            .map(|publisher| {
+                let index = comps
+                    .iter()
+                    .position(|comp| comp.pub_ == publisher.pubkey())
+                    .unwrap();
+                diagnostics.exclusion_reason(index)
+            })
+            .collect(),
+    )
+}
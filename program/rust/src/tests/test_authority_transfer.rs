@@ -0,0 +1,216 @@
+use {
+    crate::{
+        accounts::PermissionAccount,
+        error::OracleError,
+        instruction::{
+            builders,
+            OracleCommand,
+            UpdPermissionsArgs,
+        },
+        tests::pyth_simulator::{
+            copy_keypair,
+            PythSimulator,
+        },
+    },
+    solana_program::{
+        native_token::LAMPORTS_PER_SOL,
+        pubkey::Pubkey,
+    },
+    solana_sdk::{
+        instruction::InstructionError,
+        signature::Keypair,
+        signer::Signer,
+        transaction::TransactionError,
+    },
+};
+
+#[tokio::test]
+async fn test_authority_transfer() {
+    let mut sim = PythSimulator::new().await;
+    let program_id = sim.program_id;
+    let master_authority = copy_keypair(&sim.genesis_keypair);
+    let permissions_pubkey = sim.get_permissions_pubkey();
+
+    let new_master_authority = Keypair::new();
+    sim.airdrop(&new_master_authority.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    let new_authorities = UpdPermissionsArgs {
+        header:                  OracleCommand::ProposeAuthorities.into(),
+        master_authority:        new_master_authority.pubkey(),
+        data_curation_authority: Pubkey::new_unique(),
+        security_authority:      Pubkey::new_unique(),
+    };
+
+    // Nothing to accept yet
+    assert_eq!(
+        sim.process_ix_as(
+            builders::accept_authorities(&program_id, &new_master_authority.pubkey()),
+            &new_master_authority,
+        )
+        .await
+        .unwrap_err()
+        .unwrap(),
+        OracleError::NoPendingAuthorities.into()
+    );
+
+    // Only the upgrade authority can propose authorities
+    assert_eq!(
+        sim.process_ix_as(
+            builders::propose_authorities(
+                &program_id,
+                &master_authority.pubkey(),
+                &new_master_authority.pubkey(),
+                &new_master_authority.pubkey(),
+                &new_master_authority.pubkey(),
+                0,
+                0,
+            ),
+            &master_authority,
+        )
+        .await
+        .unwrap_err()
+        .unwrap(),
+        OracleError::InvalidUpgradeAuthority.into()
+    );
+
+    // The proposal doesn't change the authorities
+    sim.propose_authorities(new_authorities, 100, 0)
+        .await
+        .unwrap();
+    let permissions_account = sim.get_account(permissions_pubkey).await.unwrap();
+    assert_eq!(
+        permissions_account.data.len(),
+        PermissionAccount::PENDING_AUTHORITIES_SPACE
+    );
+    let permission_data = sim
+        .get_account_data_as::<PermissionAccount>(permissions_pubkey)
+        .await
+        .unwrap();
+    assert_eq!(permission_data.master_authority, master_authority.pubkey());
+
+    // Only the proposed master authority can accept, after the delay
+    assert_eq!(
+        sim.process_ix_as(
+            builders::accept_authorities(&program_id, &master_authority.pubkey()),
+            &master_authority,
+        )
+        .await
+        .unwrap_err()
+        .unwrap(),
+        OracleError::PermissionViolation.into()
+    );
+    assert_eq!(
+        sim.process_ix_as(
+            builders::accept_authorities(&program_id, &new_master_authority.pubkey()),
+            &new_master_authority,
+        )
+        .await
+        .unwrap_err()
+        .unwrap(),
+        OracleError::AuthorityTransferLocked.into()
+    );
+
+    // Proposing the default master authority cancels the proposal
+    sim.propose_authorities(
+        UpdPermissionsArgs {
+            master_authority: Pubkey::default(),
+            ..new_authorities
+        },
+        0,
+        0,
+    )
+    .await
+    .unwrap();
+    sim.warp_to_slot(1000).await.unwrap();
+    assert_eq!(
+        sim.process_ix_as(
+            builders::accept_authorities(&program_id, &new_master_authority.pubkey()),
+            &new_master_authority,
+        )
+        .await
+        .unwrap_err()
+        .unwrap(),
+        OracleError::NoPendingAuthorities.into()
+    );
+
+    sim.propose_authorities(new_authorities, 100, 50)
+        .await
+        .unwrap();
+    sim.warp_to_slot(1100).await.unwrap();
+    sim.process_ix_as(
+        builders::accept_authorities(&program_id, &new_master_authority.pubkey()),
+        &new_master_authority,
+    )
+    .await
+    .unwrap();
+
+    let permission_data = sim
+        .get_account_data_as::<PermissionAccount>(permissions_pubkey)
+        .await
+        .unwrap();
+    assert_eq!(
+        permission_data.master_authority,
+        new_authorities.master_authority
+    );
+    assert_eq!(
+        permission_data.data_curation_authority,
+        new_authorities.data_curation_authority
+    );
+    assert_eq!(
+        permission_data.security_authority,
+        new_authorities.security_authority
+    );
+
+    // The proposal can only be accepted once
+    assert_eq!(
+        sim.process_ix_as(
+            builders::accept_authorities(&program_id, &new_master_authority.pubkey()),
+            &new_master_authority,
+        )
+        .await
+        .unwrap_err()
+        .unwrap(),
+        OracleError::NoPendingAuthorities.into()
+    );
+
+    // The previous master authority can't execute administrative instructions anymore
+    assert_eq!(
+        sim.process_ix_as(
+            builders::init_mapping(
+                &program_id,
+                &master_authority.pubkey(),
+                &Pubkey::new_unique()
+            ),
+            &master_authority,
+        )
+        .await
+        .unwrap_err()
+        .unwrap(),
+        OracleError::PermissionViolation.into()
+    );
+
+    // The accepted proposal set the minimum delay of the next ones, which cancelling keeps
+    for delay_slots in [49, 0] {
+        assert_eq!(
+            sim.propose_authorities(new_authorities, delay_slots, 0)
+                .await
+                .unwrap_err()
+                .unwrap(),
+            TransactionError::InstructionError(0, InstructionError::InvalidArgument)
+        );
+        sim.propose_authorities(
+            UpdPermissionsArgs {
+                master_authority: Pubkey::default(),
+                ..new_authorities
+            },
+            0,
+            0,
+        )
+        .await
+        .unwrap();
+    }
+    sim.propose_authorities(new_authorities, 50, 0)
+        .await
+        .unwrap();
+}
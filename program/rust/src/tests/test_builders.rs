@@ -0,0 +1,208 @@
+use {
+    crate::{
+        accounts::{
+            account_has_key_values,
+            PermissionAccount,
+            PriceAccount,
+            PriceAccountFlags,
+            ProductAccount,
+            PythAccount,
+        },
+        c_oracle_header::{
+            PC_PTYPE_PRICE,
+            PC_STATUS_TRADING,
+            PC_VERSION,
+        },
+        deserialize::load_checked,
+        instruction::builders,
+        processor::process_instruction,
+        tests::test_utils::{
+            update_clock_slot,
+            AccountSetup,
+        },
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        instruction::Instruction,
+        pubkey::Pubkey,
+    },
+};
+
+/// Run `instruction` against `accounts`, using the signer and writable flags set by the builder.
+fn process(instruction: &Instruction, accounts: &[AccountInfo]) -> ProgramResult {
+    assert_eq!(instruction.accounts.len(), accounts.len());
+    let accounts: Vec<AccountInfo> = instruction
+        .accounts
+        .iter()
+        .zip(accounts)
+        .map(|(meta, account)| {
+            assert_eq!(meta.pubkey, *account.key);
+            let mut account = account.clone();
+            account.is_signer = meta.is_signer;
+            account.is_writable = meta.is_writable;
+            account
+        })
+        .collect();
+    process_instruction(&instruction.program_id, &accounts, &instruction.data)
+}
+
+#[test]
+fn test_builders() {
+    let program_id = Pubkey::new_unique();
+
+    let mut funding_setup = AccountSetup::new_funding();
+    let funding_account = funding_setup.as_account_info();
+
+    let mut publisher_setup = AccountSetup::new_funding();
+    let publisher_account = publisher_setup.as_account_info();
+
+    let mut product_setup = AccountSetup::new::<ProductAccount>(&program_id);
+    let product_account = product_setup.as_account_info();
+    ProductAccount::initialize(&product_account, PC_VERSION).unwrap();
+
+    let mut price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+    let price_account = price_setup.as_account_info();
+    PriceAccount::initialize(&price_account, PC_VERSION)
+        .unwrap()
+        .price_type = PC_PTYPE_PRICE;
+
+    let mut permissions_setup = AccountSetup::new_permission(&program_id);
+    let permissions_account = permissions_setup.as_account_info();
+    assert_eq!(
+        builders::permissions_pubkey(&program_id),
+        *permissions_account.key
+    );
+
+    let mut clock_setup = AccountSetup::new_clock();
+    let mut clock_account = clock_setup.as_account_info();
+    update_clock_slot(&mut clock_account, 1);
+
+    {
+        let mut permissions_account_data =
+            PermissionAccount::initialize(&permissions_account, PC_VERSION).unwrap();
+        permissions_account_data.master_authority = *funding_account.key;
+        permissions_account_data.data_curation_authority = *funding_account.key;
+        permissions_account_data.security_authority = *funding_account.key;
+    }
+
+    let admin_accounts = [
+        funding_account.clone(),
+        price_account.clone(),
+        permissions_account.clone(),
+    ];
+
+    process(
+        &builders::init_price(
+            &program_id,
+            funding_account.key,
+            price_account.key,
+            -5,
+            PC_PTYPE_PRICE,
+        ),
+        &admin_accounts,
+    )
+    .unwrap();
+    process(
+        &builders::add_publisher(
+            &program_id,
+            funding_account.key,
+            price_account.key,
+            publisher_account.key,
+        ),
+        &admin_accounts,
+    )
+    .unwrap();
+    process(
+        &builders::set_min_pub(&program_id, funding_account.key, price_account.key, 2),
+        &admin_accounts,
+    )
+    .unwrap();
+    process(
+        &builders::set_max_latency(&program_id, funding_account.key, price_account.key, 10),
+        &admin_accounts,
+    )
+    .unwrap();
+    process(
+        &builders::set_price_flags(
+            &program_id,
+            funding_account.key,
+            price_account.key,
+            PriceAccountFlags::ACCUMULATOR_V2,
+            PriceAccountFlags::empty(),
+        ),
+        &admin_accounts,
+    )
+    .unwrap();
+    process(
+        &builders::init_price_feed_index(&program_id, funding_account.key, price_account.key),
+        &admin_accounts,
+    )
+    .unwrap();
+    process(
+        &builders::upd_price(
+            &program_id,
+            publisher_account.key,
+            price_account.key,
+            PC_STATUS_TRADING,
+            100,
+            10,
+            1,
+        ),
+        &[
+            publisher_account.clone(),
+            price_account.clone(),
+            clock_account.clone(),
+        ],
+    )
+    .unwrap();
+
+    {
+        let price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        assert_eq!(price_data.exponent, -5);
+        assert_eq!(price_data.num_, 1);
+        assert_eq!(price_data.comp_[0].pub_, *publisher_account.key);
+        assert_eq!(price_data.comp_[0].latest_.price_, 100);
+        assert_eq!(price_data.min_pub_, 2);
+        assert_eq!(price_data.max_latency_, 10);
+        assert_eq!(price_data.feed_index, 1);
+        assert!(price_data.flags.contains(PriceAccountFlags::ACCUMULATOR_V2));
+    }
+
+    process(
+        &builders::del_publisher(
+            &program_id,
+            funding_account.key,
+            price_account.key,
+            publisher_account.key,
+        ),
+        &admin_accounts,
+    )
+    .unwrap();
+    assert_eq!(
+        load_checked::<PriceAccount>(&price_account, PC_VERSION)
+            .unwrap()
+            .num_,
+        0
+    );
+
+    process(
+        &builders::upd_product(
+            &program_id,
+            funding_account.key,
+            product_account.key,
+            &[("symbol", "BTC/USD"), ("asset_type", "Crypto")],
+        ),
+        &[
+            funding_account.clone(),
+            product_account.clone(),
+            permissions_account.clone(),
+        ],
+    )
+    .unwrap();
+    assert!(account_has_key_values(
+        &product_account,
+        &["symbol", "BTC/USD", "asset_type", "Crypto"]
+    )
+    .unwrap());
+}
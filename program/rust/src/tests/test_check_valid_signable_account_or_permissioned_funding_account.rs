@@ -58,6 +58,7 @@ pub fn test_permissions_account_mandatory() {
             &prod_account,
             &funding_account,
             &permissions_account,
+            &[],
             &CommandHeader {
                 version: PC_VERSION,
                 command: OracleCommand::UpdProduct as i32,
@@ -0,0 +1,232 @@
+use {
+    crate::{
+        accounts::{
+            CircuitBreaker,
+            PriceAccount,
+        },
+        aggregation::circuit_breaker::check_circuit_breaker,
+        c_oracle_header::{
+            PC_STATUS_HALTED,
+            PC_STATUS_TRADING,
+            PC_STATUS_UNKNOWN,
+        },
+        instruction::builders,
+        tests::pyth_simulator::{
+            PriceFixture,
+            PythSimulator,
+            Quote,
+        },
+    },
+    bytemuck::Zeroable,
+    solana_sdk::{
+        instruction::InstructionError,
+        signer::Signer,
+        transaction::TransactionError,
+    },
+    std::mem::size_of,
+};
+
+#[test]
+fn test_check_circuit_breaker() {
+    let mut price_account = PriceAccount::zeroed();
+    price_account.prev_price_ = 100;
+    price_account.prev_conf_ = 5;
+    price_account.prev_slot_ = 10;
+    let mut circuit_breaker = CircuitBreaker {
+        max_move_bps: 1000,
+        required_confirmations: 2,
+        max_slots: 10,
+        ..CircuitBreaker::zeroed()
+    };
+
+    // Small moves don't trip the breaker
+    price_account.agg_.price_ = 109;
+    assert!(check_circuit_breaker(
+        &price_account,
+        20,
+        true,
+        &mut circuit_breaker
+    ));
+    assert!(!circuit_breaker.is_tripped());
+
+    // Nor do large moves from a stale or missing previous aggregate
+    price_account.agg_.price_ = 120;
+    assert!(check_circuit_breaker(
+        &price_account,
+        21,
+        true,
+        &mut circuit_breaker
+    ));
+    price_account.prev_conf_ = 0;
+    assert!(check_circuit_breaker(
+        &price_account,
+        20,
+        true,
+        &mut circuit_breaker
+    ));
+    assert!(!circuit_breaker.is_tripped());
+    price_account.prev_conf_ = 5;
+
+    price_account.agg_.price_ = 80;
+    assert!(!check_circuit_breaker(
+        &price_account,
+        20,
+        true,
+        &mut circuit_breaker
+    ));
+    assert_eq!(
+        circuit_breaker,
+        CircuitBreaker {
+            max_move_bps:           1000,
+            required_confirmations: 2,
+            max_slots:              10,
+            tripped:                1,
+            num_confirmations:      0,
+            pending_price:          80,
+        }
+    );
+
+    // A return to the previous aggregate resumes trading right away
+    price_account.agg_.price_ = 95;
+    assert!(check_circuit_breaker(
+        &price_account,
+        21,
+        true,
+        &mut circuit_breaker
+    ));
+    assert!(!circuit_breaker.is_tripped());
+
+    // A new level must be confirmed by consecutive aggregates, a failed aggregate or another
+    // move starting over
+    let rounds = [
+        (120, true, false, 0),
+        (125, true, false, 1),
+        (0, false, false, 0),
+        (125, true, false, 1),
+        (150, true, false, 0),
+        (155, true, false, 1),
+    ];
+    for (price, updated, expected_may_trade, expected_confirmations) in rounds {
+        price_account.agg_.price_ = price;
+        assert_eq!(
+            check_circuit_breaker(&price_account, 20, updated, &mut circuit_breaker),
+            expected_may_trade
+        );
+        assert!(circuit_breaker.is_tripped());
+        assert_eq!(circuit_breaker.num_confirmations, expected_confirmations);
+    }
+    assert_eq!(circuit_breaker.pending_price, 150);
+    price_account.agg_.price_ = 145;
+    assert!(check_circuit_breaker(
+        &price_account,
+        20,
+        true,
+        &mut circuit_breaker
+    ));
+    assert!(!circuit_breaker.is_tripped());
+    assert_eq!(circuit_breaker.pending_price, 0);
+}
+
+#[tokio::test]
+async fn test_set_circuit_breaker() {
+    let mut sim = PythSimulator::new().await;
+    let PriceFixture {
+        program_id,
+        master_authority,
+        price,
+        publishers,
+        outsider,
+        ..
+    } = sim.setup_price_fixture(1).await;
+    let publisher = &publishers[0];
+    sim.process_ix_as(
+        builders::set_min_pub(&program_id, &master_authority.pubkey(), &price, 1),
+        &master_authority,
+    )
+    .await
+    .unwrap();
+
+    sim.assert_permission_violation(
+        builders::set_circuit_breaker(&program_id, &outsider.pubkey(), &price, 1000, 2, 100),
+        &outsider,
+    )
+    .await;
+    assert_eq!(
+        sim.process_ix_as(
+            builders::set_circuit_breaker(
+                &program_id,
+                &master_authority.pubkey(),
+                &price,
+                1000,
+                0,
+                100
+            ),
+            &master_authority,
+        )
+        .await
+        .unwrap_err()
+        .unwrap(),
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument)
+    );
+    sim.process_ix_as(
+        builders::set_circuit_breaker(
+            &program_id,
+            &master_authority.pubkey(),
+            &price,
+            1000,
+            2,
+            100,
+        ),
+        &master_authority,
+    )
+    .await
+    .unwrap();
+    sim.get_resized_account(
+        price,
+        PriceAccount::EXTENSIONS_HEADER_SPACE + size_of::<CircuitBreaker>(),
+    )
+    .await;
+
+    // Each round aggregates the quote of the previous one. The jump to 150 halts the price
+    // account, which trades again after two aggregates confirm the new level.
+    let quotes = [100, 102, 150, 151, 152, 153];
+    let expected_aggregates = [
+        (PC_STATUS_UNKNOWN, 0),
+        (PC_STATUS_TRADING, 100),
+        (PC_STATUS_TRADING, 102),
+        (PC_STATUS_HALTED, 150),
+        (PC_STATUS_HALTED, 151),
+        (PC_STATUS_TRADING, 152),
+    ];
+    for (round, (quote, (expected_status, expected_price))) in
+        quotes.into_iter().zip(expected_aggregates).enumerate()
+    {
+        sim.warp_to_slot(10 * (round as u64 + 1)).await.unwrap();
+        sim.upd_price(
+            publisher,
+            price,
+            Quote {
+                price:      quote,
+                confidence: 10,
+                status:     PC_STATUS_TRADING,
+            },
+        )
+        .await
+        .unwrap();
+
+        let price_data = sim
+            .get_account_data_as::<PriceAccount>(price)
+            .await
+            .unwrap();
+        assert_eq!(price_data.agg_.status_, expected_status);
+        assert_eq!(price_data.agg_.price_, expected_price);
+        if expected_status == PC_STATUS_HALTED {
+            assert_eq!(price_data.prev_price_, 102);
+        }
+    }
+
+    let price_account = sim.get_account(price).await.unwrap();
+    assert!(!PriceAccount::circuit_breaker(&price_account.data)
+        .unwrap()
+        .is_tripped());
+}
@@ -0,0 +1,143 @@
+use {
+    crate::{
+        accounts::{
+            PriceAccount,
+            PriceAccountFlags,
+            PythOracleSerialize,
+        },
+        c_oracle_header::PC_STATUS_TRADING,
+        instruction::builders,
+        tests::pyth_simulator::{
+            copy_keypair,
+            PythSimulator,
+        },
+    },
+    solana_program::native_token::LAMPORTS_PER_SOL,
+    solana_sdk::{
+        signature::Keypair,
+        signer::Signer,
+    },
+};
+
+#[tokio::test]
+async fn test_corp_act_status() {
+    let mut sim = PythSimulator::new().await;
+    let program_id = sim.program_id;
+    let master_authority = copy_keypair(&sim.genesis_keypair);
+
+    let mapping_keypair = sim.init_mapping().await.unwrap();
+    let product_keypair = sim.add_product(&mapping_keypair).await.unwrap();
+    let price_keypair = sim.add_price(&product_keypair, -8).await.unwrap();
+    let price = price_keypair.pubkey();
+
+    let publishers = [Keypair::new(), Keypair::new(), Keypair::new()];
+    for publisher in publishers.iter() {
+        sim.airdrop(&publisher.pubkey(), LAMPORTS_PER_SOL)
+            .await
+            .unwrap();
+        sim.add_publisher(&price_keypair, publisher.pubkey())
+            .await
+            .unwrap();
+    }
+    sim.process_ix_as(
+        builders::set_min_pub(&program_id, &master_authority.pubkey(), &price, 1),
+        &master_authority,
+    )
+    .await
+    .unwrap();
+
+    let set_price_flags = |set_flags, clear_flags| {
+        builders::set_price_flags(
+            &program_id,
+            &master_authority.pubkey(),
+            &price,
+            set_flags,
+            clear_flags,
+        )
+    };
+    sim.process_ix_as(
+        set_price_flags(
+            PriceAccountFlags::CORP_ACT_STATUS,
+            PriceAccountFlags::empty(),
+        ),
+        &master_authority,
+    )
+    .await
+    .unwrap();
+
+    // Each round aggregates the statuses of the previous one: by majority, then by the any rule
+    // from the fourth round, until the aggregation of the statuses is disabled in the last round
+    let rounds = [
+        ([2, 2, 0], 0),
+        ([2, 0, 0], 2),
+        ([2, 3, 0], 0),
+        ([5, 5, 5], 2),
+        ([5, 5, 5], 0),
+    ];
+    for (round, (corp_act_statuses, expected_corp_act_status)) in rounds.into_iter().enumerate() {
+        match round {
+            3 => {
+                sim.process_ix_as(
+                    set_price_flags(PriceAccountFlags::CORP_ACT_ANY, PriceAccountFlags::empty()),
+                    &master_authority,
+                )
+                .await
+                .unwrap();
+            }
+            4 => {
+                sim.process_ix_as(
+                    set_price_flags(
+                        PriceAccountFlags::empty(),
+                        PriceAccountFlags::CORP_ACT_STATUS | PriceAccountFlags::CORP_ACT_ANY,
+                    ),
+                    &master_authority,
+                )
+                .await
+                .unwrap();
+            }
+            _ => {}
+        }
+
+        let slot = 10 * (round as u64 + 1);
+        sim.warp_to_slot(slot).await.unwrap();
+        for (publisher, corp_act_status) in publishers.iter().zip(corp_act_statuses) {
+            sim.process_ix_as(
+                builders::upd_price_with_corp_act_status(
+                    &program_id,
+                    &publisher.pubkey(),
+                    &price,
+                    PC_STATUS_TRADING,
+                    corp_act_status,
+                    100,
+                    1,
+                    slot,
+                ),
+                publisher,
+            )
+            .await
+            .unwrap();
+        }
+
+        let price_data = sim
+            .get_account_data_as::<PriceAccount>(price)
+            .await
+            .unwrap();
+        assert_eq!(price_data.agg_.corp_act_status_, expected_corp_act_status);
+        let message = price_data.as_corp_act_status_message(&price);
+        assert_eq!(message.corp_act_status, expected_corp_act_status);
+        assert_eq!(
+            message.to_bytes()[33..37],
+            expected_corp_act_status.to_be_bytes()
+        );
+
+        // The status of each quote is stored in its component
+        let mut stored_corp_act_statuses: Vec<u32> = price_data.comp_[..3]
+            .iter()
+            .map(|comp| comp.latest_.corp_act_status_)
+            .collect();
+        stored_corp_act_statuses.sort_unstable();
+        let mut corp_act_statuses = corp_act_statuses.to_vec();
+        corp_act_statuses.sort_unstable();
+        assert_eq!(stored_corp_act_statuses, corp_act_statuses);
+    }
+}
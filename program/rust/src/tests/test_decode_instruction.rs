@@ -0,0 +1,767 @@
+use {
+    crate::{
+        accounts::OutlierFilterMode,
+        c_oracle_header::{
+            PC_PTYPE_PRICE,
+            PC_STATUS_TRADING,
+            PC_VERSION,
+        },
+        instruction::{
+            builders,
+            decode_instruction,
+            AddPriceArgs,
+            AddPublisherArgs,
+            BlockPublisherArgs,
+            CommandHeader,
+            DecodeError,
+            DelPublisherArgs,
+            MessageBufferAccounts,
+            OracleCommand,
+            OracleInstruction,
+            PriceUpdate,
+            ProposeAuthoritiesArgs,
+            RotatePublisherArgs,
+            SetCircuitBreakerArgs,
+            SetConfThresholdArgs,
+            SetEmaHalfLifeArgs,
+            SetEmaHorizonsArgs,
+            SetOutlierFilterArgs,
+            SetPriceBandArgs,
+            SetPublisherWeightArgs,
+            UnblockPublisherArgs,
+            UpdPriceAccounts,
+            UpdPriceArgs,
+        },
+    },
+    bytemuck::bytes_of,
+    solana_program::{
+        instruction::{
+            AccountMeta,
+            Instruction,
+        },
+        pubkey::Pubkey,
+        system_program,
+        sysvar::clock,
+    },
+};
+
+fn decode(instruction: &Instruction) -> Result<OracleInstruction, DecodeError> {
+    decode_instruction(&instruction.data, &instruction.accounts)
+}
+
+fn readonly_accounts(keys: &[Pubkey]) -> Vec<AccountMeta> {
+    keys.iter()
+        .map(|key| AccountMeta::new_readonly(*key, false))
+        .collect()
+}
+
+#[test]
+fn test_decode_instruction() {
+    let program_id = Pubkey::new_unique();
+    let funding_account = Pubkey::new_unique();
+    let product_account = Pubkey::new_unique();
+    let price_account = Pubkey::new_unique();
+    let permissions_account = builders::permissions_pubkey(&program_id);
+
+    assert_eq!(
+        decode(&builders::add_price(
+            &program_id,
+            &funding_account,
+            &product_account,
+            &price_account,
+            -8,
+            PC_PTYPE_PRICE,
+        )),
+        Ok(OracleInstruction::AddPrice {
+            args: AddPriceArgs {
+                header:     OracleCommand::AddPrice.into(),
+                exponent:   -8,
+                price_type: PC_PTYPE_PRICE,
+            },
+            funding_account,
+            product_account,
+            price_account,
+            permissions_account,
+            additional_signers: vec![],
+        })
+    );
+
+    let upd_price = builders::upd_price(
+        &program_id,
+        &funding_account,
+        &price_account,
+        PC_STATUS_TRADING,
+        42,
+        2,
+        1000,
+    );
+    let expected_upd_price = OracleInstruction::UpdPrice {
+        args:     UpdPriceArgs {
+            header:          OracleCommand::UpdPrice.into(),
+            status:          PC_STATUS_TRADING,
+            corp_act_status: 0,
+            price:           42,
+            confidence:      2,
+            publishing_slot: 1000,
+        },
+        accounts: UpdPriceAccounts {
+            funding_account,
+            price_account,
+            clock_account: clock::id(),
+            message_buffer: None,
+            publisher_blocklist: None,
+            product_account: None,
+        },
+    };
+    assert_eq!(decode(&upd_price), Ok(expected_upd_price.clone()));
+
+    // The legacy layout with an extra account before the clock is also accepted
+    assert_eq!(
+        decode_instruction(
+            &upd_price.data,
+            &readonly_accounts(&[
+                funding_account,
+                price_account,
+                Pubkey::new_unique(),
+                clock::id()
+            ])
+        ),
+        Ok(expected_upd_price.clone())
+    );
+
+    // The publisher blocklist account follows the clock
+    let publisher_blocklist = builders::publisher_blocklist_pubkey(&program_id);
+    let mut expected_upd_price = expected_upd_price;
+    if let OracleInstruction::UpdPrice { accounts, .. } = &mut expected_upd_price {
+        accounts.publisher_blocklist = Some(publisher_blocklist);
+    }
+    assert_eq!(
+        decode(&builders::with_publisher_blocklist(
+            upd_price.clone(),
+            &program_id
+        )),
+        Ok(expected_upd_price.clone())
+    );
+
+    // Followed by the product account
+    if let OracleInstruction::UpdPrice { accounts, .. } = &mut expected_upd_price {
+        accounts.product_account = Some(product_account);
+    }
+    assert_eq!(
+        decode(&builders::with_product_accounts(
+            builders::with_publisher_blocklist(upd_price.clone(), &program_id),
+            &[product_account]
+        )),
+        Ok(expected_upd_price.clone())
+    );
+
+    // The message buffer accounts come before the publisher blocklist account
+    let message_buffer_program = Pubkey::new_unique();
+    let message_buffer = MessageBufferAccounts {
+        program_id:          message_buffer_program,
+        whitelist:           Pubkey::new_unique(),
+        oracle_auth_pda:     builders::oracle_auth_pubkey(&program_id, &message_buffer_program),
+        message_buffer_data: Pubkey::new_unique(),
+    };
+    if let OracleInstruction::UpdPrice { accounts, .. } = &mut expected_upd_price {
+        accounts.message_buffer = Some(message_buffer.clone());
+    }
+    assert_eq!(
+        decode(&builders::with_product_accounts(
+            builders::with_publisher_blocklist(
+                builders::with_message_buffer(
+                    upd_price.clone(),
+                    &message_buffer.program_id,
+                    &message_buffer.whitelist,
+                    &message_buffer.message_buffer_data,
+                ),
+                &program_id
+            ),
+            &[product_account]
+        )),
+        Ok(expected_upd_price)
+    );
+
+    // The decoder doesn't require the data to be aligned
+    let mut unaligned_data = vec![0u8];
+    unaligned_data.extend_from_slice(&upd_price.data);
+    assert!(decode_instruction(
+        &unaligned_data[1..],
+        &readonly_accounts(&[funding_account, price_account, clock::id()])
+    )
+    .is_ok());
+
+    assert_eq!(
+        decode(&builders::upd_product(
+            &program_id,
+            &funding_account,
+            &product_account,
+            &[("symbol", "BTC/USD"), ("quote_currency", "USD")],
+        )),
+        Ok(OracleInstruction::UpdProduct {
+            funding_account,
+            product_account,
+            permissions_account,
+            metadata: vec![
+                ("symbol".to_string(), "BTC/USD".to_string()),
+                ("quote_currency".to_string(), "USD".to_string()),
+            ],
+            additional_signers: vec![],
+        })
+    );
+
+    assert_eq!(
+        decode(&builders::init_price_feed_index(
+            &program_id,
+            &funding_account,
+            &price_account
+        )),
+        Ok(OracleInstruction::InitPriceFeedIndex {
+            funding_account,
+            price_account,
+            permissions_account,
+            additional_signers: vec![],
+        })
+    );
+
+    let additional_signers = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+    assert_eq!(
+        decode(&builders::with_additional_signers(
+            builders::init_price_feed_index(&program_id, &funding_account, &price_account),
+            &additional_signers
+        )),
+        Ok(OracleInstruction::InitPriceFeedIndex {
+            funding_account,
+            price_account,
+            permissions_account,
+            additional_signers,
+        })
+    );
+
+    assert_eq!(
+        decode(&builders::set_conf_threshold(
+            &program_id,
+            &funding_account,
+            &price_account,
+            10
+        )),
+        Ok(OracleInstruction::SetConfThreshold {
+            args: SetConfThresholdArgs {
+                header:       OracleCommand::SetConfThreshold.into(),
+                conf_divisor: 10,
+            },
+            funding_account,
+            price_account,
+            permissions_account,
+            system_program: system_program::id(),
+            additional_signers: vec![],
+        })
+    );
+
+    assert_eq!(
+        decode(&builders::set_ema_half_life(
+            &program_id,
+            &funding_account,
+            &price_account,
+            100
+        )),
+        Ok(OracleInstruction::SetEmaHalfLife {
+            args: SetEmaHalfLifeArgs {
+                header:    OracleCommand::SetEmaHalfLife.into(),
+                half_life: 100,
+            },
+            funding_account,
+            price_account,
+            permissions_account,
+            system_program: system_program::id(),
+            additional_signers: vec![],
+        })
+    );
+
+    assert_eq!(
+        decode(&builders::set_ema_horizons(
+            &program_id,
+            &funding_account,
+            &price_account,
+            [100, 1000, 0]
+        )),
+        Ok(OracleInstruction::SetEmaHorizons {
+            args: SetEmaHorizonsArgs {
+                header:     OracleCommand::SetEmaHorizons.into(),
+                half_lives: [100, 1000, 0],
+            },
+            funding_account,
+            price_account,
+            permissions_account,
+            system_program: system_program::id(),
+            additional_signers: vec![],
+        })
+    );
+
+    assert_eq!(
+        decode(&builders::init_price_history(
+            &program_id,
+            &funding_account,
+            &price_account
+        )),
+        Ok(OracleInstruction::InitPriceHistory {
+            funding_account,
+            price_account,
+            permissions_account,
+            system_program: system_program::id(),
+            additional_signers: vec![],
+        })
+    );
+
+    let publisher = Pubkey::new_unique();
+    assert_eq!(
+        decode(&builders::set_publisher_weight(
+            &program_id,
+            &funding_account,
+            &price_account,
+            &publisher,
+            42
+        )),
+        Ok(OracleInstruction::SetPublisherWeight {
+            args: SetPublisherWeightArgs {
+                header: OracleCommand::SetPublisherWeight.into(),
+                publisher,
+                weight: 42,
+            },
+            funding_account,
+            price_account,
+            permissions_account,
+            system_program: system_program::id(),
+            additional_signers: vec![],
+        })
+    );
+
+    assert_eq!(
+        decode(&builders::set_outlier_filter(
+            &program_id,
+            &funding_account,
+            &price_account,
+            Some(OutlierFilterMode::Median),
+            3
+        )),
+        Ok(OracleInstruction::SetOutlierFilter {
+            args: SetOutlierFilterArgs {
+                header: OracleCommand::SetOutlierFilter.into(),
+                mode:   OutlierFilterMode::Median as u32,
+                k:      3,
+            },
+            funding_account,
+            price_account,
+            permissions_account,
+            system_program: system_program::id(),
+            additional_signers: vec![],
+        })
+    );
+
+    assert_eq!(
+        decode(&builders::init_aggregation_diagnostics(
+            &program_id,
+            &funding_account,
+            &price_account
+        )),
+        Ok(OracleInstruction::InitAggregationDiagnostics {
+            funding_account,
+            price_account,
+            permissions_account,
+            system_program: system_program::id(),
+            additional_signers: vec![],
+        })
+    );
+
+    assert_eq!(
+        decode(&builders::init_publisher_stats(
+            &program_id,
+            &funding_account,
+            &price_account
+        )),
+        Ok(OracleInstruction::InitPublisherStats {
+            funding_account,
+            price_account,
+            permissions_account,
+            system_program: system_program::id(),
+            additional_signers: vec![],
+        })
+    );
+
+    let schedule = "-0500;0930-1600,0930-1600,0930-1600,0930-1600,0930-1600,C,C";
+    assert_eq!(
+        decode(&builders::set_trading_schedule(
+            &program_id,
+            &funding_account,
+            &product_account,
+            schedule
+        )),
+        Ok(OracleInstruction::SetTradingSchedule {
+            funding_account,
+            product_account,
+            permissions_account,
+            system_program: system_program::id(),
+            schedule: schedule.to_string(),
+            additional_signers: vec![],
+        })
+    );
+
+    assert_eq!(
+        decode(&builders::init_aggregate_confidence(
+            &program_id,
+            &funding_account,
+            &price_account
+        )),
+        Ok(OracleInstruction::InitAggregateConfidence {
+            funding_account,
+            price_account,
+            permissions_account,
+            system_program: system_program::id(),
+            additional_signers: vec![],
+        })
+    );
+
+    assert_eq!(
+        decode(&builders::set_circuit_breaker(
+            &program_id,
+            &funding_account,
+            &price_account,
+            500,
+            3,
+            25,
+        )),
+        Ok(OracleInstruction::SetCircuitBreaker {
+            args: SetCircuitBreakerArgs {
+                header:                 OracleCommand::SetCircuitBreaker.into(),
+                max_move_bps:           500,
+                required_confirmations: 3,
+                max_slots:              25,
+            },
+            funding_account,
+            price_account,
+            permissions_account,
+            system_program: system_program::id(),
+            additional_signers: vec![],
+        })
+    );
+
+    assert_eq!(
+        decode(&builders::set_price_band(
+            &program_id,
+            &funding_account,
+            &price_account,
+            -1000,
+            1000,
+        )),
+        Ok(OracleInstruction::SetPriceBand {
+            args: SetPriceBandArgs {
+                header:    OracleCommand::SetPriceBand.into(),
+                min_price: -1000,
+                max_price: 1000,
+            },
+            funding_account,
+            price_account,
+            permissions_account,
+            system_program: system_program::id(),
+            additional_signers: vec![],
+        })
+    );
+
+    let update = PriceUpdate {
+        status:          PC_STATUS_TRADING,
+        corp_act_status: 0,
+        price:           42,
+        confidence:      2,
+        publishing_slot: 1000,
+    };
+    let other_price_account = Pubkey::new_unique();
+    let other_product_account = Pubkey::new_unique();
+    assert_eq!(
+        decode(&builders::upd_price_batch(
+            &program_id,
+            &funding_account,
+            &[(price_account, update), (other_price_account, update)]
+        )),
+        Ok(OracleInstruction::UpdPriceBatch {
+            funding_account,
+            clock_account: clock::id(),
+            updates: vec![(price_account, update), (other_price_account, update)],
+            publisher_blocklist: None,
+            product_accounts: vec![],
+        })
+    );
+    assert_eq!(
+        decode(&builders::with_product_accounts(
+            builders::with_publisher_blocklist(
+                builders::upd_price_batch(
+                    &program_id,
+                    &funding_account,
+                    &[(price_account, update)]
+                ),
+                &program_id,
+            ),
+            &[product_account, other_product_account],
+        )),
+        Ok(OracleInstruction::UpdPriceBatch {
+            funding_account,
+            clock_account: clock::id(),
+            updates: vec![(price_account, update)],
+            publisher_blocklist: Some(publisher_blocklist),
+            product_accounts: vec![product_account, other_product_account],
+        })
+    );
+
+    let publisher = Pubkey::new_unique();
+    assert_eq!(
+        decode(&builders::block_publisher(
+            &program_id,
+            &funding_account,
+            &publisher
+        )),
+        Ok(OracleInstruction::BlockPublisher {
+            args: BlockPublisherArgs {
+                header: OracleCommand::BlockPublisher.into(),
+                publisher,
+            },
+            funding_account,
+            publisher_blocklist_account: publisher_blocklist,
+            permissions_account,
+            system_program: system_program::id(),
+            additional_signers: vec![],
+        })
+    );
+    assert_eq!(
+        decode(&builders::unblock_publisher(
+            &program_id,
+            &funding_account,
+            &publisher
+        )),
+        Ok(OracleInstruction::UnblockPublisher {
+            args: UnblockPublisherArgs {
+                header: OracleCommand::UnblockPublisher.into(),
+                publisher,
+            },
+            funding_account,
+            publisher_blocklist_account: publisher_blocklist,
+            permissions_account,
+            additional_signers: vec![],
+        })
+    );
+
+    let new_publisher = Pubkey::new_unique();
+    let signer = Pubkey::new_unique();
+    assert_eq!(
+        decode(&builders::with_additional_signers(
+            builders::rotate_publisher(
+                &program_id,
+                &funding_account,
+                &publisher,
+                &new_publisher,
+                &[price_account, product_account],
+                false,
+            ),
+            &[signer]
+        )),
+        Ok(OracleInstruction::RotatePublisher {
+            args: RotatePublisherArgs {
+                header:             OracleCommand::RotatePublisher.into(),
+                num_price_accounts: 2,
+                unused_:            0,
+            },
+            funding_account,
+            permissions_account,
+            publisher_blocklist_account: publisher_blocklist,
+            old_publisher: publisher,
+            new_publisher,
+            price_accounts: vec![price_account, product_account],
+            additional_signers: vec![signer],
+        })
+    );
+
+    assert_eq!(
+        decode(&builders::with_additional_signers(
+            builders::propose_authorities(
+                &program_id,
+                &funding_account,
+                &publisher,
+                &new_publisher,
+                &product_account,
+                100,
+                10,
+            ),
+            &[signer],
+        )),
+        Ok(OracleInstruction::ProposeAuthorities {
+            args: ProposeAuthoritiesArgs {
+                header:                  OracleCommand::ProposeAuthorities.into(),
+                master_authority:        publisher,
+                data_curation_authority: new_publisher,
+                security_authority:      product_account,
+                delay_slots:             100,
+                min_delay_slots:         10,
+            },
+            upgrade_authority: funding_account,
+            programdata_account: builders::programdata_pubkey(&program_id),
+            permissions_account,
+            system_program: system_program::id(),
+            additional_signers: vec![signer],
+        })
+    );
+
+    // The publisher manager accounts don't sign, unlike the additional signers
+    let publisher_manager_account = Pubkey::new_unique();
+    let mapping_account = Pubkey::new_unique();
+    assert_eq!(
+        decode(&builders::with_publisher_manager(
+            builders::add_publisher(&program_id, &funding_account, &price_account, &publisher),
+            &publisher_manager_account,
+            Some(&mapping_account),
+        )),
+        Ok(OracleInstruction::AddPublisher {
+            args: AddPublisherArgs {
+                header: OracleCommand::AddPublisher.into(),
+                publisher,
+            },
+            funding_account,
+            price_account,
+            permissions_account,
+            publisher_manager_account: Some(publisher_manager_account),
+            scope_mapping_account: Some(mapping_account),
+            additional_signers: vec![],
+        })
+    );
+    assert_eq!(
+        decode(&builders::with_additional_signers(
+            builders::del_publisher(&program_id, &funding_account, &price_account, &publisher),
+            &[signer],
+        )),
+        Ok(OracleInstruction::DelPublisher {
+            args: DelPublisherArgs {
+                header: OracleCommand::DelPublisher.into(),
+                publisher,
+            },
+            funding_account,
+            price_account,
+            permissions_account,
+            publisher_manager_account: None,
+            scope_mapping_account: None,
+            additional_signers: vec![signer],
+        })
+    );
+}
+
+#[test]
+fn test_decode_instruction_errors() {
+    let accounts = readonly_accounts(&[Pubkey::new_unique(), Pubkey::new_unique()]);
+
+    // Data shorter than the header
+    assert_eq!(
+        decode_instruction(&[0u8; 4], &accounts),
+        Err(DecodeError::InstructionDataTooShort {
+            expected: 8,
+            actual:   4,
+        })
+    );
+
+    // Wrong version
+    let header = CommandHeader {
+        version: PC_VERSION + 1,
+        command: OracleCommand::UpdPrice as i32,
+    };
+    assert_eq!(
+        decode_instruction(bytes_of(&header), &accounts),
+        Err(DecodeError::InvalidVersion(PC_VERSION + 1))
+    );
+
+    // Unknown command
+    let header = CommandHeader {
+        version: PC_VERSION,
+        command: 1000,
+    };
+    assert_eq!(
+        decode_instruction(bytes_of(&header), &accounts),
+        Err(DecodeError::UnknownCommand(1000))
+    );
+
+    // Data too short for the arguments of the command
+    let header: CommandHeader = OracleCommand::SetMaxLatency.into();
+    assert_eq!(
+        decode_instruction(
+            bytes_of(&header),
+            &readonly_accounts(&[Pubkey::new_unique(); 3])
+        ),
+        Err(DecodeError::InstructionDataTooShort {
+            expected: 12,
+            actual:   8,
+        })
+    );
+
+    // Wrong number of accounts
+    let header: CommandHeader = OracleCommand::UpdPrice.into();
+    let args = UpdPriceArgs {
+        header,
+        status: PC_STATUS_TRADING,
+        corp_act_status: 0,
+        price: 1,
+        confidence: 1,
+        publishing_slot: 1,
+    };
+    assert_eq!(
+        decode_instruction(bytes_of(&args), &accounts),
+        Err(DecodeError::InvalidNumberOfAccounts {
+            command:  OracleCommand::UpdPrice,
+            expected: &[3, 4, 5, 7, 8, 9],
+            actual:   2,
+        })
+    );
+
+    // Malformed product metadata: the value is missing
+    let mut data = bytes_of::<CommandHeader>(&OracleCommand::UpdProduct.into()).to_vec();
+    data.extend_from_slice(&[3, b'k', b'e', b'y', 5, b'v']);
+    assert_eq!(
+        decode_instruction(&data, &readonly_accounts(&[Pubkey::new_unique(); 3])),
+        Err(DecodeError::InvalidProductMetadata(4))
+    );
+
+    // The number of price accounts of a batch doesn't match the number of updates
+    let mut data = bytes_of::<CommandHeader>(&OracleCommand::UpdPriceBatch.into()).to_vec();
+    data.extend_from_slice(&[0u8; 32]);
+    assert_eq!(
+        decode_instruction(&data, &accounts),
+        Err(DecodeError::InvalidNumberOfBatchAccounts {
+            expected: 3,
+            actual:   2,
+        })
+    );
+
+    // Truncated price update
+    assert_eq!(
+        decode_instruction(&data[..data.len() - 1], &accounts),
+        Err(DecodeError::InvalidPriceUpdatesLength(31))
+    );
+
+    // The trading schedule isn't valid UTF-8
+    let mut data = bytes_of::<CommandHeader>(&OracleCommand::SetTradingSchedule.into()).to_vec();
+    data.push(0xff);
+    assert_eq!(
+        decode_instruction(&data, &readonly_accounts(&[Pubkey::new_unique(); 4])),
+        Err(DecodeError::InvalidTradingSchedule)
+    );
+
+    // Fewer accounts follow the publishers than the number of price accounts
+    let args = RotatePublisherArgs {
+        header:             OracleCommand::RotatePublisher.into(),
+        num_price_accounts: 2,
+        unused_:            0,
+    };
+    assert_eq!(
+        decode_instruction(
+            bytes_of(&args),
+            &readonly_accounts(&[Pubkey::new_unique(); 6])
+        ),
+        Err(DecodeError::InvalidNumberOfPriceAccounts {
+            expected: 2,
+            actual:   1,
+        })
+    );
+}
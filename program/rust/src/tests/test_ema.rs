@@ -3,9 +3,11 @@ extern crate test_generator;
 use {
     crate::{
         accounts::PriceAccount,
-        processor::{
-            c_upd_aggregate,
-            c_upd_twap,
+        aggregation::{
+            upd_aggregate,
+            upd_twap,
+            EmaParams,
+            MAX_EMA_HALF_LIFE,
         },
     },
     bytemuck::Zeroable,
@@ -103,34 +105,63 @@ fn run_ema_test(inputs: &[InputRecord], expected_outputs: &[OutputRecord]) {
         price_account.agg_.price_ = input.price;
         price_account.agg_.conf_ = input.conf;
 
-        upd_twap(&mut price_account, input.nslots);
+        upd_twap(&mut price_account, input.nslots, EmaParams::default());
 
         assert_eq!(expected_output.twap, price_account.twap_.val_);
         assert_eq!(expected_output.twac, price_account.twac_.val_);
     }
 }
 
+#[test]
+fn test_ema_params_from_half_life() {
+    // The defaults correspond to a half-life of 5921 slots
+    assert_eq!(EmaParams::from_half_life(5921), EmaParams::default());
+    assert_eq!(EmaParams::from_half_life(0), EmaParams::default());
+    assert_eq!(
+        EmaParams::from_half_life(MAX_EMA_HALF_LIFE + 1),
+        EmaParams::default()
+    );
 
-// TODO: put these functions somewhere more accessible
-pub fn upd_aggregate(
-    price_account: &mut PriceAccount,
-    clock_slot: u64,
-    clock_timestamp: i64,
-) -> bool {
-    unsafe {
-        c_upd_aggregate(
-            (price_account as *mut PriceAccount) as *mut u8,
-            clock_slot,
-            clock_timestamp,
-        )
-    }
+    assert_eq!(
+        EmaParams::from_half_life(100),
+        EmaParams {
+            decay:    -6_931_471,
+            max_diff: 70,
+        }
+    );
+    assert_eq!(EmaParams::from_half_life(MAX_EMA_HALF_LIFE).decay, -1);
 }
 
-pub fn upd_twap(price_account: &mut PriceAccount, nslots: i64) {
-    unsafe { c_upd_twap((price_account as *mut PriceAccount) as *mut u8, nslots) }
+#[test]
+fn test_ema_half_life() {
+    // The EMA of a price jumping from 100 to 200, `nslots` slots after the EMAs were initialized
+    let ema_after_jump = |half_life: u64, nslots: i64| {
+        let ema_params = EmaParams::from_half_life(half_life);
+        let mut price_account = PriceAccount::zeroed();
+        price_account.agg_.price_ = 100;
+        price_account.agg_.conf_ = 1;
+        upd_twap(&mut price_account, i64::MAX, ema_params);
+        assert_eq!(price_account.twap_.val_, 100);
+
+        price_account.agg_.price_ = 200;
+        upd_twap(&mut price_account, nslots, ema_params);
+        price_account.twap_.val_
+    };
+
+    assert_eq!(ema_after_jump(0, 5), ema_after_jump(5921, 5));
+
+    // A shorter half-life follows the price faster
+    let short_ema = ema_after_jump(10, 5);
+    let long_ema = ema_after_jump(1000, 5);
+    assert!(100 < long_ema && long_ema < short_ema && short_ema < 200);
+
+    // and is reset after fewer slots without aggregation
+    assert_eq!(EmaParams::from_half_life(10).max_diff, 7);
+    assert!(ema_after_jump(10, 7) < 200);
+    assert_eq!(ema_after_jump(10, 8), 200);
+    assert!(ema_after_jump(0, 8) < 200);
 }
 
-
 #[derive(Serialize, Deserialize, Debug)]
 struct InputRecord {
     price:  i64,
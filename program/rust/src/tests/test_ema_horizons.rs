@@ -0,0 +1,178 @@
+use {
+    crate::{
+        accounts::{
+            EmaHorizons,
+            PriceAccount,
+            PriceAccountFlags,
+            PythOracleSerialize,
+        },
+        aggregation::MAX_EMA_HALF_LIFE,
+        c_oracle_header::PC_STATUS_TRADING,
+        instruction::builders,
+        tests::pyth_simulator::{
+            PriceFixture,
+            PythSimulator,
+            Quote,
+        },
+        validator,
+    },
+    solana_sdk::{
+        instruction::InstructionError,
+        signer::Signer,
+        transaction::TransactionError,
+    },
+    std::mem::size_of,
+};
+
+#[tokio::test]
+async fn test_ema_horizons() {
+    let mut sim = PythSimulator::new().await;
+    let PriceFixture {
+        program_id,
+        master_authority,
+        price,
+        publishers,
+        outsider,
+        ..
+    } = sim.setup_price_fixture(1).await;
+    let publisher = &publishers[0];
+    // Aggregate the quotes of a single publisher
+    sim.process_ix_as(
+        builders::set_min_pub(&program_id, &master_authority.pubkey(), &price, 1),
+        &master_authority,
+    )
+    .await
+    .unwrap();
+
+    // Only the authorities can set the horizons
+    sim.assert_permission_violation(
+        builders::set_ema_horizons(&program_id, &outsider.pubkey(), &price, [5921, 100, 0]),
+        &outsider,
+    )
+    .await;
+
+    assert_eq!(
+        sim.process_ix_as(
+            builders::set_ema_horizons(
+                &program_id,
+                &master_authority.pubkey(),
+                &price,
+                [5921, MAX_EMA_HALF_LIFE + 1, 0]
+            ),
+            &master_authority,
+        )
+        .await
+        .unwrap_err()
+        .unwrap(),
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument)
+    );
+
+    // Setting the horizons resizes the price account, keeping the default EMA parameters
+    sim.process_ix_as(
+        builders::set_ema_horizons(
+            &program_id,
+            &master_authority.pubkey(),
+            &price,
+            [5921, 100, 0],
+        ),
+        &master_authority,
+    )
+    .await
+    .unwrap();
+    let price_account = sim
+        .get_resized_account(
+            price,
+            PriceAccount::EXTENSIONS_HEADER_SPACE + size_of::<EmaHorizons>(),
+        )
+        .await;
+    assert_eq!(PriceAccount::ema_half_life(&price_account.data), 0);
+    assert_eq!(
+        PriceAccount::ema_horizons(&price_account.data)
+            .unwrap()
+            .half_lives,
+        [5921, 100, 0]
+    );
+
+    // The price jumps from 100 to 200
+    for (slot, price_value) in [(10, 100), (20, 100), (30, 200), (40, 200)] {
+        sim.warp_to_slot(slot).await.unwrap();
+        sim.upd_price(
+            publisher,
+            price,
+            Quote {
+                price:      price_value,
+                confidence: 1,
+                status:     PC_STATUS_TRADING,
+            },
+        )
+        .await
+        .unwrap();
+    }
+
+    let price_account = sim.get_account(price).await.unwrap();
+    let price_data = sim
+        .get_account_data_as::<PriceAccount>(price)
+        .await
+        .unwrap();
+    let ema_horizons = PriceAccount::ema_horizons(&price_account.data).unwrap();
+    assert_eq!(price_data.agg_.price_, 200);
+    // A horizon with the default half-life tracks the EMAs of the price account
+    assert_eq!(ema_horizons.twap[0].val_, price_data.twap_.val_);
+    assert_eq!(ema_horizons.twac[0].val_, price_data.twac_.val_);
+    // A shorter horizon moves faster towards the new price
+    assert!(ema_horizons.twap[1].val_ > ema_horizons.twap[0].val_);
+    assert!(ema_horizons.twap[1].val_ < 200);
+    // Unused horizons aren't updated
+    assert_eq!(ema_horizons.twap[2].val_, 0);
+    assert_eq!(ema_horizons.twac[2].val_, 0);
+
+    // Changing the half-life of a horizon restarts its EMAs only
+    sim.process_ix_as(
+        builders::set_ema_horizons(
+            &program_id,
+            &master_authority.pubkey(),
+            &price,
+            [5921, 50, 0],
+        ),
+        &master_authority,
+    )
+    .await
+    .unwrap();
+    let mut price_account_data = sim.get_account(price).await.unwrap().data;
+    let new_ema_horizons = PriceAccount::ema_horizons(&price_account_data).unwrap();
+    assert_eq!(new_ema_horizons.half_lives, [5921, 50, 0]);
+    assert_eq!(new_ema_horizons.twap[0].val_, ema_horizons.twap[0].val_);
+    assert_eq!(new_ema_horizons.twap[1].val_, 0);
+    assert_eq!(new_ema_horizons.twap[1].denom_, 0);
+
+    // The validator aggregation updates the horizons and publishes them in a third message
+    {
+        let price_data =
+            validator::checked_load_price_account_mut(&mut price_account_data).unwrap();
+        price_data.flags.insert(PriceAccountFlags::ACCUMULATOR_V2);
+        price_data
+            .flags
+            .insert(PriceAccountFlags::MESSAGE_BUFFER_CLEARED);
+    }
+    let messages = validator::aggregate_price_with_extensions(
+        41,
+        141,
+        &price,
+        &mut price_account_data,
+        &[],
+        &[],
+    )
+    .unwrap();
+    let price_data = validator::checked_load_price_account(&price_account_data).unwrap();
+    let ema_horizons = PriceAccount::ema_horizons(&price_account_data).unwrap();
+    assert_eq!(price_data.agg_.pub_slot_, 41);
+    assert_eq!(ema_horizons.twap[0].val_, price_data.twap_.val_);
+    assert_ne!(ema_horizons.twap[1].val_, 0);
+    assert_eq!(messages.len(), 3);
+    assert_eq!(
+        messages[2],
+        price_data
+            .as_ema_horizons_message(&price, &ema_horizons)
+            .to_bytes()
+    );
+}
@@ -1,5 +1,13 @@
 use {
-    crate::accounts::PythOracleSerialize,
+    crate::accounts::{
+        AggregateConfidenceMessage,
+        CorpActStatusMessage,
+        EmaHorizonsMessage,
+        HorizonEma,
+        PublisherStat,
+        PublisherStatsMessage,
+        PythOracleSerialize,
+    },
     byteorder::BigEndian,
     pythnet_sdk::{
         messages::{
@@ -15,6 +23,7 @@ use {
         QuickCheck,
     },
     quickcheck_macros::quickcheck,
+    solana_program::pubkey::Pubkey,
 };
 
 #[quickcheck]
@@ -65,3 +74,118 @@ fn test_publisher_caps_message_roundtrip() {
         .gen(Gen::new(1024))
         .quickcheck(prop_publisher_caps_message_roundtrip as fn(PublisherStakeCapsMessage) -> bool);
 }
+
+#[test]
+fn test_ema_horizons_message() {
+    let message = EmaHorizonsMessage {
+        feed_id:           [7; 32],
+        exponent:          -8,
+        publish_time:      1_700_000_000,
+        prev_publish_time: 1_699_999_999,
+        emas:              [
+            HorizonEma {
+                half_life: 100,
+                ema_price: -42,
+                ema_conf:  3,
+            },
+            HorizonEma {
+                half_life: 5921,
+                ema_price: 40,
+                ema_conf:  2,
+            },
+            HorizonEma {
+                half_life: 0,
+                ema_price: 0,
+                ema_conf:  0,
+            },
+        ],
+    };
+
+    let mut expected = vec![3];
+    expected.extend_from_slice(&[7; 32]);
+    expected.extend_from_slice(&(-8i32).to_be_bytes());
+    expected.extend_from_slice(&1_700_000_000i64.to_be_bytes());
+    expected.extend_from_slice(&1_699_999_999i64.to_be_bytes());
+    for (half_life, ema_price, ema_conf) in [(100u64, -42i64, 3u64), (5921, 40, 2), (0, 0, 0)] {
+        expected.extend_from_slice(&half_life.to_be_bytes());
+        expected.extend_from_slice(&ema_price.to_be_bytes());
+        expected.extend_from_slice(&ema_conf.to_be_bytes());
+    }
+    assert_eq!(message.to_bytes(), expected);
+}
+
+#[test]
+fn test_publisher_stats_message() {
+    let message = PublisherStatsMessage {
+        feed_id:      [7; 32],
+        publish_slot: 1000,
+        stats:        vec![
+            PublisherStat {
+                publisher:        Pubkey::new_from_array([1; 32]),
+                num_aggregations: 10,
+                num_misses:       2,
+                sum_deviation:    5_000_000,
+            },
+            PublisherStat {
+                publisher:        Pubkey::new_from_array([2; 32]),
+                num_aggregations: 3,
+                num_misses:       0,
+                sum_deviation:    u64::MAX,
+            },
+        ],
+    };
+
+    let mut expected = vec![4];
+    expected.extend_from_slice(&[7; 32]);
+    expected.extend_from_slice(&1000u64.to_be_bytes());
+    expected.extend_from_slice(&2u16.to_be_bytes());
+    for (publisher, num_aggregations, num_misses, sum_deviation) in
+        [(1, 10u64, 2u64, 5_000_000u64), (2, 3, 0, u64::MAX)]
+    {
+        expected.extend_from_slice(&[publisher; 32]);
+        expected.extend_from_slice(&num_aggregations.to_be_bytes());
+        expected.extend_from_slice(&num_misses.to_be_bytes());
+        expected.extend_from_slice(&sum_deviation.to_be_bytes());
+    }
+    assert_eq!(message.to_bytes(), expected);
+}
+
+#[test]
+fn test_corp_act_status_message() {
+    let message = CorpActStatusMessage {
+        feed_id:           [7; 32],
+        corp_act_status:   3,
+        publish_time:      1_700_000_000,
+        prev_publish_time: -1,
+    };
+
+    let mut expected = vec![5];
+    expected.extend_from_slice(&[7; 32]);
+    expected.extend_from_slice(&3u32.to_be_bytes());
+    expected.extend_from_slice(&1_700_000_000i64.to_be_bytes());
+    expected.extend_from_slice(&(-1i64).to_be_bytes());
+    assert_eq!(message.to_bytes(), expected);
+}
+
+#[test]
+fn test_aggregate_confidence_message() {
+    let message = AggregateConfidenceMessage {
+        feed_id:           [7; 32],
+        price:             -110,
+        conf_left:         10,
+        conf_right:        80,
+        exponent:          -8,
+        publish_time:      1_700_000_000,
+        prev_publish_time: 1_699_999_999,
+    };
+
+    let mut expected = vec![6];
+    expected.extend_from_slice(&[7; 32]);
+    expected.extend_from_slice(&(-110i64).to_be_bytes());
+    expected.extend_from_slice(&10u64.to_be_bytes());
+    expected.extend_from_slice(&80u64.to_be_bytes());
+    expected.extend_from_slice(&(-8i32).to_be_bytes());
+    expected.extend_from_slice(&1_700_000_000i64.to_be_bytes());
+    expected.extend_from_slice(&1_699_999_999i64.to_be_bytes());
+    assert_eq!(message.to_bytes(), expected);
+}
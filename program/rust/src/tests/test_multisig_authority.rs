@@ -0,0 +1,334 @@
+use {
+    crate::{
+        accounts::{
+            PermissionAccount,
+            PriceAccount,
+            PriceAccountFlags,
+            PublisherManagerAccount,
+        },
+        error::OracleError,
+        instruction::{
+            builders,
+            OracleCommand,
+            UpdPermissionsArgs,
+        },
+        tests::pyth_simulator::{
+            copy_keypair,
+            PythSimulator,
+        },
+    },
+    solana_program::{
+        native_token::LAMPORTS_PER_SOL,
+        pubkey::Pubkey,
+        rent::Rent,
+    },
+    solana_sdk::{
+        signature::Keypair,
+        signer::Signer,
+    },
+    std::mem::size_of,
+};
+
+#[tokio::test]
+async fn test_multisig_authority() {
+    let mut sim = PythSimulator::new().await;
+    let program_id = sim.program_id;
+    let master_authority = copy_keypair(&sim.genesis_keypair);
+
+    let mapping_keypair = sim.init_mapping().await.unwrap();
+    let product_keypair = sim.add_product(&mapping_keypair).await.unwrap();
+    let price_keypair = sim.add_price(&product_keypair, -8).await.unwrap();
+
+    let security_authority = Keypair::new();
+    sim.transfer_authorities(
+        UpdPermissionsArgs {
+            header:                  OracleCommand::ProposeAuthorities.into(),
+            master_authority:        master_authority.pubkey(),
+            data_curation_authority: Pubkey::new_unique(),
+            security_authority:      security_authority.pubkey(),
+        },
+        &master_authority,
+    )
+    .await
+    .unwrap();
+
+    let signers = [Keypair::new(), Keypair::new(), Keypair::new()];
+    let outsider = Keypair::new();
+    for keypair in signers.iter().chain([&outsider, &security_authority]) {
+        sim.airdrop(&keypair.pubkey(), LAMPORTS_PER_SOL)
+            .await
+            .unwrap();
+    }
+    let signer_keys: Vec<Pubkey> = signers.iter().map(|signer| signer.pubkey()).collect();
+
+    // Only the upgrade authority can set the multisig authority
+    assert_eq!(
+        sim.process_ix_as(
+            builders::set_multisig_authority(
+                &program_id,
+                &master_authority.pubkey(),
+                2,
+                &signer_keys
+            ),
+            &master_authority,
+        )
+        .await
+        .unwrap_err()
+        .unwrap(),
+        OracleError::InvalidUpgradeAuthority.into()
+    );
+
+    // Invalid configurations are rejected, as is disabling a multisig authority that doesn't
+    // exist
+    for (threshold, keys) in [
+        (0, vec![]),
+        (4, signer_keys.clone()),
+        (2, vec![signer_keys[0], signer_keys[1], signer_keys[0]]),
+        (1, vec![Pubkey::default()]),
+    ] {
+        assert_eq!(
+            sim.set_multisig_authority(threshold, &keys)
+                .await
+                .unwrap_err()
+                .unwrap(),
+            OracleError::InvalidMultisigAuthority.into()
+        );
+    }
+
+    // Enabling the multisig authority resizes the permissions account
+    sim.set_multisig_authority(2, &signer_keys).await.unwrap();
+    let permissions_account = sim.get_account(sim.get_permissions_pubkey()).await.unwrap();
+    assert_eq!(
+        permissions_account.data.len(),
+        PermissionAccount::MULTISIG_AUTHORITY_SPACE
+    );
+    assert!(Rent::default().is_exempt(
+        permissions_account.lamports,
+        PermissionAccount::MULTISIG_AUTHORITY_SPACE
+    ));
+
+    // From now on, the upgrade authority alone can neither replace nor disable it
+    let upgrade_authority = copy_keypair(&sim.upgrade_authority);
+    let set_multisig_authority = |threshold: u8, keys: &[Pubkey], additional_signers: &[Pubkey]| {
+        builders::with_additional_signers(
+            builders::set_multisig_authority(
+                &program_id,
+                &upgrade_authority.pubkey(),
+                threshold,
+                keys,
+            ),
+            additional_signers,
+        )
+    };
+    for (threshold, keys) in [(1, vec![outsider.pubkey()]), (0, vec![])] {
+        assert_eq!(
+            sim.process_ix_as(
+                set_multisig_authority(threshold, &keys, &[]),
+                &upgrade_authority
+            )
+            .await
+            .unwrap_err()
+            .unwrap(),
+            OracleError::PermissionViolation.into()
+        );
+        assert_eq!(
+            sim.process_ix_signed_by(
+                set_multisig_authority(threshold, &keys, &[signer_keys[0]]),
+                &vec![&upgrade_authority, &signers[0]],
+            )
+            .await
+            .unwrap_err()
+            .unwrap(),
+            OracleError::PermissionViolation.into()
+        );
+    }
+
+    let publisher = Pubkey::new_unique();
+    let add_publisher = |funding_account: &Keypair, additional_signers: &[&Keypair]| {
+        builders::with_additional_signers(
+            builders::add_publisher(
+                &program_id,
+                &funding_account.pubkey(),
+                &price_keypair.pubkey(),
+                &publisher,
+            ),
+            &additional_signers
+                .iter()
+                .map(|signer| signer.pubkey())
+                .collect::<Vec<_>>(),
+        )
+    };
+
+    // The master authority alone, a single signer or a signer with a key outside of the set
+    // can't approve the instruction
+    for (funding_account, additional_signers) in [
+        (&master_authority, vec![]),
+        (&signers[0], vec![]),
+        (&signers[0], vec![&outsider]),
+        (&master_authority, vec![&signers[1]]),
+    ] {
+        let mut keypairs = vec![funding_account];
+        keypairs.extend(&additional_signers);
+        assert_eq!(
+            sim.process_ix_signed_by(
+                add_publisher(funding_account, &additional_signers),
+                &keypairs
+            )
+            .await
+            .unwrap_err()
+            .unwrap(),
+            OracleError::PermissionViolation.into()
+        );
+    }
+
+    // Two signers of the set reach the threshold
+    sim.process_ix_signed_by(
+        add_publisher(&signers[0], &[&signers[2]]),
+        &vec![&signers[0], &signers[2]],
+    )
+    .await
+    .unwrap();
+    let price_data = sim
+        .get_account_data_as::<PriceAccount>(price_keypair.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(price_data.num_, 1);
+    assert_eq!(price_data.comp_[0].pub_, publisher);
+
+    // So do commands reserved to the master authority
+    sim.process_ix_signed_by(
+        builders::with_additional_signers(
+            builders::set_price_flags(
+                &program_id,
+                &outsider.pubkey(),
+                &price_keypair.pubkey(),
+                PriceAccountFlags::ACCUMULATOR_V2,
+                PriceAccountFlags::empty(),
+            ),
+            &[signer_keys[1], signer_keys[2]],
+        ),
+        &vec![&outsider, &signers[1], &signers[2]],
+    )
+    .await
+    .unwrap();
+
+    // The authorities of the roles keep their other commands, but changing the publishers needs
+    // the multisig authority
+    sim.process_ix_as(
+        builders::set_min_pub(
+            &program_id,
+            &security_authority.pubkey(),
+            &price_keypair.pubkey(),
+            1,
+        ),
+        &security_authority,
+    )
+    .await
+    .unwrap();
+    let del_publisher = |additional_signers: &[Pubkey]| {
+        builders::with_additional_signers(
+            builders::del_publisher(
+                &program_id,
+                &security_authority.pubkey(),
+                &price_keypair.pubkey(),
+                &publisher,
+            ),
+            additional_signers,
+        )
+    };
+    assert_eq!(
+        sim.process_ix_as(del_publisher(&[]), &security_authority)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::PermissionViolation.into()
+    );
+    sim.process_ix_signed_by(
+        del_publisher(&[signer_keys[0], signer_keys[1]]),
+        &vec![&security_authority, &signers[0], &signers[1]],
+    )
+    .await
+    .unwrap();
+
+    // Otherwise the security authority could delegate the publishers to itself
+    let publisher_manager_keypair = sim
+        .create_pyth_account(size_of::<PublisherManagerAccount>())
+        .await;
+    let set_publisher_manager = |additional_signers: &[Pubkey]| {
+        builders::with_additional_signers(
+            builders::set_publisher_manager(
+                &program_id,
+                &security_authority.pubkey(),
+                &publisher_manager_keypair.pubkey(),
+                &security_authority.pubkey(),
+                None,
+                &[product_keypair.pubkey()],
+            ),
+            additional_signers,
+        )
+    };
+    assert_eq!(
+        sim.process_ix_as(set_publisher_manager(&[]), &security_authority)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::PermissionViolation.into()
+    );
+    sim.process_ix_signed_by(
+        set_publisher_manager(&[signer_keys[0], signer_keys[1]]),
+        &vec![&security_authority, &signers[0], &signers[1]],
+    )
+    .await
+    .unwrap();
+
+    // So does proposing new authorities, on top of the upgrade authority
+    let propose_authorities = |additional_signers: &[Pubkey]| {
+        builders::with_additional_signers(
+            builders::propose_authorities(
+                &program_id,
+                &upgrade_authority.pubkey(),
+                &master_authority.pubkey(),
+                &Pubkey::new_unique(),
+                &security_authority.pubkey(),
+                0,
+                0,
+            ),
+            additional_signers,
+        )
+    };
+    assert_eq!(
+        sim.process_ix_as(propose_authorities(&[]), &upgrade_authority)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::PermissionViolation.into()
+    );
+    sim.process_ix_signed_by(
+        propose_authorities(&[signer_keys[0], signer_keys[2]]),
+        &vec![&upgrade_authority, &signers[0], &signers[2]],
+    )
+    .await
+    .unwrap();
+
+    // Once disabled with its approval, the master authority is enough again and additional
+    // signers are rejected
+    sim.process_ix_signed_by(
+        set_multisig_authority(0, &[], &[signer_keys[1], signer_keys[2]]),
+        &vec![&upgrade_authority, &signers[1], &signers[2]],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        sim.process_ix_signed_by(
+            add_publisher(&master_authority, &[&signers[0]]),
+            &vec![&master_authority, &signers[0]],
+        )
+        .await
+        .unwrap_err()
+        .unwrap(),
+        OracleError::InvalidNumberOfAccounts.into()
+    );
+    sim.process_ix_as(add_publisher(&master_authority, &[]), &master_authority)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,146 @@
+use {
+    crate::{
+        accounts::{
+            OutlierFilter,
+            OutlierFilterMode,
+            PriceAccount,
+        },
+        c_oracle_header::PC_STATUS_TRADING,
+        instruction::builders,
+        tests::pyth_simulator::{
+            PriceFixture,
+            PythSimulator,
+            Quote,
+        },
+    },
+    solana_sdk::{
+        instruction::InstructionError,
+        signer::Signer,
+        transaction::TransactionError,
+    },
+    std::mem::size_of,
+};
+
+#[tokio::test]
+async fn test_outlier_filter() {
+    let mut sim = PythSimulator::new().await;
+    let PriceFixture {
+        program_id,
+        master_authority,
+        price,
+        publishers,
+        outsider,
+        ..
+    } = sim.setup_price_fixture(4).await;
+    sim.process_ix_as(
+        builders::set_min_pub(&program_id, &master_authority.pubkey(), &price, 1),
+        &master_authority,
+    )
+    .await
+    .unwrap();
+
+    // Only the authorities can set the outlier filter
+    sim.assert_permission_violation(
+        builders::set_outlier_filter(
+            &program_id,
+            &outsider.pubkey(),
+            &price,
+            Some(OutlierFilterMode::Median),
+            3,
+        ),
+        &outsider,
+    )
+    .await;
+
+    // An enabled filter needs a non-zero k
+    assert_eq!(
+        sim.process_ix_as(
+            builders::set_outlier_filter(
+                &program_id,
+                &master_authority.pubkey(),
+                &price,
+                Some(OutlierFilterMode::Median),
+                0
+            ),
+            &master_authority,
+        )
+        .await
+        .unwrap_err()
+        .unwrap(),
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument)
+    );
+
+    // Setting the filter resizes the price account
+    sim.process_ix_as(
+        builders::set_outlier_filter(
+            &program_id,
+            &master_authority.pubkey(),
+            &price,
+            Some(OutlierFilterMode::Median),
+            3,
+        ),
+        &master_authority,
+    )
+    .await
+    .unwrap();
+    let price_account = sim
+        .get_resized_account(
+            price,
+            PriceAccount::EXTENSIONS_HEADER_SPACE + size_of::<OutlierFilter>(),
+        )
+        .await;
+    let outlier_filter = PriceAccount::outlier_filter(&price_account.data).unwrap();
+    assert_eq!(outlier_filter.mode(), Some(OutlierFilterMode::Median));
+    assert_eq!(outlier_filter.k, 3);
+
+    // Each round aggregates the quotes of the previous one: the fat-finger quote is rejected
+    // until the filter is disabled
+    let expected_aggregates = [(None, 0, 0), (Some((100, 1)), 3, 1), (Some((100, 2)), 4, 0)];
+    for (round, (expected_aggregate, expected_num_qt, expected_num_rejected)) in
+        expected_aggregates.into_iter().enumerate()
+    {
+        if round == 2 {
+            sim.process_ix_as(
+                builders::set_outlier_filter(
+                    &program_id,
+                    &master_authority.pubkey(),
+                    &price,
+                    None,
+                    0,
+                ),
+                &master_authority,
+            )
+            .await
+            .unwrap();
+        }
+
+        sim.warp_to_slot(10 * (round as u64 + 1)).await.unwrap();
+        for (publisher, price_value) in publishers.iter().zip([100, 101, 99, 10000]) {
+            sim.upd_price(
+                publisher,
+                price,
+                Quote {
+                    price:      price_value,
+                    confidence: 1,
+                    status:     PC_STATUS_TRADING,
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        let price_account = sim.get_account(price).await.unwrap();
+        let outlier_filter = PriceAccount::outlier_filter(&price_account.data).unwrap();
+        assert_eq!(outlier_filter.num_rejected, expected_num_rejected);
+        let price_data = sim
+            .get_account_data_as::<PriceAccount>(price)
+            .await
+            .unwrap();
+        assert_eq!(price_data.num_qt_, expected_num_qt);
+        if let Some((expected_price, expected_conf)) = expected_aggregate {
+            assert_eq!(price_data.agg_.status_, PC_STATUS_TRADING);
+            assert_eq!(price_data.agg_.price_, expected_price);
+            assert_eq!(price_data.agg_.conf_, expected_conf);
+        }
+    }
+}
@@ -272,55 +272,82 @@ fn test_permission_migration() {
         Err(OracleError::PermissionViolation.into())
     );
 
-    // Security authority can't change minimum number of publishers
-    assert_eq!(
-        process_instruction(
-            &program_id,
-            &[
-                security_auth_account.clone(),
-                price_account.clone(),
-                permissions_account.clone(),
-            ],
-            bytes_of::<SetMinPubArgs>(&SetMinPubArgs {
-                header:             SetMinPub.into(),
-                minimum_publishers: 5,
-                unused_:            [0; 3],
-            }),
-        ),
-        Err(OracleError::PermissionViolation.into())
-    );
+    // Security authority can change minimum number of publishers
+    process_instruction(
+        &program_id,
+        &[
+            security_auth_account.clone(),
+            price_account.clone(),
+            permissions_account.clone(),
+        ],
+        bytes_of::<SetMinPubArgs>(&SetMinPubArgs {
+            header:             SetMinPub.into(),
+            minimum_publishers: 5,
+            unused_:            [0; 3],
+        }),
+    )
+    .unwrap();
+
+    // Security authority can change maximum latency
+    process_instruction(
+        &program_id,
+        &[
+            security_auth_account.clone(),
+            price_account.clone(),
+            permissions_account.clone(),
+        ],
+        bytes_of::<SetMaxLatencyArgs>(&SetMaxLatencyArgs {
+            header:      SetMaxLatency.into(),
+            max_latency: 5,
+            unused_:     [0; 3],
+        }),
+    )
+    .unwrap();
 
-    // Security authority can't change maximum latency
+    // Security authority can add publishers
+    process_instruction(
+        &program_id,
+        &[
+            security_auth_account.clone(),
+            price_account.clone(),
+            permissions_account.clone(),
+        ],
+        bytes_of::<AddPublisherArgs>(&AddPublisherArgs {
+            header:    AddPublisher.into(),
+            publisher: Pubkey::new_unique(),
+        }),
+    )
+    .unwrap();
+
+    // Security authority can't initialize price accounts
     assert_eq!(
         process_instruction(
             &program_id,
             &[
                 security_auth_account.clone(),
                 price_account.clone(),
-                permissions_account.clone(),
+                permissions_account.clone()
             ],
-            bytes_of::<SetMaxLatencyArgs>(&SetMaxLatencyArgs {
-                header:      SetMaxLatency.into(),
-                max_latency: 5,
-                unused_:     [0; 3],
-            }),
+            bytes_of::<InitPriceArgs>(&InitPriceArgs {
+                header:     InitPrice.into(),
+                exponent:   -8,
+                price_type: 1,
+            })
         ),
         Err(OracleError::PermissionViolation.into())
     );
 
-    // Security authority can't add publishers
+    // Security authority can't delete products
     assert_eq!(
         process_instruction(
             &program_id,
             &[
                 security_auth_account.clone(),
-                price_account.clone(),
-                permissions_account.clone(),
+                mapping_account.clone(),
+                product_account.clone(),
+                permissions_account.clone()
             ],
-            bytes_of::<AddPublisherArgs>(&AddPublisherArgs {
-                header:    AddPublisher.into(),
-                publisher: Pubkey::new_unique(),
-            })
+            bytes_of::<CommandHeader>(&DelProduct.into())
         ),
         Err(OracleError::PermissionViolation.into())
     )
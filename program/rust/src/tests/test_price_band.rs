@@ -0,0 +1,261 @@
+use {
+    crate::{
+        accounts::{
+            AggregationDiagnostics,
+            ExclusionReason,
+            PriceAccount,
+            PriceAccountFlags,
+            PriceBand,
+        },
+        aggregation::diagnostics::record_outside_price_band,
+        c_oracle_header::{
+            PC_STATUS_IGNORED,
+            PC_STATUS_TRADING,
+            PC_STATUS_UNKNOWN,
+        },
+        instruction::{
+            builders,
+            PriceUpdate,
+        },
+        tests::pyth_simulator::{
+            PriceFixture,
+            PythSimulator,
+            Quote,
+        },
+        utils::get_status_for_price_band,
+        validator,
+    },
+    bytemuck::Zeroable,
+    solana_program::pubkey::Pubkey,
+    solana_sdk::{
+        instruction::InstructionError,
+        signer::Signer,
+        transaction::TransactionError,
+    },
+    std::mem::size_of,
+};
+
+#[test]
+fn test_get_status_for_price_band() {
+    let price_band = PriceBand {
+        min_price: -10,
+        max_price: 1000,
+    };
+    for (price, expected_status) in [
+        (-11, PC_STATUS_IGNORED),
+        (-10, PC_STATUS_TRADING),
+        (0, PC_STATUS_TRADING),
+        (1000, PC_STATUS_TRADING),
+        (10_000, PC_STATUS_IGNORED),
+    ] {
+        assert_eq!(
+            get_status_for_price_band(price, PC_STATUS_TRADING, Some(&price_band)),
+            expected_status
+        );
+    }
+    assert_eq!(
+        get_status_for_price_band(10_000, PC_STATUS_UNKNOWN, Some(&price_band)),
+        PC_STATUS_IGNORED
+    );
+    assert_eq!(
+        get_status_for_price_band(5, PC_STATUS_UNKNOWN, Some(&price_band)),
+        PC_STATUS_UNKNOWN
+    );
+
+    // Without an enabled band, every price is valid
+    assert_eq!(
+        get_status_for_price_band(i64::MAX, PC_STATUS_TRADING, Some(&PriceBand::zeroed())),
+        PC_STATUS_TRADING
+    );
+    assert_eq!(
+        get_status_for_price_band(i64::MAX, PC_STATUS_TRADING, None),
+        PC_STATUS_TRADING
+    );
+}
+
+#[test]
+fn test_record_outside_price_band() {
+    let mut price_account = PriceAccount::zeroed();
+    price_account.num_ = 3;
+    for (comp, price) in price_account.comp_.iter_mut().zip([1000, 100, 1000]) {
+        comp.agg_.price_ = price;
+    }
+    let mut diagnostics = AggregationDiagnostics::zeroed();
+    diagnostics.reasons[..3].copy_from_slice(&[
+        ExclusionReason::ConfidenceRatio as u8,
+        ExclusionReason::ConfidenceRatio as u8,
+        ExclusionReason::Stale as u8,
+    ]);
+
+    // Without an enabled band, the ignored quotes are too wide
+    record_outside_price_band(&price_account, &PriceBand::zeroed(), &mut diagnostics);
+    assert_eq!(
+        diagnostics.exclusion_reason(0),
+        Some(ExclusionReason::ConfidenceRatio)
+    );
+
+    let price_band = PriceBand {
+        min_price: 50,
+        max_price: 200,
+    };
+    record_outside_price_band(&price_account, &price_band, &mut diagnostics);
+    assert_eq!(
+        (0..3)
+            .map(|i| diagnostics.exclusion_reason(i))
+            .collect::<Vec<_>>(),
+        vec![
+            Some(ExclusionReason::OutsidePriceBand),
+            Some(ExclusionReason::ConfidenceRatio),
+            Some(ExclusionReason::Stale),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_set_price_band() {
+    let mut sim = PythSimulator::new().await;
+    let PriceFixture {
+        program_id,
+        master_authority,
+        price,
+        publishers,
+        outsider,
+        ..
+    } = sim.setup_price_fixture(1).await;
+    let publisher = &publishers[0];
+
+    // Only the authorities can set the band
+    sim.assert_permission_violation(
+        builders::set_price_band(&program_id, &outsider.pubkey(), &price, 50, 200),
+        &outsider,
+    )
+    .await;
+
+    // The bounds can't be swapped
+    assert_eq!(
+        sim.process_ix_as(
+            builders::set_price_band(&program_id, &master_authority.pubkey(), &price, 200, 50),
+            &master_authority,
+        )
+        .await
+        .unwrap_err()
+        .unwrap(),
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument)
+    );
+
+    // Setting the band only grows the price account by the header and the band, although it is
+    // the last extension
+    sim.process_ix_as(
+        builders::set_price_band(&program_id, &master_authority.pubkey(), &price, 50, 200),
+        &master_authority,
+    )
+    .await
+    .unwrap();
+    let price_account = sim
+        .get_resized_account(
+            price,
+            PriceAccount::EXTENSIONS_HEADER_SPACE + size_of::<PriceBand>(),
+        )
+        .await;
+    assert_eq!(
+        PriceAccount::price_band(&price_account.data),
+        Some(PriceBand {
+            min_price: 50,
+            max_price: 200,
+        })
+    );
+
+    // A price off by a power of ten is ignored
+    let quote = |price| Quote {
+        price,
+        confidence: 1,
+        status: PC_STATUS_TRADING,
+    };
+    sim.warp_to_slot(100).await.unwrap();
+    sim.upd_price(publisher, price, quote(1000)).await.unwrap();
+    assert_eq!(
+        get_publisher_status(&mut sim, price).await,
+        PC_STATUS_IGNORED
+    );
+    sim.warp_to_slot(200).await.unwrap();
+    sim.upd_price(publisher, price, quote(100)).await.unwrap();
+    assert_eq!(
+        get_publisher_status(&mut sim, price).await,
+        PC_STATUS_TRADING
+    );
+
+    // Batch updates use the band of each price account as well
+    sim.warp_to_slot(300).await.unwrap();
+    let update = |price, publishing_slot| PriceUpdate {
+        status: PC_STATUS_TRADING,
+        corp_act_status: 0,
+        price,
+        confidence: 1,
+        publishing_slot,
+    };
+    sim.process_ix_as(
+        builders::upd_price_batch(
+            &program_id,
+            &publisher.pubkey(),
+            &[(price, update(10, 300))],
+        ),
+        publisher,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        get_publisher_status(&mut sim, price).await,
+        PC_STATUS_IGNORED
+    );
+
+    // (0, 0) removes the band without shrinking the account
+    sim.process_ix_as(
+        builders::set_price_band(&program_id, &master_authority.pubkey(), &price, 0, 0),
+        &master_authority,
+    )
+    .await
+    .unwrap();
+    sim.warp_to_slot(400).await.unwrap();
+    sim.upd_price(publisher, price, quote(1000)).await.unwrap();
+    assert_eq!(
+        get_publisher_status(&mut sim, price).await,
+        PC_STATUS_TRADING
+    );
+    assert_eq!(
+        sim.get_account(price).await.unwrap().data.len(),
+        PriceAccount::EXTENSIONS_HEADER_SPACE + size_of::<PriceBand>()
+    );
+
+    // The other extensions aren't enabled, so they don't add their messages to the ones of the
+    // aggregation
+    let mut price_account_data = sim.get_account(price).await.unwrap().data;
+    assert!(PriceAccount::publisher_stats(&price_account_data).is_none());
+    assert!(PriceAccount::aggregate_confidence(&price_account_data).is_none());
+    {
+        let price_data =
+            validator::checked_load_price_account_mut(&mut price_account_data).unwrap();
+        price_data.flags.insert(PriceAccountFlags::ACCUMULATOR_V2);
+        price_data
+            .flags
+            .insert(PriceAccountFlags::MESSAGE_BUFFER_CLEARED);
+    }
+    let messages = validator::aggregate_price_with_extensions(
+        401,
+        501,
+        &price,
+        &mut price_account_data,
+        &[],
+        &[],
+    )
+    .unwrap();
+    assert_eq!(messages.len(), 2);
+}
+
+async fn get_publisher_status(sim: &mut PythSimulator, price: Pubkey) -> u32 {
+    sim.get_account_data_as::<PriceAccount>(price)
+        .await
+        .unwrap()
+        .comp_[0]
+        .latest_
+        .status_
+}
@@ -0,0 +1,193 @@
+use {
+    crate::{
+        accounts::{
+            PriceAccount,
+            PriceAccountFlags,
+            PriceHistory,
+            PriceHistoryEntry,
+            PRICE_HISTORY_LEN,
+        },
+        c_oracle_header::{
+            PC_STATUS_TRADING,
+            PC_STATUS_UNKNOWN,
+        },
+        instruction::builders,
+        tests::pyth_simulator::{
+            PriceFixture,
+            PythSimulator,
+            Quote,
+        },
+        validator,
+    },
+    bytemuck::Zeroable,
+    solana_sdk::signer::Signer,
+    std::mem::size_of,
+};
+
+fn entry(slot: u64, status: u32) -> PriceHistoryEntry {
+    PriceHistoryEntry {
+        slot,
+        timestamp: slot as i64 + 1000,
+        price: slot as i64 * 10,
+        conf: 1,
+        status,
+        unused_: 0,
+    }
+}
+
+#[test]
+fn test_price_history_ring_buffer() {
+    let mut price_history = PriceHistory::zeroed();
+    assert_eq!(price_history.iter_latest().count(), 0);
+    assert_eq!(price_history.price_at_or_before(u64::MAX), None);
+
+    price_history.push(entry(10, PC_STATUS_TRADING));
+    price_history.push(entry(11, PC_STATUS_UNKNOWN));
+    assert_eq!(
+        price_history.iter_latest().copied().collect::<Vec<_>>(),
+        vec![entry(11, PC_STATUS_UNKNOWN), entry(10, PC_STATUS_TRADING)]
+    );
+    assert_eq!(price_history.price_at_or_before(9), None);
+    assert_eq!(
+        price_history.price_at_or_before(10),
+        Some(entry(10, PC_STATUS_TRADING))
+    );
+    // Aggregates without TRADING status are skipped
+    assert_eq!(
+        price_history.price_at_or_before(100),
+        Some(entry(10, PC_STATUS_TRADING))
+    );
+
+    // Once full, the oldest entries are overwritten
+    for slot in 12..(12 + PRICE_HISTORY_LEN as u64) {
+        price_history.push(entry(slot, PC_STATUS_TRADING));
+    }
+    assert_eq!(price_history.num_writes, PRICE_HISTORY_LEN as u64 + 2);
+    assert_eq!(price_history.iter_latest().count(), PRICE_HISTORY_LEN);
+    assert_eq!(
+        price_history.iter_latest().next(),
+        Some(&entry(11 + PRICE_HISTORY_LEN as u64, PC_STATUS_TRADING))
+    );
+    assert_eq!(
+        price_history.iter_latest().last(),
+        Some(&entry(12, PC_STATUS_TRADING))
+    );
+    assert_eq!(price_history.price_at_or_before(11), None);
+    assert_eq!(
+        price_history.price_at_or_before(40),
+        Some(entry(40, PC_STATUS_TRADING))
+    );
+}
+
+#[tokio::test]
+async fn test_price_history() {
+    let mut sim = PythSimulator::new().await;
+    let PriceFixture {
+        program_id,
+        master_authority,
+        price,
+        publishers,
+        outsider,
+        ..
+    } = sim.setup_price_fixture(1).await;
+    let publisher = &publishers[0];
+    // Aggregate the quotes of a single publisher
+    sim.process_ix_as(
+        builders::set_min_pub(&program_id, &master_authority.pubkey(), &price, 1),
+        &master_authority,
+    )
+    .await
+    .unwrap();
+
+    // Only the authorities can initialize the history
+    sim.assert_permission_violation(
+        builders::init_price_history(&program_id, &outsider.pubkey(), &price),
+        &outsider,
+    )
+    .await;
+
+    sim.process_ix_as(
+        builders::init_price_history(&program_id, &master_authority.pubkey(), &price),
+        &master_authority,
+    )
+    .await
+    .unwrap();
+    let price_account = sim
+        .get_resized_account(
+            price,
+            PriceAccount::EXTENSIONS_HEADER_SPACE + size_of::<PriceHistory>(),
+        )
+        .await;
+    assert_eq!(
+        PriceAccount::price_history(&price_account.data)
+            .unwrap()
+            .num_writes,
+        0
+    );
+
+    // Every aggregation is recorded, the first one has no valid quote
+    for (slot, price_value) in [(10, 100), (20, 110), (30, 120)] {
+        sim.warp_to_slot(slot).await.unwrap();
+        sim.upd_price(
+            publisher,
+            price,
+            Quote {
+                price:      price_value,
+                confidence: 1,
+                status:     PC_STATUS_TRADING,
+            },
+        )
+        .await
+        .unwrap();
+    }
+
+    let price_history =
+        PriceAccount::price_history(&sim.get_account(price).await.unwrap().data).unwrap();
+    let entries: Vec<(u64, i64, u32)> = price_history
+        .iter_latest()
+        .map(|entry| (entry.slot, entry.price, entry.status))
+        .collect();
+    assert_eq!(
+        entries,
+        vec![
+            (30, 110, PC_STATUS_TRADING),
+            (20, 100, PC_STATUS_TRADING),
+            (10, 0, PC_STATUS_UNKNOWN),
+        ]
+    );
+    assert_eq!(price_history.price_at_or_before(25).unwrap().price, 100);
+    assert_eq!(price_history.price_at_or_before(30).unwrap().price, 110);
+    assert_eq!(price_history.price_at_or_before(15), None);
+
+    // The validator aggregation records its aggregates as well
+    let mut price_account_data = sim.get_account(price).await.unwrap().data;
+    {
+        let price_data =
+            validator::checked_load_price_account_mut(&mut price_account_data).unwrap();
+        price_data.flags.insert(PriceAccountFlags::ACCUMULATOR_V2);
+        price_data
+            .flags
+            .insert(PriceAccountFlags::MESSAGE_BUFFER_CLEARED);
+    }
+    validator::aggregate_price_with_extensions(31, 1031, &price, &mut price_account_data, &[], &[])
+        .unwrap();
+    let price_history = PriceAccount::price_history(&price_account_data).unwrap();
+    assert_eq!(price_history.num_writes, 4);
+    let latest = price_history.iter_latest().next().unwrap();
+    assert_eq!(
+        (latest.slot, latest.timestamp, latest.price, latest.status),
+        (31, 1031, 120, PC_STATUS_TRADING)
+    );
+
+    // Initializing the history again clears it
+    sim.process_ix_as(
+        builders::init_price_history(&program_id, &master_authority.pubkey(), &price),
+        &master_authority,
+    )
+    .await
+    .unwrap();
+    let price_history =
+        PriceAccount::price_history(&sim.get_account(price).await.unwrap().data).unwrap();
+    assert_eq!(price_history.num_writes, 0);
+    assert_eq!(price_history.price_at_or_before(u64::MAX), None);
+}
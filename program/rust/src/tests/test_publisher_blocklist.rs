@@ -0,0 +1,389 @@
+use {
+    crate::{
+        accounts::{
+            AggregationExtensions,
+            PriceAccount,
+            PriceAccountFlags,
+            PriceInfo,
+            PublisherBlocklistAccount,
+            PythAccount,
+            PUBLISHER_BLOCKLIST_SEED,
+        },
+        aggregation::{
+            update_aggregate,
+            EmaParams,
+        },
+        c_oracle_header::{
+            PC_MAGIC,
+            PC_STATUS_IGNORED,
+            PC_STATUS_TRADING,
+        },
+        error::OracleError,
+        instruction::{
+            builders,
+            PriceUpdate,
+        },
+        tests::pyth_simulator::{
+            copy_keypair,
+            PythSimulator,
+        },
+        validator::{
+            self,
+            AggregationError,
+        },
+    },
+    bytemuck::{
+        bytes_of,
+        Zeroable,
+    },
+    solana_program::{
+        native_token::LAMPORTS_PER_SOL,
+        pubkey::Pubkey,
+    },
+    solana_sdk::{
+        instruction::InstructionError,
+        signature::Keypair,
+        signer::Signer,
+        transaction::TransactionError,
+    },
+};
+
+#[test]
+fn test_publisher_blocklist_aggregation() {
+    let blocked_publisher = Pubkey::new_unique();
+    let mut publisher_blocklist = PublisherBlocklistAccount::zeroed();
+    publisher_blocklist.num_publishers = 1;
+    publisher_blocklist.publishers[0] = blocked_publisher;
+
+    let mut price_account = PriceAccount::zeroed();
+    price_account.num_ = 2;
+    price_account.min_pub_ = 1;
+    price_account.comp_[0].pub_ = blocked_publisher;
+    price_account.comp_[1].pub_ = Pubkey::new_unique();
+    for (comp, price_) in price_account.comp_.iter_mut().zip([100, 200]) {
+        comp.latest_ = PriceInfo {
+            price_,
+            conf_: 10,
+            status_: PC_STATUS_TRADING,
+            pub_slot_: 1000,
+            corp_act_status_: 0,
+        };
+    }
+
+    assert!(update_aggregate(
+        &mut price_account,
+        1001,
+        0,
+        EmaParams::default(),
+        &mut AggregationExtensions::new(&mut []),
+        Some(&publisher_blocklist),
+        None,
+    ));
+    assert_eq!(price_account.num_qt_, 1);
+    assert_eq!(price_account.agg_.price_, 200);
+    // The quote of the blocked publisher is only ignored for the aggregation
+    assert_eq!(price_account.comp_[0].agg_.status_, PC_STATUS_IGNORED);
+    assert_eq!(price_account.comp_[0].latest_.status_, PC_STATUS_TRADING);
+    assert_eq!(price_account.comp_[1].latest_.status_, PC_STATUS_TRADING);
+}
+
+#[test]
+fn test_publisher_blocklist_validator() {
+    let blocked_publisher = Pubkey::new_unique();
+    let mut publisher_blocklist = PublisherBlocklistAccount::zeroed();
+    publisher_blocklist.header.magic_number = PC_MAGIC;
+    publisher_blocklist.header.account_type = PublisherBlocklistAccount::ACCOUNT_TYPE;
+    publisher_blocklist.num_publishers = 1;
+    publisher_blocklist.publishers[0] = blocked_publisher;
+
+    let price = Pubkey::new_unique();
+    let mut price_account_data = vec![0u8; PriceAccount::MINIMUM_SIZE];
+    let price_account = bytemuck::from_bytes_mut::<PriceAccount>(&mut price_account_data);
+    price_account.header.magic_number = PC_MAGIC;
+    price_account.header.account_type = PriceAccount::ACCOUNT_TYPE;
+    price_account.flags =
+        PriceAccountFlags::ACCUMULATOR_V2 | PriceAccountFlags::MESSAGE_BUFFER_CLEARED;
+    price_account.num_ = 2;
+    price_account.min_pub_ = 1;
+    price_account.comp_[0].pub_ = blocked_publisher;
+    price_account.comp_[1].pub_ = Pubkey::new_unique();
+    for (comp, price_) in price_account.comp_.iter_mut().zip([100, 200]) {
+        comp.latest_ = PriceInfo {
+            price_,
+            conf_: 10,
+            status_: PC_STATUS_TRADING,
+            pub_slot_: 1000,
+            corp_act_status_: 0,
+        };
+    }
+
+    // The validator rejects data that isn't a publisher blocklist account
+    assert_eq!(
+        validator::aggregate_price_with_extensions(
+            1001,
+            0,
+            &price,
+            &mut price_account_data,
+            &[0u8; 8],
+            &[]
+        ),
+        Err(AggregationError::InvalidPublisherBlocklist)
+    );
+
+    validator::aggregate_price_with_extensions(
+        1001,
+        0,
+        &price,
+        &mut price_account_data,
+        bytes_of(&publisher_blocklist),
+        &[],
+    )
+    .unwrap();
+    let price_account = validator::checked_load_price_account_mut(&mut price_account_data).unwrap();
+    assert_eq!(price_account.num_qt_, 1);
+    assert_eq!(price_account.agg_.price_, 200);
+    assert_eq!(price_account.comp_[0].agg_.status_, PC_STATUS_IGNORED);
+    assert_eq!(price_account.comp_[0].latest_.status_, PC_STATUS_TRADING);
+}
+
+#[tokio::test]
+async fn test_publisher_blocklist() {
+    let mut sim = PythSimulator::new().await;
+    let program_id = sim.program_id;
+    let master_authority = copy_keypair(&sim.genesis_keypair);
+    let publisher_blocklist = builders::publisher_blocklist_pubkey(&program_id);
+
+    let mapping_keypair = sim.init_mapping().await.unwrap();
+    let product_keypair = sim.add_product(&mapping_keypair).await.unwrap();
+    let price_keypair = sim.add_price(&product_keypair, -8).await.unwrap();
+    let price = price_keypair.pubkey();
+
+    let blocked_publisher = Keypair::new();
+    let publisher = Keypair::new();
+    let outsider = Keypair::new();
+    for keypair in [&blocked_publisher, &publisher, &outsider] {
+        sim.airdrop(&keypair.pubkey(), LAMPORTS_PER_SOL)
+            .await
+            .unwrap();
+    }
+    for keypair in [&blocked_publisher, &publisher] {
+        sim.add_publisher(&price_keypair, keypair.pubkey())
+            .await
+            .unwrap();
+    }
+    sim.process_ix_as(
+        builders::set_min_pub(&program_id, &master_authority.pubkey(), &price, 1),
+        &master_authority,
+    )
+    .await
+    .unwrap();
+
+    // Only the authorities can block publishers
+    assert_eq!(
+        sim.process_ix_as(
+            builders::block_publisher(&program_id, &outsider.pubkey(), &blocked_publisher.pubkey()),
+            &outsider,
+        )
+        .await
+        .unwrap_err()
+        .unwrap(),
+        OracleError::PermissionViolation.into()
+    );
+    assert!(sim.get_account(publisher_blocklist).await.is_none());
+
+    sim.process_ix_as(
+        builders::block_publisher(
+            &program_id,
+            &master_authority.pubkey(),
+            &blocked_publisher.pubkey(),
+        ),
+        &master_authority,
+    )
+    .await
+    .unwrap();
+    let blocklist_data = sim
+        .get_account_data_as::<PublisherBlocklistAccount>(publisher_blocklist)
+        .await
+        .unwrap();
+    assert_eq!(blocklist_data.num_publishers, 1);
+    assert_eq!(blocklist_data.publishers[0], blocked_publisher.pubkey());
+    assert_eq!(
+        blocklist_data.bump,
+        Pubkey::find_program_address(&[PUBLISHER_BLOCKLIST_SEED.as_bytes()], &program_id).1
+    );
+
+    // A publisher can't be blocked twice
+    assert_eq!(
+        sim.process_ix_as(
+            builders::block_publisher(
+                &program_id,
+                &master_authority.pubkey(),
+                &blocked_publisher.pubkey(),
+            ),
+            &master_authority,
+        )
+        .await
+        .unwrap_err()
+        .unwrap(),
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument)
+    );
+
+    let upd_price = |publisher: &Keypair, price_value: i64, slot: u64| {
+        builders::with_publisher_blocklist(
+            builders::upd_price(
+                &program_id,
+                &publisher.pubkey(),
+                &price,
+                PC_STATUS_TRADING,
+                price_value,
+                10,
+                slot,
+            ),
+            &program_id,
+        )
+    };
+
+    // Without the blocklist account, the update of the blocked publisher is stored...
+    sim.warp_to_slot(10).await.unwrap();
+    sim.process_ix_as(
+        builders::upd_price(
+            &program_id,
+            &blocked_publisher.pubkey(),
+            &price,
+            PC_STATUS_TRADING,
+            100,
+            10,
+            10,
+        ),
+        &blocked_publisher,
+    )
+    .await
+    .unwrap();
+    sim.process_ix_as(upd_price(&publisher, 200, 10), &publisher)
+        .await
+        .unwrap();
+
+    // ...but ignored by the aggregation, and it is rejected with the blocklist account
+    sim.warp_to_slot(20).await.unwrap();
+    assert_eq!(
+        sim.process_ix_as(upd_price(&blocked_publisher, 100, 20), &blocked_publisher)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::PermissionViolation.into()
+    );
+    sim.process_ix_as(upd_price(&publisher, 200, 20), &publisher)
+        .await
+        .unwrap();
+    let price_data = sim
+        .get_account_data_as::<PriceAccount>(price)
+        .await
+        .unwrap();
+    assert_eq!(price_data.agg_.status_, PC_STATUS_TRADING);
+    assert_eq!(price_data.agg_.price_, 200);
+    assert_eq!(price_data.num_qt_, 1);
+    let blocked_comp = price_data
+        .comp_
+        .iter()
+        .find(|comp| comp.pub_ == blocked_publisher.pubkey())
+        .unwrap();
+    assert_eq!(blocked_comp.agg_.status_, PC_STATUS_IGNORED);
+    assert_eq!(blocked_comp.latest_.status_, PC_STATUS_TRADING);
+
+    // Batch updates of the blocked publisher fail as well
+    sim.warp_to_slot(30).await.unwrap();
+    sim.process_ix_as(
+        builders::with_publisher_blocklist(
+            builders::upd_price_batch(
+                &program_id,
+                &blocked_publisher.pubkey(),
+                &[(
+                    price,
+                    PriceUpdate {
+                        status:          PC_STATUS_TRADING,
+                        corp_act_status: 0,
+                        price:           150,
+                        confidence:      10,
+                        publishing_slot: 30,
+                    },
+                )],
+            ),
+            &program_id,
+        ),
+        &blocked_publisher,
+    )
+    .await
+    .unwrap();
+    let price_data = sim
+        .get_account_data_as::<PriceAccount>(price)
+        .await
+        .unwrap();
+    let blocked_comp = price_data
+        .comp_
+        .iter()
+        .find(|comp| comp.pub_ == blocked_publisher.pubkey())
+        .unwrap();
+    assert_eq!(blocked_comp.latest_.price_, 100);
+
+    // Once unblocked, the publisher counts again
+    assert_eq!(
+        sim.process_ix_as(
+            builders::unblock_publisher(
+                &program_id,
+                &outsider.pubkey(),
+                &blocked_publisher.pubkey()
+            ),
+            &outsider,
+        )
+        .await
+        .unwrap_err()
+        .unwrap(),
+        OracleError::PermissionViolation.into()
+    );
+    sim.process_ix_as(
+        builders::unblock_publisher(
+            &program_id,
+            &master_authority.pubkey(),
+            &blocked_publisher.pubkey(),
+        ),
+        &master_authority,
+    )
+    .await
+    .unwrap();
+    let blocklist_data = sim
+        .get_account_data_as::<PublisherBlocklistAccount>(publisher_blocklist)
+        .await
+        .unwrap();
+    assert_eq!(blocklist_data.num_publishers, 0);
+    assert_eq!(blocklist_data.publishers[0], Pubkey::default());
+    assert_eq!(
+        sim.process_ix_as(
+            builders::unblock_publisher(
+                &program_id,
+                &master_authority.pubkey(),
+                &blocked_publisher.pubkey(),
+            ),
+            &master_authority,
+        )
+        .await
+        .unwrap_err()
+        .unwrap(),
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument)
+    );
+
+    sim.warp_to_slot(40).await.unwrap();
+    for keypair in [&blocked_publisher, &publisher] {
+        sim.process_ix_as(upd_price(keypair, 200, 40), keypair)
+            .await
+            .unwrap();
+    }
+    sim.warp_to_slot(50).await.unwrap();
+    sim.process_ix_as(upd_price(&publisher, 200, 50), &publisher)
+        .await
+        .unwrap();
+    let price_data = sim
+        .get_account_data_as::<PriceAccount>(price)
+        .await
+        .unwrap();
+    assert_eq!(price_data.num_qt_, 2);
+}
@@ -0,0 +1,221 @@
+use {
+    crate::{
+        accounts::{
+            PriceAccount,
+            PublisherManagerAccount,
+        },
+        error::OracleError,
+        instruction::builders,
+        tests::pyth_simulator::{
+            copy_keypair,
+            PythSimulator,
+        },
+    },
+    solana_program::{
+        instruction::Instruction,
+        native_token::LAMPORTS_PER_SOL,
+        pubkey::Pubkey,
+    },
+    solana_sdk::{
+        instruction::InstructionError,
+        signature::Keypair,
+        signer::Signer,
+        transaction::TransactionError,
+    },
+    std::mem::size_of,
+};
+
+#[tokio::test]
+async fn test_publisher_manager() {
+    let mut sim = PythSimulator::new().await;
+    let program_id = sim.program_id;
+    let master_authority = copy_keypair(&sim.genesis_keypair);
+
+    let mapping_keypair = sim.init_mapping().await.unwrap();
+    let product_1 = sim.add_product(&mapping_keypair).await.unwrap();
+    let price_1 = sim.add_price(&product_1, -8).await.unwrap();
+    let product_2 = sim.add_product(&mapping_keypair).await.unwrap();
+    let price_2 = sim.add_price(&product_2, -8).await.unwrap();
+
+    let other_mapping_keypair = sim.init_mapping().await.unwrap();
+    let product_3 = sim.add_product(&other_mapping_keypair).await.unwrap();
+    let price_3 = sim.add_price(&product_3, -8).await.unwrap();
+
+    let manager = Keypair::new();
+    sim.airdrop(&manager.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    let publisher_manager_keypair = sim
+        .create_pyth_account(size_of::<PublisherManagerAccount>())
+        .await;
+    let publisher = Pubkey::new_unique();
+
+    let add_publisher = |price_keypair: &Keypair, mapping_account: Option<&Pubkey>| {
+        builders::with_publisher_manager(
+            builders::add_publisher(
+                &program_id,
+                &manager.pubkey(),
+                &price_keypair.pubkey(),
+                &publisher,
+            ),
+            &publisher_manager_keypair.pubkey(),
+            mapping_account,
+        )
+    };
+    let set_publisher_manager =
+        |funding_account: &Keypair, mapping_account: Option<&Pubkey>, products: &[Pubkey]| {
+            builders::set_publisher_manager(
+                &program_id,
+                &funding_account.pubkey(),
+                &publisher_manager_keypair.pubkey(),
+                &manager.pubkey(),
+                mapping_account,
+                products,
+            )
+        };
+
+    // The manager isn't permissioned without the publisher manager account
+    assert_eq!(
+        sim.process_ix_as(
+            builders::add_publisher(
+                &program_id,
+                &manager.pubkey(),
+                &price_1.pubkey(),
+                &publisher
+            ),
+            &manager,
+        )
+        .await
+        .unwrap_err()
+        .unwrap(),
+        OracleError::PermissionViolation.into()
+    );
+
+    // Only the security authority can set the publisher manager, with a single kind of scope
+    assert_eq!(
+        sim.process_ix_as(
+            set_publisher_manager(&manager, None, &[product_1.pubkey()]),
+            &manager
+        )
+        .await
+        .unwrap_err()
+        .unwrap(),
+        OracleError::PermissionViolation.into()
+    );
+    assert_eq!(
+        sim.process_ix_as(
+            set_publisher_manager(
+                &master_authority,
+                Some(&mapping_keypair.pubkey()),
+                &[product_1.pubkey()]
+            ),
+            &master_authority
+        )
+        .await
+        .unwrap_err()
+        .unwrap(),
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument)
+    );
+
+    // Scope with a list of products
+    sim.process_ix_as(
+        set_publisher_manager(&master_authority, None, &[product_1.pubkey()]),
+        &master_authority,
+    )
+    .await
+    .unwrap();
+    sim.process_ix_as(add_publisher(&price_1, None), &manager)
+        .await
+        .unwrap();
+    assert_permission_violation(&mut sim, add_publisher(&price_2, None), &manager).await;
+
+    // Scope with a mapping account
+    sim.process_ix_as(
+        set_publisher_manager(&master_authority, Some(&mapping_keypair.pubkey()), &[]),
+        &master_authority,
+    )
+    .await
+    .unwrap();
+    assert_permission_violation(&mut sim, add_publisher(&price_2, None), &manager).await;
+    assert_permission_violation(
+        &mut sim,
+        add_publisher(&price_2, Some(&other_mapping_keypair.pubkey())),
+        &manager,
+    )
+    .await;
+    assert_permission_violation(
+        &mut sim,
+        add_publisher(&price_3, Some(&mapping_keypair.pubkey())),
+        &manager,
+    )
+    .await;
+    sim.process_ix_as(
+        add_publisher(&price_2, Some(&mapping_keypair.pubkey())),
+        &manager,
+    )
+    .await
+    .unwrap();
+
+    let price_data = sim
+        .get_account_data_as::<PriceAccount>(price_2.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(price_data.num_, 1);
+    assert_eq!(price_data.comp_[0].pub_, publisher);
+
+    sim.process_ix_as(
+        builders::with_publisher_manager(
+            builders::del_publisher(
+                &program_id,
+                &manager.pubkey(),
+                &price_2.pubkey(),
+                &publisher,
+            ),
+            &publisher_manager_keypair.pubkey(),
+            Some(&mapping_keypair.pubkey()),
+        ),
+        &manager,
+    )
+    .await
+    .unwrap();
+    let price_data = sim
+        .get_account_data_as::<PriceAccount>(price_2.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(price_data.num_, 0);
+
+    // Other keys can't use the delegation
+    let other_key = Keypair::new();
+    sim.airdrop(&other_key.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    assert_permission_violation(
+        &mut sim,
+        builders::with_publisher_manager(
+            builders::add_publisher(
+                &program_id,
+                &other_key.pubkey(),
+                &price_2.pubkey(),
+                &publisher,
+            ),
+            &publisher_manager_keypair.pubkey(),
+            Some(&mapping_keypair.pubkey()),
+        ),
+        &other_key,
+    )
+    .await;
+}
+
+async fn assert_permission_violation(
+    sim: &mut PythSimulator,
+    instruction: Instruction,
+    signer: &Keypair,
+) {
+    assert_eq!(
+        sim.process_ix_as(instruction, signer)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::PermissionViolation.into()
+    );
+}
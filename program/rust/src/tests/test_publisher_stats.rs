@@ -0,0 +1,189 @@
+use {
+    crate::{
+        accounts::{
+            PriceAccount,
+            PriceComponent,
+            PublisherStat,
+            PublisherStats,
+            PUBLISHER_DEVIATION_SCALE,
+        },
+        c_oracle_header::PC_STATUS_TRADING,
+        instruction::builders,
+        tests::pyth_simulator::{
+            PriceFixture,
+            PythSimulator,
+            Quote,
+        },
+    },
+    bytemuck::Zeroable,
+    solana_program::{
+        native_token::LAMPORTS_PER_SOL,
+        pubkey::Pubkey,
+    },
+    solana_sdk::{
+        signature::Keypair,
+        signer::Signer,
+    },
+    std::mem::size_of,
+};
+
+fn comps(publishers: &[u8]) -> Vec<PriceComponent> {
+    publishers
+        .iter()
+        .map(|i| PriceComponent {
+            pub_: Pubkey::new_from_array([*i; 32]),
+            ..PriceComponent::zeroed()
+        })
+        .collect()
+}
+
+fn stat(publisher: u8, num_aggregations: u64) -> PublisherStat {
+    PublisherStat {
+        publisher: Pubkey::new_from_array([publisher; 32]),
+        num_aggregations,
+        ..PublisherStat::zeroed()
+    }
+}
+
+#[test]
+fn test_publisher_stats_entry_mut() {
+    let mut publisher_stats = PublisherStats::zeroed();
+    let align = |publisher_stats: &mut PublisherStats, comps: &[PriceComponent]| {
+        for i in 0..comps.len() {
+            let entry = publisher_stats.entry_mut(comps, i);
+            assert_eq!(entry.publisher, comps[i].pub_);
+            entry.num_aggregations += 1;
+        }
+    };
+
+    align(&mut publisher_stats, &comps(&[2, 4, 6]));
+    align(&mut publisher_stats, &comps(&[2, 4, 6]));
+    assert_eq!(
+        publisher_stats.entries[..3],
+        [stat(2, 2), stat(4, 2), stat(6, 2)]
+    );
+
+    // The entries follow their publishers when publishers are added or removed
+    align(&mut publisher_stats, &comps(&[1, 2, 4, 6]));
+    assert_eq!(
+        publisher_stats.entries[..4],
+        [stat(1, 1), stat(2, 3), stat(4, 3), stat(6, 3)]
+    );
+    align(&mut publisher_stats, &comps(&[1, 4, 5, 6]));
+    assert_eq!(
+        publisher_stats.entries[..4],
+        [stat(1, 2), stat(4, 4), stat(5, 1), stat(6, 4)]
+    );
+
+    // Every entry can be in use
+    let all: Vec<u8> = (1..=publisher_stats.entries.len() as u8).collect();
+    align(&mut publisher_stats, &comps(&all));
+    assert_eq!(publisher_stats.entries[3], stat(4, 5));
+    assert_eq!(publisher_stats.entries[5], stat(6, 5));
+    assert_eq!(publisher_stats.entries[1], stat(2, 1));
+}
+
+#[tokio::test]
+async fn test_publisher_stats() {
+    let mut sim = PythSimulator::new().await;
+    let PriceFixture {
+        program_id,
+        master_authority,
+        price_keypair,
+        price,
+        mut publishers,
+        outsider,
+    } = sim.setup_price_fixture(3).await;
+    // The fourth publisher is added to the price account later
+    publishers.push(Keypair::new());
+    sim.airdrop(&publishers[3].pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    sim.process_ix_as(
+        builders::set_min_pub(&program_id, &master_authority.pubkey(), &price, 1),
+        &master_authority,
+    )
+    .await
+    .unwrap();
+
+    // Only the authorities can initialize the statistics
+    sim.assert_permission_violation(
+        builders::init_publisher_stats(&program_id, &outsider.pubkey(), &price),
+        &outsider,
+    )
+    .await;
+
+    sim.process_ix_as(
+        builders::init_publisher_stats(&program_id, &master_authority.pubkey(), &price),
+        &master_authority,
+    )
+    .await
+    .unwrap();
+    sim.get_resized_account(
+        price,
+        PriceAccount::EXTENSIONS_HEADER_SPACE + size_of::<PublisherStats>(),
+    )
+    .await;
+
+    // Each round aggregates the quotes of the previous one, at a price of 110. The fourth
+    // publisher is added after the second round and publishes from the third one.
+    for (round, slot) in [10, 20, 30, 40].into_iter().enumerate() {
+        if round == 2 {
+            sim.add_publisher(&price_keypair, publishers[3].pubkey())
+                .await
+                .unwrap();
+        }
+        sim.warp_to_slot(slot).await.unwrap();
+        for (publisher, price_value) in publishers
+            .iter()
+            .zip([100, 110, 200, 100])
+            .take(3 + round / 2)
+        {
+            sim.upd_price(
+                publisher,
+                price,
+                Quote {
+                    price:      price_value,
+                    confidence: 10,
+                    status:     PC_STATUS_TRADING,
+                },
+            )
+            .await
+            .unwrap();
+        }
+    }
+
+    let price_account = sim.get_account(price).await.unwrap();
+    let price_data = sim
+        .get_account_data_as::<PriceAccount>(price)
+        .await
+        .unwrap();
+    assert_eq!(price_data.agg_.price_, 110);
+    assert_eq!(price_data.agg_.conf_, 10);
+    let publisher_stats = PriceAccount::publisher_stats(&price_account.data).unwrap();
+    let message = price_data.as_publisher_stats_message(&price, &publisher_stats);
+    assert_eq!(message.feed_id, price.to_bytes());
+    assert_eq!(message.publish_slot, 40);
+    assert_eq!(message.stats.len(), 4);
+
+    let expected_stats = [(3, 0, 3), (3, 0, 0), (3, 3, 27), (1, 0, 1)];
+    for (publisher, (num_aggregations, num_misses, sum_deviation)) in
+        publishers.iter().zip(expected_stats)
+    {
+        let stat = message
+            .stats
+            .iter()
+            .find(|stat| stat.publisher == publisher.pubkey())
+            .unwrap();
+        assert_eq!(stat.num_aggregations, num_aggregations);
+        assert_eq!(stat.num_misses, num_misses);
+        assert_eq!(
+            stat.sum_deviation,
+            sum_deviation * PUBLISHER_DEVIATION_SCALE
+        );
+        assert_eq!(
+            stat.avg_deviation(),
+            sum_deviation * PUBLISHER_DEVIATION_SCALE / num_aggregations
+        );
+    }
+}
@@ -0,0 +1,251 @@
+use {
+    crate::{
+        accounts::{
+            PriceAccount,
+            PriceAccountFlags,
+            PublisherWeight,
+            PublisherWeights,
+        },
+        c_oracle_header::{
+            PC_NUM_COMP,
+            PC_STATUS_TRADING,
+        },
+        error::OracleError,
+        instruction::builders,
+        tests::pyth_simulator::{
+            PriceFixture,
+            PythSimulator,
+            Quote,
+        },
+    },
+    bytemuck::Zeroable,
+    solana_program::pubkey::Pubkey,
+    solana_sdk::{
+        instruction::InstructionError,
+        signer::Signer,
+        transaction::TransactionError,
+    },
+    std::mem::size_of,
+};
+
+fn publisher(i: u8) -> Pubkey {
+    Pubkey::new_from_array([i; 32])
+}
+
+#[test]
+fn test_publisher_weights_set_weight() {
+    let mut publisher_weights = PublisherWeights::zeroed();
+    assert_eq!(publisher_weights.weight(&publisher(1)), 0);
+
+    // The entries are kept sorted by publisher
+    assert!(publisher_weights.set_weight(&publisher(3), 30));
+    assert!(publisher_weights.set_weight(&publisher(1), 10));
+    assert!(publisher_weights.set_weight(&publisher(2), 20));
+    assert!(publisher_weights.set_weight(&publisher(4), 0));
+    assert_eq!(
+        publisher_weights.entries(),
+        [(1, 10), (2, 20), (3, 30)]
+            .map(|(i, weight)| PublisherWeight {
+                publisher: publisher(i),
+                weight,
+            })
+            .as_slice()
+    );
+    assert_eq!(publisher_weights.weight(&publisher(2)), 20);
+    assert_eq!(publisher_weights.weight(&publisher(4)), 0);
+
+    assert!(publisher_weights.set_weight(&publisher(2), 25));
+    assert_eq!(publisher_weights.weight(&publisher(2)), 25);
+
+    // A weight of 0 removes the entry
+    assert!(publisher_weights.set_weight(&publisher(1), 0));
+    assert_eq!(publisher_weights.num_weights, 2);
+    assert_eq!(publisher_weights.weight(&publisher(1)), 0);
+    assert_eq!(publisher_weights.weight(&publisher(3)), 30);
+    assert_eq!(publisher_weights.weights[2], PublisherWeight::zeroed());
+
+    // Once full, only the existing entries can be updated
+    for i in 0..(PC_NUM_COMP - 2) as u8 {
+        assert!(publisher_weights.set_weight(&publisher(100 + i), 1));
+    }
+    assert_eq!(publisher_weights.num_weights, u64::from(PC_NUM_COMP));
+    assert!(!publisher_weights.set_weight(&publisher(1), 10));
+    assert!(publisher_weights.set_weight(&publisher(3), 10));
+    assert!(publisher_weights.set_weight(&publisher(3), 0));
+    assert!(publisher_weights.set_weight(&publisher(1), 10));
+}
+
+#[tokio::test]
+async fn test_publisher_weights() {
+    let mut sim = PythSimulator::new().await;
+    let PriceFixture {
+        program_id,
+        master_authority,
+        price,
+        publishers,
+        outsider,
+        ..
+    } = sim.setup_price_fixture(3).await;
+    sim.process_ix_as(
+        builders::set_min_pub(&program_id, &master_authority.pubkey(), &price, 1),
+        &master_authority,
+    )
+    .await
+    .unwrap();
+
+    // Only the authorities can set the weights
+    sim.assert_permission_violation(
+        builders::set_publisher_weight(
+            &program_id,
+            &outsider.pubkey(),
+            &price,
+            &publishers[0].pubkey(),
+            1,
+        ),
+        &outsider,
+    )
+    .await;
+
+    assert_eq!(
+        sim.process_ix_as(
+            builders::set_publisher_weight(
+                &program_id,
+                &master_authority.pubkey(),
+                &price,
+                &Pubkey::default(),
+                1
+            ),
+            &master_authority,
+        )
+        .await
+        .unwrap_err()
+        .unwrap(),
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument)
+    );
+
+    // The stake-weighted aggregation can't be enabled without weights
+    assert_eq!(
+        sim.process_ix_as(
+            builders::set_price_flags(
+                &program_id,
+                &master_authority.pubkey(),
+                &price,
+                PriceAccountFlags::STAKE_WEIGHTED,
+                PriceAccountFlags::empty(),
+            ),
+            &master_authority,
+        )
+        .await
+        .unwrap_err()
+        .unwrap(),
+        OracleError::InvalidPriceAccountFlags.into()
+    );
+
+    // Setting a weight resizes the price account
+    for (publisher, weight) in publishers.iter().zip([1, 1, 10]) {
+        sim.process_ix_as(
+            builders::set_publisher_weight(
+                &program_id,
+                &master_authority.pubkey(),
+                &price,
+                &publisher.pubkey(),
+                weight,
+            ),
+            &master_authority,
+        )
+        .await
+        .unwrap();
+    }
+    let price_account = sim
+        .get_resized_account(
+            price,
+            PriceAccount::EXTENSIONS_HEADER_SPACE + size_of::<PublisherWeights>(),
+        )
+        .await;
+    let publisher_weights = PriceAccount::publisher_weights(&price_account.data).unwrap();
+    assert_eq!(publisher_weights.num_weights, 3);
+    assert_eq!(publisher_weights.weight(&publishers[2].pubkey()), 10);
+
+    sim.process_ix_as(
+        builders::set_price_flags(
+            &program_id,
+            &master_authority.pubkey(),
+            &price,
+            PriceAccountFlags::STAKE_WEIGHTED,
+            PriceAccountFlags::empty(),
+        ),
+        &master_authority,
+    )
+    .await
+    .unwrap();
+
+    // Each round aggregates the quotes of the previous one: the heaviest publisher prevails, until
+    // its weight is removed and then until the stake-weighted aggregation is disabled
+    let expected_aggregates = [
+        (None, 0),
+        (Some((200, 10)), 3),
+        (Some((105, 5)), 2),
+        (Some((110, 80)), 3),
+    ];
+    for (round, (expected_aggregate, expected_num_qt)) in
+        expected_aggregates.into_iter().enumerate()
+    {
+        match round {
+            2 => {
+                sim.process_ix_as(
+                    builders::set_publisher_weight(
+                        &program_id,
+                        &master_authority.pubkey(),
+                        &price,
+                        &publishers[2].pubkey(),
+                        0,
+                    ),
+                    &master_authority,
+                )
+                .await
+                .unwrap();
+            }
+            3 => {
+                sim.process_ix_as(
+                    builders::set_price_flags(
+                        &program_id,
+                        &master_authority.pubkey(),
+                        &price,
+                        PriceAccountFlags::empty(),
+                        PriceAccountFlags::STAKE_WEIGHTED,
+                    ),
+                    &master_authority,
+                )
+                .await
+                .unwrap();
+            }
+            _ => {}
+        }
+
+        sim.warp_to_slot(10 * (round as u64 + 1)).await.unwrap();
+        for (publisher, price_value) in publishers.iter().zip([100, 110, 200]) {
+            sim.upd_price(
+                publisher,
+                price,
+                Quote {
+                    price:      price_value,
+                    confidence: 10,
+                    status:     PC_STATUS_TRADING,
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        let price_data = sim
+            .get_account_data_as::<PriceAccount>(price)
+            .await
+            .unwrap();
+        assert_eq!(price_data.num_qt_, expected_num_qt);
+        if let Some((expected_price, expected_conf)) = expected_aggregate {
+            assert_eq!(price_data.agg_.status_, PC_STATUS_TRADING);
+            assert_eq!(price_data.agg_.price_, expected_price);
+            assert_eq!(price_data.agg_.conf_, expected_conf);
+        }
+    }
+}
@@ -0,0 +1,408 @@
+use {
+    crate::{
+        accounts::PriceAccount,
+        c_oracle_header::{
+            PC_STATUS_TRADING,
+            PC_STATUS_UNKNOWN,
+        },
+        error::OracleError,
+        instruction::builders,
+        tests::pyth_simulator::{
+            copy_keypair,
+            PriceFixture,
+            PythSimulator,
+            Quote,
+        },
+    },
+    solana_program::{
+        native_token::LAMPORTS_PER_SOL,
+        pubkey::Pubkey,
+    },
+    solana_sdk::{
+        instruction::InstructionError,
+        signature::Keypair,
+        signer::Signer,
+        transaction::TransactionError,
+    },
+};
+
+#[tokio::test]
+async fn test_rotate_publisher() {
+    let mut sim = PythSimulator::new().await;
+    let program_id = sim.program_id;
+    let master_authority = copy_keypair(&sim.genesis_keypair);
+
+    let mapping_keypair = sim.init_mapping().await.unwrap();
+    let product_keypair = sim.add_product(&mapping_keypair).await.unwrap();
+    let mut price_accounts = vec![];
+    for _ in 0..2 {
+        let price_keypair = sim.add_price(&product_keypair, -8).await.unwrap();
+        price_accounts.push(price_keypair);
+    }
+    let prices: Vec<Pubkey> = price_accounts.iter().map(Keypair::pubkey).collect();
+
+    let old_publisher = Keypair::new();
+    let new_publisher = Keypair::new();
+    let other_publisher = Keypair::new();
+    let outsider = Keypair::new();
+    for keypair in [&old_publisher, &new_publisher, &other_publisher, &outsider] {
+        sim.airdrop(&keypair.pubkey(), LAMPORTS_PER_SOL)
+            .await
+            .unwrap();
+    }
+    for price_keypair in price_accounts.iter() {
+        for publisher in [&old_publisher, &other_publisher] {
+            sim.add_publisher(price_keypair, publisher.pubkey())
+                .await
+                .unwrap();
+        }
+    }
+    sim.process_ix_as(
+        builders::set_publisher_weight(
+            &program_id,
+            &master_authority.pubkey(),
+            &prices[0],
+            &old_publisher.pubkey(),
+            10,
+        ),
+        &master_authority,
+    )
+    .await
+    .unwrap();
+
+    sim.warp_to_slot(10).await.unwrap();
+    for price in prices.iter() {
+        sim.upd_price(
+            &old_publisher,
+            *price,
+            Quote {
+                price:      100,
+                confidence: 10,
+                status:     PC_STATUS_TRADING,
+            },
+        )
+        .await
+        .unwrap();
+    }
+
+    // Neither an outsider nor a single key of the publisher can rotate it
+    assert_eq!(
+        sim.process_ix_as(
+            builders::rotate_publisher(
+                &program_id,
+                &outsider.pubkey(),
+                &old_publisher.pubkey(),
+                &new_publisher.pubkey(),
+                &prices,
+                false,
+            ),
+            &outsider,
+        )
+        .await
+        .unwrap_err()
+        .unwrap(),
+        OracleError::PermissionViolation.into()
+    );
+    assert_eq!(
+        sim.process_ix_as(
+            builders::rotate_publisher(
+                &program_id,
+                &old_publisher.pubkey(),
+                &old_publisher.pubkey(),
+                &new_publisher.pubkey(),
+                &prices,
+                false,
+            ),
+            &old_publisher,
+        )
+        .await
+        .unwrap_err()
+        .unwrap(),
+        OracleError::PermissionViolation.into()
+    );
+
+    // Both keys can
+    sim.process_ix_signed_by(
+        builders::rotate_publisher(
+            &program_id,
+            &old_publisher.pubkey(),
+            &old_publisher.pubkey(),
+            &new_publisher.pubkey(),
+            &prices,
+            true,
+        ),
+        &vec![&old_publisher, &new_publisher],
+    )
+    .await
+    .unwrap();
+    for price in prices.iter() {
+        let price_data = sim
+            .get_account_data_as::<PriceAccount>(*price)
+            .await
+            .unwrap();
+        let comps = &price_data.comp_[..price_data.num_ as usize];
+        assert_eq!(comps.len(), 2);
+        assert!(comps.windows(2).all(|pair| pair[0].pub_ < pair[1].pub_));
+        assert!(!comps.iter().any(|comp| comp.pub_ == old_publisher.pubkey()));
+        let comp = comps
+            .iter()
+            .find(|comp| comp.pub_ == new_publisher.pubkey())
+            .unwrap();
+        assert_eq!(comp.latest_.price_, 100);
+        assert_eq!(comp.latest_.pub_slot_, 10);
+    }
+    let price_account = sim.get_account(prices[0]).await.unwrap();
+    let publisher_weights = PriceAccount::publisher_weights(&price_account.data).unwrap();
+    assert_eq!(publisher_weights.weight(&old_publisher.pubkey()), 0);
+    assert_eq!(publisher_weights.weight(&new_publisher.pubkey()), 10);
+
+    // The new key publishes in place of the old one
+    sim.warp_to_slot(20).await.unwrap();
+    for (publisher, price_value) in [(&old_publisher, 200), (&new_publisher, 150)] {
+        sim.upd_price(
+            publisher,
+            prices[1],
+            Quote {
+                price:      price_value,
+                confidence: 10,
+                status:     PC_STATUS_TRADING,
+            },
+        )
+        .await
+        .unwrap();
+    }
+    let price_data = sim
+        .get_account_data_as::<PriceAccount>(prices[1])
+        .await
+        .unwrap();
+    let comp = price_data
+        .comp_
+        .iter()
+        .find(|comp| comp.pub_ == new_publisher.pubkey())
+        .unwrap();
+    assert_eq!(comp.latest_.price_, 150);
+
+    // The new key must not be a publisher already, and the old one must be
+    for (old_key, new_key) in [
+        (new_publisher.pubkey(), other_publisher.pubkey()),
+        (old_publisher.pubkey(), Pubkey::new_unique()),
+    ] {
+        assert_eq!(
+            sim.process_ix_as(
+                builders::rotate_publisher(
+                    &program_id,
+                    &master_authority.pubkey(),
+                    &old_key,
+                    &new_key,
+                    &prices,
+                    false,
+                ),
+                &master_authority,
+            )
+            .await
+            .unwrap_err()
+            .unwrap(),
+            TransactionError::InstructionError(0, InstructionError::InvalidArgument)
+        );
+    }
+
+    // The security authority doesn't need the signatures of the publishers
+    sim.process_ix_as(
+        builders::rotate_publisher(
+            &program_id,
+            &master_authority.pubkey(),
+            &new_publisher.pubkey(),
+            &old_publisher.pubkey(),
+            &prices[..1],
+            false,
+        ),
+        &master_authority,
+    )
+    .await
+    .unwrap();
+    let price_data = sim
+        .get_account_data_as::<PriceAccount>(prices[0])
+        .await
+        .unwrap();
+    assert!(price_data.comp_[..2]
+        .iter()
+        .any(|comp| comp.pub_ == old_publisher.pubkey()));
+
+    // Neither a blocked key nor the key replacing it can take part in a rotation
+    let blocked_publisher = Keypair::new();
+    sim.airdrop(&blocked_publisher.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    for publisher in [&blocked_publisher, &other_publisher] {
+        sim.process_ix_as(
+            builders::block_publisher(&program_id, &master_authority.pubkey(), &publisher.pubkey()),
+            &master_authority,
+        )
+        .await
+        .unwrap();
+    }
+    assert_eq!(
+        sim.process_ix_signed_by(
+            builders::rotate_publisher(
+                &program_id,
+                &old_publisher.pubkey(),
+                &old_publisher.pubkey(),
+                &blocked_publisher.pubkey(),
+                &prices[..1],
+                true,
+            ),
+            &vec![&old_publisher, &blocked_publisher],
+        )
+        .await
+        .unwrap_err()
+        .unwrap(),
+        OracleError::PermissionViolation.into()
+    );
+    assert_eq!(
+        sim.process_ix_as(
+            builders::rotate_publisher(
+                &program_id,
+                &master_authority.pubkey(),
+                &other_publisher.pubkey(),
+                &Pubkey::new_unique(),
+                &prices,
+                false,
+            ),
+            &master_authority,
+        )
+        .await
+        .unwrap_err()
+        .unwrap(),
+        OracleError::PermissionViolation.into()
+    );
+
+    // The additional signers of the multisig authority follow the price accounts
+    let signers = [Keypair::new(), Keypair::new()];
+    for signer in signers.iter() {
+        sim.airdrop(&signer.pubkey(), LAMPORTS_PER_SOL)
+            .await
+            .unwrap();
+    }
+    let signer_keys: Vec<Pubkey> = signers.iter().map(Keypair::pubkey).collect();
+    sim.set_multisig_authority(2, &signer_keys).await.unwrap();
+    let rotated_publisher = Pubkey::new_unique();
+    let rotate_publisher = builders::rotate_publisher(
+        &program_id,
+        &signers[0].pubkey(),
+        &old_publisher.pubkey(),
+        &rotated_publisher,
+        &prices[..1],
+        false,
+    );
+    assert_eq!(
+        sim.process_ix_as(rotate_publisher.clone(), &signers[0])
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::PermissionViolation.into()
+    );
+    sim.process_ix_signed_by(
+        builders::with_additional_signers(rotate_publisher, &signer_keys[1..]),
+        &vec![&signers[0], &signers[1]],
+    )
+    .await
+    .unwrap();
+    let price_data = sim
+        .get_account_data_as::<PriceAccount>(prices[0])
+        .await
+        .unwrap();
+    assert!(price_data.comp_[..2]
+        .iter()
+        .any(|comp| comp.pub_ == rotated_publisher));
+}
+
+#[tokio::test]
+async fn test_rotate_publisher_diagnostics() {
+    let mut sim = PythSimulator::new().await;
+    let PriceFixture {
+        program_id,
+        master_authority,
+        price,
+        publishers,
+        ..
+    } = sim.setup_price_fixture(3).await;
+    for instruction in [
+        builders::set_min_pub(&program_id, &master_authority.pubkey(), &price, 1),
+        builders::init_aggregation_diagnostics(&program_id, &master_authority.pubkey(), &price),
+        builders::init_publisher_stats(&program_id, &master_authority.pubkey(), &price),
+    ] {
+        sim.process_ix_as(instruction, &master_authority)
+            .await
+            .unwrap();
+    }
+
+    // The first publisher in `comp_` is left out of the aggregation
+    let first_publisher = {
+        let price_data = sim
+            .get_account_data_as::<PriceAccount>(price)
+            .await
+            .unwrap();
+        price_data.comp_[0].pub_
+    };
+    for slot in [10, 11] {
+        sim.warp_to_slot(slot).await.unwrap();
+        for publisher in publishers.iter() {
+            let status = if publisher.pubkey() == first_publisher {
+                PC_STATUS_UNKNOWN
+            } else {
+                PC_STATUS_TRADING
+            };
+            sim.upd_price(
+                publisher,
+                price,
+                Quote {
+                    price: 100,
+                    confidence: 10,
+                    status,
+                },
+            )
+            .await
+            .unwrap();
+        }
+    }
+    let price_account = sim.get_account(price).await.unwrap();
+    let reasons = PriceAccount::aggregation_diagnostics(&price_account.data)
+        .unwrap()
+        .reasons;
+
+    // Its new key sorts last, its diagnostics and statistics move along with its component
+    let new_publisher = Pubkey::new_from_array([0xff; 32]);
+    sim.process_ix_as(
+        builders::rotate_publisher(
+            &program_id,
+            &master_authority.pubkey(),
+            &first_publisher,
+            &new_publisher,
+            &[price],
+            false,
+        ),
+        &master_authority,
+    )
+    .await
+    .unwrap();
+    let price_account = sim.get_account(price).await.unwrap();
+    let price_data = sim
+        .get_account_data_as::<PriceAccount>(price)
+        .await
+        .unwrap();
+    assert_eq!(price_data.comp_[2].pub_, new_publisher);
+    let diagnostics = PriceAccount::aggregation_diagnostics(&price_account.data).unwrap();
+    assert_eq!(
+        diagnostics.reasons[..3],
+        [reasons[1], reasons[2], reasons[0]]
+    );
+    let publisher_stats = PriceAccount::publisher_stats(&price_account.data).unwrap();
+    for (comp, entry) in price_data.comp_[..3]
+        .iter()
+        .zip(publisher_stats.entries.iter())
+    {
+        assert_eq!(comp.pub_, entry.publisher);
+    }
+    assert_eq!(publisher_stats.entries[2].num_aggregations, 0);
+    assert_eq!(publisher_stats.entries[0].num_aggregations, 1);
+}
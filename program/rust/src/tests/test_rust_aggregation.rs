@@ -0,0 +1,121 @@
+use {
+    crate::{
+        accounts::PriceAccount,
+        aggregation::{
+            c,
+            rust,
+            EmaParams,
+        },
+        c_oracle_header::{
+            PC_NUM_COMP,
+            PC_STATUS_TRADING,
+            PC_STATUS_UNKNOWN,
+        },
+    },
+    bytemuck::Zeroable,
+    quickcheck_macros::quickcheck,
+};
+
+const START_SLOT: u64 = 1000;
+
+/// A publisher quote: price, confidence, whether it is trading and how many slots old it is.
+type Quote = (i64, u64, bool, u8);
+
+fn price_account_with_quotes(
+    quotes: &[Quote],
+    exponent: i8,
+    min_pub: u8,
+    max_latency: u8,
+) -> PriceAccount {
+    let mut price_account = PriceAccount::zeroed();
+    price_account.exponent = -i32::from(exponent.unsigned_abs() % 13);
+    price_account.min_pub_ = min_pub % 8;
+    price_account.max_latency_ = max_latency;
+    price_account.last_slot_ = START_SLOT;
+    price_account.agg_.pub_slot_ = START_SLOT;
+    price_account.num_ = quotes.len().min(PC_NUM_COMP as usize) as u32;
+    for (comp, (price, conf, is_trading, lag)) in price_account.comp_.iter_mut().zip(quotes) {
+        comp.latest_.price_ = *price;
+        comp.latest_.conf_ = *conf;
+        comp.latest_.status_ = if *is_trading {
+            PC_STATUS_TRADING
+        } else {
+            PC_STATUS_UNKNOWN
+        };
+        comp.latest_.pub_slot_ = START_SLOT - u64::from(*lag);
+    }
+    price_account
+}
+
+/// Run the aggregation (and the EMA update if it succeeds) with both implementations and
+/// check that they produce exactly the same price account.
+fn assert_same_aggregation(
+    price_account: &PriceAccount,
+    slot: u64,
+    timestamp: i64,
+    ema_params: EmaParams,
+) -> PriceAccount {
+    let mut c_price_account = *price_account;
+    let mut rust_price_account = *price_account;
+
+    let c_updated = c::upd_aggregate(&mut c_price_account, slot, timestamp);
+    let rust_updated = rust::upd_aggregate(&mut rust_price_account, slot, timestamp);
+    assert_eq!(c_updated, rust_updated);
+
+    if c_updated {
+        let nslots = (slot as i64) - c_price_account.prev_slot_ as i64;
+        c::upd_twap(&mut c_price_account, nslots, ema_params);
+        rust::upd_twap(&mut rust_price_account, nslots, ema_params);
+    }
+
+    assert_eq!(
+        bytemuck::bytes_of(&c_price_account),
+        bytemuck::bytes_of(&rust_price_account)
+    );
+    rust_price_account
+}
+
+#[quickcheck]
+fn test_upd_aggregate_matches_c(quotes: Vec<Quote>, exponent: i8, min_pub: u8, max_latency: u8) {
+    let price_account = price_account_with_quotes(&quotes, exponent, min_pub, max_latency);
+    assert_same_aggregation(&price_account, START_SLOT + 1, 1234, EmaParams::default());
+}
+
+// Same as above with prices and confidences in realistic ranges, so that most quotes are valid.
+#[quickcheck]
+fn test_upd_aggregate_realistic_quotes_matches_c(
+    quotes: Vec<(i32, u16, u8)>,
+    exponent: i8,
+    min_pub: u8,
+) {
+    let quotes: Vec<Quote> = quotes
+        .into_iter()
+        .map(|(price, conf, lag)| (i64::from(price), u64::from(conf), true, lag % 32))
+        .collect();
+    let price_account = price_account_with_quotes(&quotes, exponent, min_pub, 0);
+    assert_same_aggregation(&price_account, START_SLOT + 1, 1234, EmaParams::default());
+}
+
+// Run several aggregations in a row so that the EMAs are updated from non-trivial states, with
+// any EMA half-life.
+#[quickcheck]
+fn test_aggregation_sequence_matches_c(
+    rounds: Vec<(Vec<(i32, u16)>, u16)>,
+    exponent: i8,
+    ema_half_life: u16,
+) {
+    let ema_params = EmaParams::from_half_life(u64::from(ema_half_life));
+    let mut price_account = price_account_with_quotes(&[], exponent, 0, 0);
+    let mut slot = START_SLOT;
+    for (round, (quotes, slot_gap)) in rounds.into_iter().enumerate() {
+        slot += u64::from(slot_gap) + 1;
+        price_account.num_ = quotes.len().min(PC_NUM_COMP as usize) as u32;
+        for (comp, (price, conf)) in price_account.comp_.iter_mut().zip(quotes) {
+            comp.latest_.price_ = i64::from(price);
+            comp.latest_.conf_ = u64::from(conf);
+            comp.latest_.status_ = PC_STATUS_TRADING;
+            comp.latest_.pub_slot_ = slot - 1;
+        }
+        price_account = assert_same_aggregation(&price_account, slot, round as i64, ema_params);
+    }
+}
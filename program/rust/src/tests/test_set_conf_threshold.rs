@@ -0,0 +1,158 @@
+use {
+    crate::{
+        accounts::PriceAccount,
+        c_oracle_header::{
+            PC_STATUS_IGNORED,
+            PC_STATUS_TRADING,
+        },
+        instruction::{
+            builders,
+            PriceUpdate,
+        },
+        tests::pyth_simulator::{
+            PriceFixture,
+            PythSimulator,
+            Quote,
+        },
+    },
+    solana_program::pubkey::Pubkey,
+    solana_sdk::{
+        instruction::InstructionError,
+        signer::Signer,
+        transaction::TransactionError,
+    },
+    std::mem::size_of,
+};
+
+#[tokio::test]
+async fn test_set_conf_threshold() {
+    let mut sim = PythSimulator::new().await;
+    let PriceFixture {
+        program_id,
+        master_authority,
+        price,
+        publishers,
+        outsider,
+        ..
+    } = sim.setup_price_fixture(1).await;
+    let publisher = &publishers[0];
+
+    // A confidence of 20% of the price is below the default threshold of a third of the price
+    let quote = || Quote {
+        price:      100,
+        confidence: 20,
+        status:     PC_STATUS_TRADING,
+    };
+    sim.upd_price(publisher, price, quote()).await.unwrap();
+    assert_eq!(
+        get_publisher_status(&mut sim, price).await,
+        PC_STATUS_TRADING
+    );
+
+    // Only the authorities can set the threshold
+    sim.assert_permission_violation(
+        builders::set_conf_threshold(&program_id, &outsider.pubkey(), &price, 10),
+        &outsider,
+    )
+    .await;
+
+    // The divisor must fit in an i64
+    assert_eq!(
+        sim.process_ix_as(
+            builders::set_conf_threshold(&program_id, &master_authority.pubkey(), &price, u64::MAX),
+            &master_authority,
+        )
+        .await
+        .unwrap_err()
+        .unwrap(),
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument)
+    );
+
+    // Setting the divisor resizes the price account
+    sim.process_ix_as(
+        builders::set_conf_threshold(&program_id, &master_authority.pubkey(), &price, 10),
+        &master_authority,
+    )
+    .await
+    .unwrap();
+    sim.get_resized_account(
+        price,
+        PriceAccount::EXTENSIONS_HEADER_SPACE + size_of::<u64>(),
+    )
+    .await;
+
+    // With a tenth of the price as threshold, the same quote is ignored
+    sim.warp_to_slot(100).await.unwrap();
+    sim.upd_price(publisher, price, quote()).await.unwrap();
+    assert_eq!(
+        get_publisher_status(&mut sim, price).await,
+        PC_STATUS_IGNORED
+    );
+
+    // Batch updates use the threshold of each price account as well
+    sim.warp_to_slot(200).await.unwrap();
+    let update = |confidence, publishing_slot| PriceUpdate {
+        status: PC_STATUS_TRADING,
+        corp_act_status: 0,
+        price: 100,
+        confidence,
+        publishing_slot,
+    };
+    sim.process_ix_as(
+        builders::upd_price_batch(
+            &program_id,
+            &publisher.pubkey(),
+            &[(price, update(10, 200))],
+        ),
+        publisher,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        get_publisher_status(&mut sim, price).await,
+        PC_STATUS_TRADING
+    );
+
+    sim.warp_to_slot(300).await.unwrap();
+    sim.process_ix_as(
+        builders::upd_price_batch(
+            &program_id,
+            &publisher.pubkey(),
+            &[(price, update(11, 300))],
+        ),
+        publisher,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        get_publisher_status(&mut sim, price).await,
+        PC_STATUS_IGNORED
+    );
+
+    // 0 restores the default threshold without shrinking the account
+    sim.process_ix_as(
+        builders::set_conf_threshold(&program_id, &master_authority.pubkey(), &price, 0),
+        &master_authority,
+    )
+    .await
+    .unwrap();
+    sim.warp_to_slot(400).await.unwrap();
+    sim.upd_price(publisher, price, quote()).await.unwrap();
+    assert_eq!(
+        get_publisher_status(&mut sim, price).await,
+        PC_STATUS_TRADING
+    );
+    assert_eq!(
+        sim.get_account(price).await.unwrap().data.len(),
+        PriceAccount::EXTENSIONS_HEADER_SPACE + size_of::<u64>()
+    );
+}
+
+async fn get_publisher_status(sim: &mut PythSimulator, price: Pubkey) -> u32 {
+    sim.get_account_data_as::<PriceAccount>(price)
+        .await
+        .unwrap()
+        .comp_[0]
+        .latest_
+        .status_
+}
@@ -0,0 +1,116 @@
+use {
+    crate::{
+        accounts::{
+            PriceAccount,
+            PriceExtensionFlags,
+            PythAccount,
+        },
+        aggregation::MAX_EMA_HALF_LIFE,
+        instruction::builders,
+        tests::pyth_simulator::{
+            PriceFixture,
+            PythSimulator,
+        },
+    },
+    solana_sdk::{
+        instruction::InstructionError,
+        signer::Signer,
+        transaction::TransactionError,
+    },
+    std::mem::size_of,
+};
+
+#[tokio::test]
+async fn test_set_ema_half_life() {
+    let mut sim = PythSimulator::new().await;
+    let PriceFixture {
+        program_id,
+        master_authority,
+        price,
+        outsider,
+        ..
+    } = sim.setup_price_fixture(0).await;
+
+    // Only the authorities can set the half-life
+    sim.assert_permission_violation(
+        builders::set_ema_half_life(&program_id, &outsider.pubkey(), &price, 100),
+        &outsider,
+    )
+    .await;
+
+    assert_eq!(
+        sim.process_ix_as(
+            builders::set_ema_half_life(
+                &program_id,
+                &master_authority.pubkey(),
+                &price,
+                MAX_EMA_HALF_LIFE + 1
+            ),
+            &master_authority,
+        )
+        .await
+        .unwrap_err()
+        .unwrap(),
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument)
+    );
+
+    // Setting the half-life resizes the price account, without setting a confidence divisor
+    sim.process_ix_as(
+        builders::set_ema_half_life(&program_id, &master_authority.pubkey(), &price, 100),
+        &master_authority,
+    )
+    .await
+    .unwrap();
+    let price_account = sim
+        .get_resized_account(
+            price,
+            PriceAccount::EXTENSIONS_HEADER_SPACE + size_of::<u64>(),
+        )
+        .await;
+    assert_eq!(PriceAccount::ema_half_life(&price_account.data), 100);
+    let extensions_header = PriceAccount::extensions_header(&price_account.data).unwrap();
+    assert_eq!(
+        extensions_header.enabled,
+        PriceExtensionFlags::EMA_HALF_LIFE
+    );
+    assert_eq!(
+        extensions_header.offset(PriceExtensionFlags::EMA_HALF_LIFE),
+        Some(PriceAccount::EXTENSIONS_HEADER_SPACE)
+    );
+    assert_eq!(
+        extensions_header.offset(PriceExtensionFlags::CONF_DIVISOR),
+        None
+    );
+
+    // 0 restores the default half-life
+    sim.process_ix_as(
+        builders::set_ema_half_life(&program_id, &master_authority.pubkey(), &price, 0),
+        &master_authority,
+    )
+    .await
+    .unwrap();
+    let price_account = sim.get_account(price).await.unwrap();
+    assert_eq!(PriceAccount::ema_half_life(&price_account.data), 0);
+
+    // The confidence divisor, configured later, is stored after the half-life and only grows the
+    // account by its own size
+    sim.process_ix_as(
+        builders::set_conf_threshold(&program_id, &master_authority.pubkey(), &price, 10),
+        &master_authority,
+    )
+    .await
+    .unwrap();
+    let price_account = sim
+        .get_resized_account(
+            price,
+            PriceAccount::EXTENSIONS_HEADER_SPACE + 2 * size_of::<u64>(),
+        )
+        .await;
+    assert_eq!(
+        PriceAccount::extensions_header(&price_account.data)
+            .unwrap()
+            .offset(PriceExtensionFlags::CONF_DIVISOR),
+        Some(PriceAccount::EXTENSIONS_HEADER_SPACE + size_of::<u64>())
+    );
+    assert_eq!(PriceAccount::ema_half_life(&price_account.data), 0);
+}
@@ -0,0 +1,211 @@
+use {
+    crate::{
+        accounts::{
+            PermissionAccount,
+            PriceAccount,
+            PriceAccountFlags,
+            PythAccount,
+        },
+        c_oracle_header::PC_VERSION,
+        deserialize::load_checked,
+        error::OracleError,
+        instruction::{
+            AddPublisherArgs,
+            OracleCommand,
+            SetPriceFlagsArgs,
+        },
+        processor::{
+            add_publisher,
+            set_price_flags,
+        },
+        tests::test_utils::AccountSetup,
+    },
+    bytemuck::bytes_of,
+    solana_program::{
+        account_info::AccountInfo,
+        pubkey::Pubkey,
+    },
+};
+
+#[test]
+fn test_set_price_flags() {
+    let program_id = Pubkey::new_unique();
+
+    let mut funding_setup = AccountSetup::new_funding();
+    let funding_account = funding_setup.as_account_info();
+
+    let mut attacker_setup = AccountSetup::new_funding();
+    let attacker_account = attacker_setup.as_account_info();
+
+    let mut price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+    let price_account = price_setup.as_account_info();
+    PriceAccount::initialize(&price_account, PC_VERSION).unwrap();
+
+    let mut permissions_setup = AccountSetup::new_permission(&program_id);
+    let permissions_account = permissions_setup.as_account_info();
+
+    {
+        let mut permissions_account_data =
+            PermissionAccount::initialize(&permissions_account, PC_VERSION).unwrap();
+        permissions_account_data.master_authority = *funding_account.key;
+        permissions_account_data.data_curation_authority = *funding_account.key;
+        permissions_account_data.security_authority = *funding_account.key;
+    }
+
+    let accounts = [
+        funding_account.clone(),
+        price_account.clone(),
+        permissions_account.clone(),
+    ];
+
+    assert_eq!(get_flags(&price_account), PriceAccountFlags::empty().bits());
+
+    // Enable the V2 accumulator
+    assert!(set_price_flags(
+        &program_id,
+        &accounts,
+        &instruction_data(
+            PriceAccountFlags::ACCUMULATOR_V2.bits(),
+            PriceAccountFlags::empty().bits()
+        )
+    )
+    .is_ok());
+    assert_eq!(
+        get_flags(&price_account),
+        PriceAccountFlags::ACCUMULATOR_V2.bits()
+    );
+
+    // Disabling the V2 accumulator also resets the message buffer flag, which is set by the program
+    load_checked::<PriceAccount>(&price_account, PC_VERSION)
+        .unwrap()
+        .flags
+        .insert(PriceAccountFlags::MESSAGE_BUFFER_CLEARED);
+    assert!(set_price_flags(
+        &program_id,
+        &accounts,
+        &instruction_data(
+            PriceAccountFlags::empty().bits(),
+            PriceAccountFlags::ACCUMULATOR_V2.bits()
+        )
+    )
+    .is_ok());
+    assert_eq!(get_flags(&price_account), PriceAccountFlags::empty().bits());
+
+    // The stake-weighted aggregation requires publisher weights, but can always be disabled
+    assert_eq!(
+        set_price_flags(
+            &program_id,
+            &accounts,
+            &instruction_data(PriceAccountFlags::STAKE_WEIGHTED.bits(), 0)
+        ),
+        Err(OracleError::InvalidPriceAccountFlags.into())
+    );
+    assert!(set_price_flags(
+        &program_id,
+        &accounts,
+        &instruction_data(0, PriceAccountFlags::STAKE_WEIGHTED.bits())
+    )
+    .is_ok());
+
+    // The corporate action statuses can be toggled
+    for (set_flags, clear_flags, expected_flags) in [
+        (
+            PriceAccountFlags::CORP_ACT_STATUS | PriceAccountFlags::CORP_ACT_ANY,
+            PriceAccountFlags::empty(),
+            PriceAccountFlags::CORP_ACT_STATUS | PriceAccountFlags::CORP_ACT_ANY,
+        ),
+        (
+            PriceAccountFlags::empty(),
+            PriceAccountFlags::CORP_ACT_STATUS | PriceAccountFlags::CORP_ACT_ANY,
+            PriceAccountFlags::empty(),
+        ),
+    ] {
+        assert!(set_price_flags(
+            &program_id,
+            &accounts,
+            &instruction_data(set_flags.bits(), clear_flags.bits())
+        )
+        .is_ok());
+        assert_eq!(get_flags(&price_account), expected_flags.bits());
+    }
+
+    // Only the program may set or clear the message buffer flag
+    for (set_flags, clear_flags) in [
+        (PriceAccountFlags::MESSAGE_BUFFER_CLEARED.bits(), 0),
+        (0, PriceAccountFlags::MESSAGE_BUFFER_CLEARED.bits()),
+    ] {
+        assert_eq!(
+            set_price_flags(
+                &program_id,
+                &accounts,
+                &instruction_data(set_flags, clear_flags)
+            ),
+            Err(OracleError::InvalidPriceAccountFlags.into())
+        );
+    }
+
+    // Unknown flags are rejected
+    assert_eq!(
+        set_price_flags(&program_id, &accounts, &instruction_data(0b10_0000, 0)),
+        Err(OracleError::InvalidPriceAccountFlags.into())
+    );
+
+    // A flag can't be both set and cleared
+    assert_eq!(
+        set_price_flags(
+            &program_id,
+            &accounts,
+            &instruction_data(
+                PriceAccountFlags::ACCUMULATOR_V2.bits(),
+                PriceAccountFlags::ACCUMULATOR_V2.bits()
+            )
+        ),
+        Err(OracleError::InvalidPriceAccountFlags.into())
+    );
+
+    // Unauthorized keys can't change the flags
+    assert_eq!(
+        set_price_flags(
+            &program_id,
+            &[
+                attacker_account.clone(),
+                price_account.clone(),
+                permissions_account.clone()
+            ],
+            &instruction_data(PriceAccountFlags::ACCUMULATOR_V2.bits(), 0)
+        ),
+        Err(OracleError::PermissionViolation.into())
+    );
+    assert_eq!(get_flags(&price_account), PriceAccountFlags::empty().bits());
+
+    // Adding the publisher that used to toggle the V2 accumulator just adds a publisher
+    let mut magic_publisher = [0u8; 32];
+    magic_publisher[31] = 1;
+    let args = AddPublisherArgs {
+        header:    OracleCommand::AddPublisher.into(),
+        publisher: Pubkey::from(magic_publisher),
+    };
+    assert!(add_publisher(&program_id, &accounts, bytes_of(&args)).is_ok());
+    {
+        let price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        assert_eq!(price_data.num_, 1);
+        assert!(price_data.flags.is_empty());
+    }
+}
+
+fn instruction_data(set_flags: u8, clear_flags: u8) -> Vec<u8> {
+    let args = SetPriceFlagsArgs {
+        header: OracleCommand::SetPriceFlags.into(),
+        set_flags,
+        clear_flags,
+        unused_: [0; 2],
+    };
+    bytes_of(&args).to_vec()
+}
+
+fn get_flags(account: &AccountInfo) -> u8 {
+    load_checked::<PriceAccount>(account, PC_VERSION)
+        .unwrap()
+        .flags
+        .bits()
+}
@@ -2,14 +2,33 @@ use {
     crate::{
         accounts::{
             AccountHeader,
+            AggregateConfidence,
+            AggregationDiagnostics,
+            CircuitBreaker,
+            EmaHorizons,
             MappingAccount,
+            MultisigAuthority,
+            OutlierFilter,
+            PendingAuthorities,
             PermissionAccount,
             PriceAccount,
+            PriceBand,
             PriceComponent,
             PriceEma,
+            PriceExtensionsHeader,
+            PriceHistory,
+            PriceHistoryEntry,
             PriceInfo,
             ProductAccount,
+            PublisherBlocklistAccount,
+            PublisherManagerAccount,
+            PublisherStat,
+            PublisherStats,
+            PublisherWeight,
+            PublisherWeights,
             PythAccount,
+            TradingInterval,
+            TradingSchedule,
         },
         c_oracle_header::{
             PC_MAP_TABLE_SIZE,
@@ -91,6 +110,25 @@ fn test_sizes() {
     assert_eq!(size_of::<PriceComponent>(), 96);
     assert_eq!(size_of::<PriceEma>(), 24);
     assert_eq!(size_of::<PermissionAccount>(), 112);
+    assert_eq!(size_of::<MultisigAuthority>(), 324);
+    assert_eq!(size_of::<PendingAuthorities>(), 120);
+    assert_eq!(size_of::<PublisherManagerAccount>(), 1112);
+    assert_eq!(size_of::<PublisherBlocklistAccount>(), 2072);
+    assert_eq!(size_of::<PriceExtensionsHeader>(), 144);
+    assert_eq!(size_of::<EmaHorizons>(), 168);
+    assert_eq!(size_of::<PriceHistoryEntry>(), 40);
+    assert_eq!(size_of::<PriceHistory>(), 2568);
+    assert_eq!(size_of::<PublisherWeight>(), 40);
+    assert_eq!(size_of::<PublisherWeights>(), 2568);
+    assert_eq!(size_of::<OutlierFilter>(), 16);
+    assert_eq!(size_of::<AggregationDiagnostics>(), 72);
+    assert_eq!(size_of::<PublisherStat>(), 56);
+    assert_eq!(size_of::<PublisherStats>(), 3584);
+    assert_eq!(size_of::<TradingInterval>(), 4);
+    assert_eq!(size_of::<TradingSchedule>(), 128);
+    assert_eq!(size_of::<AggregateConfidence>(), 24);
+    assert_eq!(size_of::<CircuitBreaker>(), 32);
+    assert_eq!(size_of::<PriceBand>(), 16);
 }
 
 #[test]
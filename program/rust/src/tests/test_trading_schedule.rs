@@ -0,0 +1,354 @@
+use {
+    crate::{
+        accounts::{
+            AggregationExtensions,
+            PriceAccount,
+            PriceInfo,
+            ProductAccount,
+            TradingInterval,
+            TradingSchedule,
+            MAX_TRADING_INTERVALS,
+        },
+        aggregation::{
+            update_aggregate,
+            EmaParams,
+        },
+        c_oracle_header::{
+            PC_STATUS_HALTED,
+            PC_STATUS_TRADING,
+            PC_STATUS_UNKNOWN,
+        },
+        instruction::builders,
+        tests::pyth_simulator::{
+            PriceFixture,
+            PythSimulator,
+            Quote,
+        },
+    },
+    bytemuck::Zeroable,
+    solana_program::{
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    solana_sdk::{
+        instruction::InstructionError,
+        signer::Signer,
+        transaction::TransactionError,
+    },
+};
+
+/// 2024-01-01 00:00 UTC, a Monday
+const MONDAY: i64 = 1_704_067_200;
+const HOUR: i64 = 3600;
+const DAY: i64 = 24 * HOUR;
+const NYSE: &[u8] = b"-0500;0930-1600,0930-1600,0930-1600,0930-1600,0930-1600,C,C";
+
+#[test]
+fn test_trading_schedule_parse() {
+    let interval = |start, end| TradingInterval { start, end };
+    let trading_schedule =
+        TradingSchedule::parse(b"-0130;0930-1200&1300-2400,O,C,C,C,C,C").unwrap();
+    assert!(trading_schedule.is_enabled());
+    assert_eq!(trading_schedule.utc_offset, -90);
+    assert_eq!(trading_schedule.num_intervals, 3);
+    assert_eq!(
+        trading_schedule.intervals[..3],
+        [
+            interval(570, 720),
+            interval(780, 1440),
+            interval(1440, 2880)
+        ]
+    );
+    assert_eq!(
+        trading_schedule.intervals[3..],
+        [TradingInterval::zeroed(); MAX_TRADING_INTERVALS - 3]
+    );
+
+    // An empty schedule disables it
+    assert_eq!(TradingSchedule::parse(b""), Ok(TradingSchedule::zeroed()));
+
+    // An always closed schedule is still enabled
+    let trading_schedule = TradingSchedule::parse(b"+0000;C,C,C,C,C,C,C").unwrap();
+    assert!(trading_schedule.is_enabled());
+    assert_eq!(trading_schedule.num_intervals, 0);
+
+    let four_intervals = "0000-0100&0200-0300&0400-0500&0600-0700";
+    let max_intervals = format!("+0000;{}", [four_intervals; 7].join(","));
+    assert_eq!(
+        TradingSchedule::parse(max_intervals.as_bytes())
+            .unwrap()
+            .num_intervals as usize,
+        MAX_TRADING_INTERVALS
+    );
+
+    let too_many_intervals = format!(
+        "+0000;{}&0800-0900,{}",
+        four_intervals,
+        [four_intervals; 6].join(",")
+    );
+    let invalid_schedules: &[&[u8]] = &[
+        too_many_intervals.as_bytes(),
+        // Missing or invalid offset
+        b"O,O,O,O,O,O,O",
+        b"0000;O,O,O,O,O,O,O",
+        b"+2400;O,O,O,O,O,O,O",
+        b"+000;O,O,O,O,O,O,O",
+        // Wrong number of days
+        b"+0000;O,O,O,O,O,O",
+        b"+0000;O,O,O,O,O,O,O,O",
+        // Invalid intervals
+        b"+0000;,O,O,O,O,O,O",
+        b"+0000;0930,O,O,O,O,O,O",
+        b"+0000;1600-0930,O,O,O,O,O,O",
+        b"+0000;0930-0930,O,O,O,O,O,O",
+        b"+0000;0930-2401,O,O,O,O,O,O",
+        b"+0000;0960-1600,O,O,O,O,O,O",
+        b"+0000;930-1600,O,O,O,O,O,O",
+        b"+0000;+930-1600,O,O,O,O,O,O",
+        b"+0000;0930-1200&1100-1600,O,O,O,O,O,O",
+        b"+0000;0930-1600&,O,O,O,O,O,O",
+        b"+0000;o,O,O,O,O,O,O",
+        b"+0000;O,O,O,O,O,O,\xff",
+    ];
+    for schedule in invalid_schedules {
+        assert_eq!(
+            TradingSchedule::parse(schedule),
+            Err(ProgramError::InvalidArgument),
+            "{}",
+            String::from_utf8_lossy(schedule)
+        );
+    }
+}
+
+#[test]
+fn test_trading_schedule_is_open() {
+    let nyse = TradingSchedule::parse(NYSE).unwrap();
+    assert!(!nyse.is_open(MONDAY));
+    assert!(!nyse.is_open(MONDAY + 14 * HOUR + 29 * 60));
+    assert!(nyse.is_open(MONDAY + 14 * HOUR + 30 * 60));
+    assert!(nyse.is_open(MONDAY + 20 * HOUR + 59 * 60 + 59));
+    assert!(!nyse.is_open(MONDAY + 21 * HOUR));
+    assert!(nyse.is_open(MONDAY + 4 * DAY + 15 * HOUR));
+    assert!(!nyse.is_open(MONDAY + 5 * DAY + 15 * HOUR));
+    assert!(!nyse.is_open(MONDAY + 6 * DAY + 15 * HOUR));
+    // The same time in the previous and the next weeks
+    assert!(nyse.is_open(MONDAY - 7 * DAY + 15 * HOUR));
+    assert!(nyse.is_open(MONDAY + 7 * DAY + 15 * HOUR));
+
+    // The Sunday evening session of the previous week in local time ends at midnight
+    let schedule = TradingSchedule::parse(b"+0100;O,C,C,C,C,C,2300-2400").unwrap();
+    assert!(schedule.is_open(MONDAY - 2 * HOUR));
+    assert!(!schedule.is_open(MONDAY - 2 * HOUR - 1));
+    assert!(schedule.is_open(MONDAY + 22 * HOUR));
+    assert!(!schedule.is_open(MONDAY + 23 * HOUR));
+
+    assert!(!TradingSchedule::parse(b"+0000;C,C,C,C,C,C,C")
+        .unwrap()
+        .is_open(MONDAY));
+    assert!(TradingSchedule::zeroed().is_open(MONDAY));
+}
+
+#[test]
+fn test_trading_schedule_aggregation() {
+    let mut price_account = PriceAccount::zeroed();
+    price_account.num_ = 3;
+    price_account.min_pub_ = 1;
+    let set_quotes = |price_account: &mut PriceAccount, prices: [i64; 3], pub_slot_: u64| {
+        for (comp, price_) in price_account.comp_.iter_mut().zip(prices) {
+            comp.latest_ = PriceInfo {
+                price_,
+                conf_: 10,
+                status_: PC_STATUS_TRADING,
+                pub_slot_,
+                corp_act_status_: 0,
+            };
+        }
+    };
+
+    let trading_schedule = TradingSchedule::parse(NYSE).unwrap();
+
+    // Monday 10:00 in New York
+    set_quotes(&mut price_account, [100, 200, 300], 1000);
+    assert!(update_aggregate(
+        &mut price_account,
+        1001,
+        MONDAY + 15 * HOUR,
+        EmaParams::default(),
+        &mut AggregationExtensions::new(&mut []),
+        None,
+        Some(&trading_schedule),
+    ));
+    assert_eq!(price_account.agg_.status_, PC_STATUS_TRADING);
+    assert_eq!(price_account.agg_.price_, 200);
+    let (agg_conf, twap) = (price_account.agg_.conf_, price_account.twap_.val_);
+
+    // Monday 17:00 in New York, the last aggregate is kept
+    set_quotes(&mut price_account, [1000, 2000, 3000], 1001);
+    assert!(!update_aggregate(
+        &mut price_account,
+        1002,
+        MONDAY + 22 * HOUR,
+        EmaParams::default(),
+        &mut AggregationExtensions::new(&mut []),
+        None,
+        Some(&trading_schedule),
+    ));
+    assert_eq!(price_account.agg_.status_, PC_STATUS_HALTED);
+    assert_eq!(price_account.agg_.price_, 200);
+    assert_eq!(price_account.agg_.conf_, agg_conf);
+    assert_eq!(price_account.agg_.pub_slot_, 1002);
+    assert_eq!(price_account.last_slot_, 1001);
+    assert_eq!(price_account.twap_.val_, twap);
+    assert_eq!(price_account.prev_price_, 200);
+    assert_eq!(price_account.prev_slot_, 1001);
+
+    // Tuesday 10:00 in New York, the halted aggregate doesn't become the previous one
+    set_quotes(&mut price_account, [1000, 2000, 3000], 1002);
+    assert!(update_aggregate(
+        &mut price_account,
+        1003,
+        MONDAY + DAY + 15 * HOUR,
+        EmaParams::default(),
+        &mut AggregationExtensions::new(&mut []),
+        None,
+        Some(&trading_schedule),
+    ));
+    assert_eq!(price_account.agg_.status_, PC_STATUS_TRADING);
+    assert_eq!(price_account.agg_.price_, 2000);
+    assert_eq!(price_account.last_slot_, 1003);
+    assert_eq!(price_account.prev_price_, 200);
+    assert_eq!(price_account.prev_slot_, 1001);
+}
+
+#[tokio::test]
+async fn test_set_trading_schedule() {
+    let mut sim = PythSimulator::new().await;
+    let PriceFixture {
+        program_id,
+        master_authority,
+        price,
+        publishers,
+        outsider,
+        ..
+    } = sim.setup_price_fixture(1).await;
+    let publisher = &publishers[0];
+    let product = sim
+        .get_account_data_as::<PriceAccount>(price)
+        .await
+        .unwrap()
+        .product_account;
+    sim.process_ix_as(
+        builders::set_min_pub(&program_id, &master_authority.pubkey(), &price, 1),
+        &master_authority,
+    )
+    .await
+    .unwrap();
+
+    let always_closed = "+0000;C,C,C,C,C,C,C";
+    sim.assert_permission_violation(
+        builders::set_trading_schedule(&program_id, &outsider.pubkey(), &product, always_closed),
+        &outsider,
+    )
+    .await;
+    assert_eq!(
+        sim.process_ix_as(
+            builders::set_trading_schedule(
+                &program_id,
+                &master_authority.pubkey(),
+                &product,
+                "+0000;C,C,C,C,C,C"
+            ),
+            &master_authority,
+        )
+        .await
+        .unwrap_err()
+        .unwrap(),
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument)
+    );
+
+    // The schedule applies to the updates passing the product account after the publisher
+    // blocklist account
+    let upd_price = |round: usize, product: Pubkey| {
+        builders::with_product_accounts(
+            builders::with_publisher_blocklist(
+                builders::upd_price(
+                    &program_id,
+                    &publisher.pubkey(),
+                    &price,
+                    PC_STATUS_TRADING,
+                    100 + 10 * round as i64,
+                    10,
+                    10 * (round as u64 + 1),
+                ),
+                &program_id,
+            ),
+            &[product],
+        )
+    };
+
+    // Each round aggregates the quote of the previous one, the schedule closes the market for
+    // the third round
+    let expected_aggregates = [
+        (PC_STATUS_UNKNOWN, 0),
+        (PC_STATUS_TRADING, 100),
+        (PC_STATUS_HALTED, 100),
+        (PC_STATUS_TRADING, 120),
+    ];
+    for (round, (expected_status, expected_price)) in expected_aggregates.into_iter().enumerate() {
+        let schedule = match round {
+            2 => Some(always_closed),
+            3 => Some(""),
+            _ => None,
+        };
+        if let Some(schedule) = schedule {
+            sim.process_ix_as(
+                builders::set_trading_schedule(
+                    &program_id,
+                    &master_authority.pubkey(),
+                    &product,
+                    schedule,
+                ),
+                &master_authority,
+            )
+            .await
+            .unwrap();
+        }
+
+        sim.warp_to_slot(10 * (round as u64 + 1)).await.unwrap();
+        if round == 2 {
+            // The product account must be the product of the price account
+            assert_eq!(
+                sim.process_ix_as(upd_price(round, price), publisher)
+                    .await
+                    .unwrap_err()
+                    .unwrap(),
+                TransactionError::InstructionError(0, InstructionError::InvalidArgument)
+            );
+        }
+        sim.process_ix_as(upd_price(round, product), publisher)
+            .await
+            .unwrap();
+
+        let price_data = sim
+            .get_account_data_as::<PriceAccount>(price)
+            .await
+            .unwrap();
+        assert_eq!(price_data.agg_.status_, expected_status);
+        assert_eq!(price_data.agg_.price_, expected_price);
+
+        if round == 2 {
+            let product_account = sim
+                .get_resized_account(product, ProductAccount::TRADING_SCHEDULE_SPACE)
+                .await;
+            assert_eq!(
+                ProductAccount::trading_schedule(&product_account.data),
+                Some(TradingSchedule::parse(always_closed.as_bytes()).unwrap())
+            );
+        }
+    }
+    let product_account = sim.get_account(product).await.unwrap();
+    assert_eq!(
+        ProductAccount::trading_schedule(&product_account.data),
+        Some(TradingSchedule::zeroed())
+    );
+}
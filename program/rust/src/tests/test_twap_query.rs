@@ -0,0 +1,177 @@
+use {
+    crate::{
+        accounts::{
+            PriceAccount,
+            PriceCumulative,
+        },
+        c_oracle_header::PC_STATUS_TRADING,
+        twap::{
+            twap_from_messages,
+            twap_from_price_accounts,
+            Twap,
+            TwapError,
+        },
+    },
+    bytemuck::Zeroable,
+    pythnet_sdk::messages::TwapMessage,
+    quickcheck_macros::quickcheck,
+    solana_program::pubkey::Pubkey,
+};
+
+fn twap_message(publish_slot: u64, price: i128, conf: u128, num_down_slots: u64) -> TwapMessage {
+    TwapMessage {
+        feed_id: [1; 32],
+        cumulative_price: price,
+        cumulative_conf: conf,
+        num_down_slots,
+        exponent: -8,
+        publish_time: publish_slot as i64,
+        prev_publish_time: publish_slot as i64 - 1,
+        publish_slot,
+    }
+}
+
+#[test]
+fn test_twap_from_messages() {
+    let start = twap_message(100, 1_000, 500, 2);
+    let end = twap_message(110, 1_000 - 105, 500 + 25, 5);
+
+    let twap = twap_from_messages(&start, &end).unwrap();
+    assert_eq!(
+        twap,
+        Twap {
+            feed_id:        [1; 32],
+            price:          -10,
+            conf:           2,
+            exponent:       -8,
+            start_slot:     100,
+            end_slot:       110,
+            num_down_slots: 3,
+        }
+    );
+    assert_eq!(twap.num_slots(), 10);
+    assert_eq!(twap.down_slots_ratio(), 0.3);
+
+    assert_eq!(
+        twap_from_messages(&end, &start),
+        Err(TwapError::ReversedSlots)
+    );
+    assert_eq!(
+        twap_from_messages(&start, &start),
+        Err(TwapError::IdenticalSlots)
+    );
+    assert_eq!(
+        twap_from_messages(
+            &start,
+            &TwapMessage {
+                feed_id: [2; 32],
+                ..end
+            }
+        ),
+        Err(TwapError::FeedIdMismatch)
+    );
+    assert_eq!(
+        twap_from_messages(
+            &start,
+            &TwapMessage {
+                exponent: -9,
+                ..end
+            }
+        ),
+        Err(TwapError::ExponentMismatch)
+    );
+
+    // The difference of the cumulative sums overflows
+    assert_eq!(
+        twap_from_messages(
+            &twap_message(100, i128::MIN, 0, 0),
+            &twap_message(110, i128::MAX, 0, 0)
+        ),
+        Err(TwapError::Overflow)
+    );
+    // The average doesn't fit in an i64
+    assert_eq!(
+        twap_from_messages(
+            &twap_message(100, 0, 0, 0),
+            &twap_message(101, i128::from(i64::MAX) + 1, 0, 0)
+        ),
+        Err(TwapError::Overflow)
+    );
+    // The cumulative sums decrease
+    assert_eq!(
+        twap_from_messages(&twap_message(100, 0, 10, 0), &twap_message(110, 0, 0, 0)),
+        Err(TwapError::Overflow)
+    );
+    assert_eq!(
+        twap_from_messages(&twap_message(100, 0, 0, 10), &twap_message(110, 0, 0, 0)),
+        Err(TwapError::Overflow)
+    );
+}
+
+#[test]
+fn test_twap_from_price_accounts() {
+    let key = Pubkey::new_unique();
+    let mut price_data = PriceAccount::zeroed();
+    price_data.exponent = -5;
+    price_data.agg_.status_ = PC_STATUS_TRADING;
+
+    price_data.agg_.price_ = 100;
+    price_data.agg_.conf_ = 1;
+    price_data.agg_.pub_slot_ = 10;
+    price_data.last_slot_ = 10;
+    price_data.update_price_cumulative();
+    let start = price_data;
+
+    for (slot, price) in [(14, 200), (20, 300)] {
+        price_data.prev_slot_ = price_data.agg_.pub_slot_;
+        price_data.agg_.price_ = price;
+        price_data.agg_.pub_slot_ = slot;
+        price_data.last_slot_ = slot;
+        price_data.update_price_cumulative();
+    }
+
+    let twap = twap_from_price_accounts(&key, &start, &key, &price_data).unwrap();
+    assert_eq!(twap.feed_id, key.to_bytes());
+    assert_eq!(twap.exponent, -5);
+    assert_eq!((twap.start_slot, twap.end_slot), (10, 20));
+    assert_eq!(twap.price, (200 * 4 + 300 * 6) / 10);
+    assert_eq!(twap.conf, 1);
+    assert_eq!(twap.num_down_slots, 0);
+
+    assert_eq!(
+        twap_from_price_accounts(&key, &start, &Pubkey::new_unique(), &price_data),
+        Err(TwapError::FeedIdMismatch)
+    );
+}
+
+/// The TWAP between two snapshots is the average of the prices weighted by their slot gaps
+#[quickcheck]
+fn test_twap_from_messages_average(prices: Vec<(i32, u32, u8)>) -> bool {
+    let mut price_cumulative = PriceCumulative::zeroed();
+    let start = twap_message(0, 0, 0, 0);
+    let mut slot = 0;
+    let mut weighted_sum: i128 = 0;
+    let mut conf_sum: u128 = 0;
+    for (price, conf, slot_gap) in prices.iter().copied() {
+        let slot_gap = u64::from(slot_gap) + 1;
+        price_cumulative.update(i64::from(price), u64::from(conf), slot_gap, 0);
+        slot += slot_gap;
+        weighted_sum += i128::from(price) * i128::from(slot_gap);
+        conf_sum += u128::from(conf) * u128::from(slot_gap);
+    }
+    let end = twap_message(
+        slot,
+        price_cumulative.price,
+        price_cumulative.conf,
+        price_cumulative.num_down_slots,
+    );
+
+    match twap_from_messages(&start, &end) {
+        Ok(twap) => {
+            i128::from(twap.price) == weighted_sum / i128::from(slot)
+                && u128::from(twap.conf) == conf_sum / u128::from(slot)
+                && twap.num_down_slots == price_cumulative.num_down_slots
+        }
+        Err(err) => prices.is_empty() && err == TwapError::IdenticalSlots,
+    }
+}
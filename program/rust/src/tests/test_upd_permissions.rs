@@ -2,11 +2,18 @@ use {
     crate::{
         accounts::{
             PermissionAccount,
+            PriceAccount,
+            PriceAccountFlags,
             PythAccount,
         },
+        c_oracle_header::{
+            PC_PROD_ACC_SIZE,
+            PC_PTYPE_PRICE,
+        },
         deserialize::load,
         error::OracleError,
         instruction::{
+            builders,
             OracleCommand,
             UpdPermissionsArgs,
         },
@@ -16,10 +23,15 @@ use {
         },
     },
     solana_program::{
+        native_token::LAMPORTS_PER_SOL,
         pubkey::Pubkey,
         rent::Rent,
     },
-    solana_sdk::signer::Signer,
+    solana_sdk::{
+        signature::Keypair,
+        signer::Signer,
+    },
+    std::mem::size_of,
 };
 
 #[tokio::test]
@@ -32,39 +44,30 @@ async fn test_upd_permissions() {
     let price = sim.add_price(&product, -8).await.unwrap();
     assert!(sim.get_account(price.pubkey()).await.is_some());
 
-    let mut master_authority = Pubkey::new_unique();
-    let mut data_curation_authority = Pubkey::new_unique();
-    let mut security_authority = Pubkey::new_unique();
+    let master_authority = Keypair::new();
+    sim.airdrop(&master_authority.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    let data_curation_authority = Pubkey::new_unique();
+    let security_authority = Pubkey::new_unique();
+    let cmd_args = UpdPermissionsArgs {
+        header: OracleCommand::UpdPermissions.into(),
+        master_authority: master_authority.pubkey(),
+        data_curation_authority,
+        security_authority,
+    };
 
     // Should fail because payer is not the authority
     assert_eq!(
-        sim.upd_permissions(
-            UpdPermissionsArgs {
-                header: OracleCommand::UpdPermissions.into(),
-                master_authority,
-                data_curation_authority,
-                security_authority,
-            },
-            &copy_keypair(&sim.genesis_keypair)
-        )
-        .await
-        .unwrap_err()
-        .unwrap(),
+        sim.upd_permissions(cmd_args, &copy_keypair(&sim.genesis_keypair))
+            .await
+            .unwrap_err()
+            .unwrap(),
         OracleError::InvalidUpgradeAuthority.into()
     );
 
-    let mut permissions_pubkey = sim
-        .upd_permissions(
-            UpdPermissionsArgs {
-                header: OracleCommand::UpdPermissions.into(),
-                master_authority,
-                data_curation_authority,
-                security_authority,
-            },
-            &copy_keypair(&sim.upgrade_authority),
-        )
-        .await
-        .unwrap();
+    // The simulator created the permissions account and set its authorities
+    let permissions_pubkey = sim.get_permissions_pubkey();
     let permission_account = sim.get_account(permissions_pubkey).await.unwrap();
 
     assert_eq!(
@@ -80,55 +83,254 @@ async fn test_upd_permissions() {
     let mut permission_data =
         *load::<PermissionAccount>(permission_account.data.as_slice()).unwrap();
 
-    assert_eq!(master_authority, permission_data.master_authority);
+    assert_eq!(
+        sim.genesis_keypair.pubkey(),
+        permission_data.master_authority
+    );
+    assert_eq!(
+        sim.genesis_keypair.pubkey(),
+        permission_data.data_curation_authority
+    );
+    assert_eq!(
+        sim.genesis_keypair.pubkey(),
+        permission_data.security_authority
+    );
+
+    // Once set, the authorities can only be changed with a proposal
+    assert_eq!(
+        sim.upd_permissions(cmd_args, &copy_keypair(&sim.upgrade_authority))
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::AuthorityTransferRequired.into()
+    );
+    sim.transfer_authorities(cmd_args, &master_authority)
+        .await
+        .unwrap();
+    permission_data = sim
+        .get_account_data_as::<PermissionAccount>(permissions_pubkey)
+        .await
+        .unwrap();
+
+    assert_eq!(master_authority.pubkey(), permission_data.master_authority);
     assert_eq!(
         data_curation_authority,
         permission_data.data_curation_authority
     );
     assert_eq!(security_authority, permission_data.security_authority);
+}
 
-    data_curation_authority = Pubkey::new_unique();
-    master_authority = Pubkey::new_unique();
-    security_authority = Pubkey::new_unique();
+#[tokio::test]
+async fn test_upd_permissions_roles() {
+    let mut sim = PythSimulator::new().await;
+    let program_id = sim.program_id;
 
+    let data_curation_authority = Keypair::new();
+    let security_authority = Keypair::new();
+    sim.airdrop(&data_curation_authority.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    sim.airdrop(&security_authority.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
 
-    // Should fail because payer is not the authority
+    let master_authority = copy_keypair(&sim.genesis_keypair);
+    sim.transfer_authorities(
+        UpdPermissionsArgs {
+            header:                  OracleCommand::ProposeAuthorities.into(),
+            master_authority:        master_authority.pubkey(),
+            data_curation_authority: data_curation_authority.pubkey(),
+            security_authority:      security_authority.pubkey(),
+        },
+        &master_authority,
+    )
+    .await
+    .unwrap();
+
+    let mapping_keypair = sim.init_mapping().await.unwrap();
+    let product_keypair = sim.create_pyth_account(PC_PROD_ACC_SIZE as usize).await;
+    let price_keypair = sim.create_pyth_account(size_of::<PriceAccount>()).await;
+    let publisher = Pubkey::new_unique();
+
+    // Product and price accounts are managed by the data curation authority
     assert_eq!(
-        sim.upd_permissions(
-            UpdPermissionsArgs {
-                header: OracleCommand::UpdPermissions.into(),
-                master_authority,
-                data_curation_authority,
-                security_authority,
-            },
-            &copy_keypair(&sim.genesis_keypair)
+        sim.process_ix_as(
+            builders::add_product(
+                &program_id,
+                &security_authority.pubkey(),
+                &mapping_keypair.pubkey(),
+                &product_keypair.pubkey(),
+            ),
+            &security_authority,
         )
         .await
         .unwrap_err()
         .unwrap(),
-        OracleError::InvalidUpgradeAuthority.into()
+        OracleError::PermissionViolation.into()
     );
-    permissions_pubkey = sim
-        .upd_permissions(
-            UpdPermissionsArgs {
-                header: OracleCommand::UpdPermissions.into(),
-                master_authority,
-                data_curation_authority,
-                security_authority,
-            },
-            &copy_keypair(&sim.upgrade_authority),
+    sim.process_ix_as(
+        builders::add_product(
+            &program_id,
+            &data_curation_authority.pubkey(),
+            &mapping_keypair.pubkey(),
+            &product_keypair.pubkey(),
+        ),
+        &data_curation_authority,
+    )
+    .await
+    .unwrap();
+    sim.process_ix_as(
+        builders::upd_product(
+            &program_id,
+            &data_curation_authority.pubkey(),
+            &product_keypair.pubkey(),
+            &[("symbol", "BTC/USD")],
+        ),
+        &data_curation_authority,
+    )
+    .await
+    .unwrap();
+    sim.process_ix_as(
+        builders::add_price(
+            &program_id,
+            &data_curation_authority.pubkey(),
+            &product_keypair.pubkey(),
+            &price_keypair.pubkey(),
+            -8,
+            PC_PTYPE_PRICE,
+        ),
+        &data_curation_authority,
+    )
+    .await
+    .unwrap();
+
+    // Publishers and the publishing parameters are managed by the security authority
+    assert_eq!(
+        sim.process_ix_as(
+            builders::add_publisher(
+                &program_id,
+                &data_curation_authority.pubkey(),
+                &price_keypair.pubkey(),
+                &publisher,
+            ),
+            &data_curation_authority,
         )
         .await
-        .unwrap();
-    permission_data = sim
-        .get_account_data_as::<PermissionAccount>(permissions_pubkey)
+        .unwrap_err()
+        .unwrap(),
+        OracleError::PermissionViolation.into()
+    );
+    sim.process_ix_as(
+        builders::add_publisher(
+            &program_id,
+            &security_authority.pubkey(),
+            &price_keypair.pubkey(),
+            &publisher,
+        ),
+        &security_authority,
+    )
+    .await
+    .unwrap();
+    sim.process_ix_as(
+        builders::set_min_pub(
+            &program_id,
+            &security_authority.pubkey(),
+            &price_keypair.pubkey(),
+            3,
+        ),
+        &security_authority,
+    )
+    .await
+    .unwrap();
+    sim.process_ix_as(
+        builders::set_max_latency(
+            &program_id,
+            &security_authority.pubkey(),
+            &price_keypair.pubkey(),
+            10,
+        ),
+        &security_authority,
+    )
+    .await
+    .unwrap();
+
+    let price_data = sim
+        .get_account_data_as::<PriceAccount>(price_keypair.pubkey())
         .await
         .unwrap();
+    assert_eq!(price_data.num_, 1);
+    assert_eq!(price_data.comp_[0].pub_, publisher);
+    assert_eq!(price_data.min_pub_, 3);
+    assert_eq!(price_data.max_latency_, 10);
+
+    sim.process_ix_as(
+        builders::del_publisher(
+            &program_id,
+            &security_authority.pubkey(),
+            &price_keypair.pubkey(),
+            &publisher,
+        ),
+        &security_authority,
+    )
+    .await
+    .unwrap();
+
+    // Other commands are reserved to the master authority
+    for authority in [&data_curation_authority, &security_authority] {
+        assert_eq!(
+            sim.process_ix_as(
+                builders::set_price_flags(
+                    &program_id,
+                    &authority.pubkey(),
+                    &price_keypair.pubkey(),
+                    PriceAccountFlags::empty(),
+                    PriceAccountFlags::ACCUMULATOR_V2,
+                ),
+                authority,
+            )
+            .await
+            .unwrap_err()
+            .unwrap(),
+            OracleError::PermissionViolation.into()
+        );
+    }
 
-    assert_eq!(master_authority, permission_data.master_authority);
     assert_eq!(
-        data_curation_authority,
-        permission_data.data_curation_authority
+        sim.process_ix_as(
+            builders::del_price(
+                &program_id,
+                &security_authority.pubkey(),
+                &product_keypair.pubkey(),
+                &price_keypair.pubkey(),
+            ),
+            &security_authority,
+        )
+        .await
+        .unwrap_err()
+        .unwrap(),
+        OracleError::PermissionViolation.into()
     );
-    assert_eq!(security_authority, permission_data.security_authority);
+    sim.process_ix_as(
+        builders::del_price(
+            &program_id,
+            &data_curation_authority.pubkey(),
+            &product_keypair.pubkey(),
+            &price_keypair.pubkey(),
+        ),
+        &data_curation_authority,
+    )
+    .await
+    .unwrap();
+    sim.process_ix_as(
+        builders::del_product(
+            &program_id,
+            &data_curation_authority.pubkey(),
+            &mapping_keypair.pubkey(),
+            &product_keypair.pubkey(),
+        ),
+        &data_curation_authority,
+    )
+    .await
+    .unwrap();
+    assert!(sim.get_account(product_keypair.pubkey()).await.is_none());
 }
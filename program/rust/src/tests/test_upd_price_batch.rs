@@ -0,0 +1,158 @@
+use {
+    crate::{
+        accounts::{
+            PriceAccount,
+            PythAccount,
+        },
+        c_oracle_header::{
+            PC_STATUS_TRADING,
+            PC_STATUS_UNKNOWN,
+            PC_VERSION,
+        },
+        deserialize::load_checked,
+        error::OracleError,
+        instruction::{
+            builders,
+            PriceUpdate,
+        },
+        processor::process_instruction,
+        tests::test_utils::{
+            update_clock_slot,
+            AccountSetup,
+        },
+    },
+    solana_program::pubkey::Pubkey,
+};
+
+fn price_update(price: i64, confidence: u64, publishing_slot: u64) -> PriceUpdate {
+    PriceUpdate {
+        status: PC_STATUS_TRADING,
+        corp_act_status: 0,
+        price,
+        confidence,
+        publishing_slot,
+    }
+}
+
+#[test]
+fn test_upd_price_batch() {
+    let program_id = Pubkey::new_unique();
+
+    let mut funding_setup = AccountSetup::new_funding();
+    let funding_account = funding_setup.as_account_info();
+
+    let mut clock_setup = AccountSetup::new_clock();
+    let mut clock_account = clock_setup.as_account_info();
+    clock_account.is_signer = false;
+    clock_account.is_writable = false;
+    update_clock_slot(&mut clock_account, 1);
+
+    let mut price_setup_1 = AccountSetup::new::<PriceAccount>(&program_id);
+    let mut price_account_1 = price_setup_1.as_account_info();
+    price_account_1.is_signer = false;
+    PriceAccount::initialize(&price_account_1, PC_VERSION).unwrap();
+
+    let mut price_setup_2 = AccountSetup::new::<PriceAccount>(&program_id);
+    let mut price_account_2 = price_setup_2.as_account_info();
+    price_account_2.is_signer = false;
+    PriceAccount::initialize(&price_account_2, PC_VERSION).unwrap();
+
+    // The publisher is not permissioned for this account
+    let mut price_setup_3 = AccountSetup::new::<PriceAccount>(&program_id);
+    let mut price_account_3 = price_setup_3.as_account_info();
+    price_account_3.is_signer = false;
+    PriceAccount::initialize(&price_account_3, PC_VERSION).unwrap();
+
+    for price_account in [&price_account_1, &price_account_2] {
+        let mut price_data = load_checked::<PriceAccount>(price_account, PC_VERSION).unwrap();
+        price_data.num_ = 1;
+        price_data.comp_[0].pub_ = *funding_account.key;
+    }
+
+    let accounts = [
+        funding_account.clone(),
+        clock_account.clone(),
+        price_account_1.clone(),
+        price_account_2.clone(),
+        price_account_3.clone(),
+    ];
+
+    let instruction = builders::upd_price_batch(
+        &program_id,
+        funding_account.key,
+        &[
+            (*price_account_1.key, price_update(42, 2, 1)),
+            (*price_account_2.key, price_update(100, 5, 1)),
+            (*price_account_3.key, price_update(7, 1, 1)),
+        ],
+    );
+
+    // The failed update of the third account doesn't fail the instruction
+    assert!(process_instruction(&program_id, &accounts, &instruction.data).is_ok());
+
+    {
+        let price_data = load_checked::<PriceAccount>(&price_account_1, PC_VERSION).unwrap();
+        assert_eq!(price_data.comp_[0].latest_.price_, 42);
+        assert_eq!(price_data.comp_[0].latest_.conf_, 2);
+        assert_eq!(price_data.comp_[0].latest_.pub_slot_, 1);
+        assert_eq!(price_data.comp_[0].latest_.status_, PC_STATUS_TRADING);
+        assert_eq!(price_data.agg_.pub_slot_, 1);
+        assert_eq!(price_data.agg_.status_, PC_STATUS_UNKNOWN);
+    }
+    {
+        let price_data = load_checked::<PriceAccount>(&price_account_2, PC_VERSION).unwrap();
+        assert_eq!(price_data.comp_[0].latest_.price_, 100);
+        assert_eq!(price_data.comp_[0].latest_.conf_, 5);
+        assert_eq!(price_data.comp_[0].latest_.pub_slot_, 1);
+        assert_eq!(price_data.comp_[0].latest_.status_, PC_STATUS_TRADING);
+    }
+    {
+        let price_data = load_checked::<PriceAccount>(&price_account_3, PC_VERSION).unwrap();
+        assert_eq!(price_data.num_, 0);
+        assert_eq!(price_data.agg_.pub_slot_, 0);
+    }
+
+    // The first update is stale and fails, the second one triggers the aggregation
+    update_clock_slot(&mut clock_account, 2);
+    let instruction = builders::upd_price_batch(
+        &program_id,
+        funding_account.key,
+        &[
+            (*price_account_1.key, price_update(43, 2, 1)),
+            (*price_account_2.key, price_update(101, 5, 2)),
+        ],
+    );
+    assert!(process_instruction(&program_id, &accounts[..4], &instruction.data).is_ok());
+
+    {
+        let price_data = load_checked::<PriceAccount>(&price_account_1, PC_VERSION).unwrap();
+        assert_eq!(price_data.comp_[0].latest_.price_, 42);
+        assert_eq!(price_data.comp_[0].latest_.pub_slot_, 1);
+        assert_eq!(price_data.agg_.pub_slot_, 1);
+    }
+    {
+        let price_data = load_checked::<PriceAccount>(&price_account_2, PC_VERSION).unwrap();
+        assert_eq!(price_data.comp_[0].latest_.price_, 101);
+        assert_eq!(price_data.comp_[0].latest_.pub_slot_, 2);
+        assert_eq!(price_data.agg_.pub_slot_, 2);
+        assert_eq!(price_data.agg_.price_, 100);
+        assert_eq!(price_data.agg_.conf_, 5);
+        assert_eq!(price_data.agg_.status_, PC_STATUS_TRADING);
+    }
+
+    // The number of price accounts must match the number of updates
+    assert_eq!(
+        process_instruction(&program_id, &accounts[..3], &instruction.data),
+        Err(OracleError::InvalidNumberOfAccounts.into())
+    );
+
+    // The updates must not be truncated
+    assert_eq!(
+        process_instruction(
+            &program_id,
+            &accounts[..4],
+            &instruction.data[..instruction.data.len() - 1]
+        ),
+        Err(OracleError::InvalidInstructionDataLength.into())
+    );
+}
@@ -5,14 +5,8 @@ use {
             PythAccount,
             PERMISSIONS_SEED,
         },
-        c_oracle_header::PC_VERSION,
         error::OracleError,
-        instruction::{
-            CommandHeader,
-            OracleCommand,
-        },
     },
-    num_traits::ToPrimitive,
     solana_program::{
         account_info::AccountInfo,
         clock::{
@@ -134,15 +128,6 @@ pub fn update_clock_slot(clock_account: &mut AccountInfo, slot: u64) {
     clock_data.to_account_info(clock_account);
 }
 
-impl From<OracleCommand> for CommandHeader {
-    fn from(val: OracleCommand) -> Self {
-        CommandHeader {
-            version: PC_VERSION,
-            command: val.to_i32().unwrap(), // This can never fail and is only used in tests
-        }
-    }
-}
-
 impl From<OracleError> for TransactionError {
     fn from(error: OracleError) -> Self {
         TransactionError::InstructionError(
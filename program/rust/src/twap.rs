@@ -0,0 +1,110 @@
+//! Time-weighted averages of a price feed between two snapshots of its cumulative sums, see
+//! `PriceCumulative`.
+
+use {
+    crate::accounts::PriceAccount,
+    pythnet_sdk::messages::TwapMessage,
+    solana_program::pubkey::Pubkey,
+};
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum TwapError {
+    #[error("FeedIdMismatch")]
+    FeedIdMismatch,
+    #[error("ExponentMismatch")]
+    ExponentMismatch,
+    #[error("IdenticalSlots")]
+    IdenticalSlots,
+    #[error("ReversedSlots")]
+    ReversedSlots,
+    #[error("Overflow")]
+    Overflow,
+}
+
+/// The time-weighted averages of a price feed between `start_slot` and `end_slot`, the slots of
+/// the last successful aggregations of the two snapshots.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Twap {
+    pub feed_id:        [u8; 32],
+    /// Time-weighted average of the aggregate price, rounded towards zero
+    pub price:          i64,
+    /// Time-weighted average of the aggregate confidence, rounded down
+    pub conf:           u64,
+    pub exponent:       i32,
+    pub start_slot:     u64,
+    pub end_slot:       u64,
+    /// Number of slots between `start_slot` and `end_slot` where the price wasn't recently
+    /// updated
+    pub num_down_slots: u64,
+}
+
+impl Twap {
+    /// Number of slots between the two snapshots
+    pub fn num_slots(&self) -> u64 {
+        self.end_slot - self.start_slot
+    }
+
+    /// The fraction of the slots between the two snapshots where the price wasn't recently
+    /// updated
+    pub fn down_slots_ratio(&self) -> f64 {
+        self.num_down_slots as f64 / self.num_slots() as f64
+    }
+}
+
+/// Compute the time-weighted averages of a price feed between two of its `TwapMessage`s, `start`
+/// being the older one.
+pub fn twap_from_messages(start: &TwapMessage, end: &TwapMessage) -> Result<Twap, TwapError> {
+    if start.feed_id != end.feed_id {
+        return Err(TwapError::FeedIdMismatch);
+    }
+    if start.exponent != end.exponent {
+        return Err(TwapError::ExponentMismatch);
+    }
+    if end.publish_slot == start.publish_slot {
+        return Err(TwapError::IdenticalSlots);
+    }
+    if end.publish_slot < start.publish_slot {
+        return Err(TwapError::ReversedSlots);
+    }
+    let num_slots = end.publish_slot - start.publish_slot;
+
+    let price = end
+        .cumulative_price
+        .checked_sub(start.cumulative_price)
+        .map(|price| price / i128::from(num_slots))
+        .and_then(|price| i64::try_from(price).ok())
+        .ok_or(TwapError::Overflow)?;
+    let conf = end
+        .cumulative_conf
+        .checked_sub(start.cumulative_conf)
+        .map(|conf| conf / u128::from(num_slots))
+        .and_then(|conf| u64::try_from(conf).ok())
+        .ok_or(TwapError::Overflow)?;
+    let num_down_slots = end
+        .num_down_slots
+        .checked_sub(start.num_down_slots)
+        .ok_or(TwapError::Overflow)?;
+
+    Ok(Twap {
+        feed_id: end.feed_id,
+        price,
+        conf,
+        exponent: end.exponent,
+        start_slot: start.publish_slot,
+        end_slot: end.publish_slot,
+        num_down_slots,
+    })
+}
+
+/// Same as `twap_from_messages`, given two snapshots of the price accounts and their keys.
+pub fn twap_from_price_accounts(
+    start_key: &Pubkey,
+    start: &PriceAccount,
+    end_key: &Pubkey,
+    end: &PriceAccount,
+) -> Result<Twap, TwapError> {
+    twap_from_messages(
+        &start.as_twap_message(start_key),
+        &end.as_twap_message(end_key),
+    )
+}
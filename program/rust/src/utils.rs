@@ -2,12 +2,23 @@ use {
     crate::{
         accounts::{
             AccountHeader,
+            MappingAccount,
+            MultisigAuthority,
             PermissionAccount,
+            PriceAccount,
+            PriceBand,
+            ProductAccount,
+            PublisherBlocklistAccount,
+            PublisherManagerAccount,
+            PythAccount,
+            TradingSchedule,
             PERMISSIONS_SEED,
+            PUBLISHER_BLOCKLIST_SEED,
         },
         c_oracle_header::{
             MAX_CI_DIVISOR,
             MAX_NUM_DECIMALS,
+            PC_MAGIC,
             PC_STATUS_IGNORED,
         },
         deserialize::{
@@ -35,7 +46,13 @@ use {
         system_instruction::transfer,
         sysvar::rent::Rent,
     },
-    std::cell::Ref,
+    std::{
+        cell::{
+            Ref,
+            RefMut,
+        },
+        mem::size_of,
+    },
 };
 
 pub fn pyth_assert(condition: bool, error_code: ProgramError) -> Result<(), ProgramError> {
@@ -58,26 +75,222 @@ pub fn check_valid_funding_account(account: &AccountInfo) -> Result<(), ProgramE
 }
 
 /// Check that `account` is a valid signable pyth account or
-/// that `funding_account` is a signer and is permissioned by the `permission_account`
+/// that `funding_account` is a signer and is permissioned by the `permission_account`.
+/// If the `permission_account` has a multisig authority, it replaces the master authority:
+/// the instruction must be signed by enough keys of the multisig authority among
+/// `funding_account` and `additional_signers`, or by the authority of the role of the command
+/// unless the command requires the multisig authority, see
+/// `PermissionAccount::requires_multisig_authority`. `additional_signers` must be empty
+/// otherwise.
 pub fn check_permissioned_funding_account(
     program_id: &Pubkey,
     account: &AccountInfo,
     funding_account: &AccountInfo,
     permissions_account: &AccountInfo,
+    additional_signers: &[AccountInfo],
+    cmd_hdr: &CommandHeader,
+) -> Result<(), ProgramError> {
+    check_funding_account_permission(
+        program_id,
+        funding_account,
+        permissions_account,
+        additional_signers,
+        cmd_hdr,
+    )?;
+    check_valid_writable_account(program_id, account)
+}
+
+/// Same as `check_permissioned_funding_account`, without checking the account the command
+/// modifies, for the commands that modify several accounts.
+pub fn check_funding_account_permission(
+    program_id: &Pubkey,
+    funding_account: &AccountInfo,
+    permissions_account: &AccountInfo,
+    additional_signers: &[AccountInfo],
     cmd_hdr: &CommandHeader,
 ) -> Result<(), ProgramError> {
     check_valid_permissions_account(program_id, permissions_account)?;
     let permissions_account_data =
-        load_checked::<PermissionAccount>(permissions_account, cmd_hdr.version)?;
+        *load_checked::<PermissionAccount>(permissions_account, cmd_hdr.version)?;
+    let multisig_authority = PermissionAccount::load_multisig_authority(permissions_account)?;
     check_valid_funding_account(funding_account)?;
-    pyth_assert(
-        permissions_account_data.is_authorized(
-            funding_account.key,
-            OracleCommand::from_i32(cmd_hdr.command).ok_or(OracleError::UnrecognizedInstruction)?,
+    let command =
+        OracleCommand::from_i32(cmd_hdr.command).ok_or(OracleError::UnrecognizedInstruction)?;
+    let is_authorized = match multisig_authority {
+        Some(multisig_authority) => {
+            (!PermissionAccount::requires_multisig_authority(command)
+                && permissions_account_data.has_role(funding_account.key, command))
+                || is_approved_by_multisig_authority(
+                    &multisig_authority,
+                    funding_account,
+                    additional_signers,
+                )
+        }
+        None => {
+            pyth_assert(
+                additional_signers.is_empty(),
+                OracleError::InvalidNumberOfAccounts.into(),
+            )?;
+            permissions_account_data.is_authorized(funding_account.key, command)
+        }
+    };
+    pyth_assert(is_authorized, OracleError::PermissionViolation.into())
+}
+
+/// Whether `funding_account` and the signers among `additional_signers` include enough keys of
+/// `multisig_authority`
+fn is_approved_by_multisig_authority(
+    multisig_authority: &MultisigAuthority,
+    funding_account: &AccountInfo,
+    additional_signers: &[AccountInfo],
+) -> bool {
+    multisig_authority.is_approved(
+        std::iter::once(funding_account)
+            .chain(additional_signers.iter().filter(|signer| signer.is_signer))
+            .map(|signer| signer.key),
+    )
+}
+
+/// Check that the instruction of the upgrade authority `funding_account` is also approved by the
+/// multisig authority of `permissions_account`, if it has one, with the signers among
+/// `funding_account` and `additional_signers`. `additional_signers` must be empty otherwise.
+pub fn check_multisig_approval(
+    permissions_account: &AccountInfo,
+    funding_account: &AccountInfo,
+    additional_signers: &[AccountInfo],
+) -> Result<(), ProgramError> {
+    match PermissionAccount::load_multisig_authority(permissions_account)? {
+        Some(multisig_authority) => pyth_assert(
+            is_approved_by_multisig_authority(
+                &multisig_authority,
+                funding_account,
+                additional_signers,
+            ),
+            OracleError::PermissionViolation.into(),
+        ),
+        None => pyth_assert(
+            additional_signers.is_empty(),
+            OracleError::InvalidNumberOfAccounts.into(),
+        ),
+    }
+}
+
+/// Check that `funding_account` may add or remove the publishers of `price_account`: either it is
+/// permissioned by the `permissions_account`, see `check_permissioned_funding_account`, or the
+/// `remaining_accounts` start with a publisher manager account delegating the publishers of the
+/// price account to `funding_account`. In the latter case, the mapping account of the scope of
+/// the delegation, if there is one, follows the publisher manager account.
+pub fn check_publisher_set_authority(
+    program_id: &Pubkey,
+    price_account: &AccountInfo,
+    funding_account: &AccountInfo,
+    permissions_account: &AccountInfo,
+    remaining_accounts: &[AccountInfo],
+    cmd_hdr: &CommandHeader,
+) -> Result<(), ProgramError> {
+    match remaining_accounts {
+        [publisher_manager_account, scope_accounts @ ..]
+            if is_publisher_manager_account(program_id, publisher_manager_account) =>
+        {
+            check_valid_permissions_account(program_id, permissions_account)?;
+            check_valid_funding_account(funding_account)?;
+            check_valid_writable_account(program_id, price_account)?;
+            let publisher_manager = *load_checked::<PublisherManagerAccount>(
+                publisher_manager_account,
+                cmd_hdr.version,
+            )?;
+            pyth_assert(
+                publisher_manager.manager == *funding_account.key,
+                OracleError::PermissionViolation.into(),
+            )?;
+            let product =
+                load_checked::<PriceAccount>(price_account, cmd_hdr.version)?.product_account;
+            let is_in_scope = match scope_accounts {
+                [] => publisher_manager.is_in_scope(&product, None),
+                [mapping_account] => {
+                    pyth_assert(
+                        *mapping_account.key == publisher_manager.mapping_account
+                            && publisher_manager.mapping_account != Pubkey::default(),
+                        OracleError::PermissionViolation.into(),
+                    )?;
+                    check_valid_readable_account(program_id, mapping_account)?;
+                    let mapping = load_checked::<MappingAccount>(mapping_account, cmd_hdr.version)?;
+                    publisher_manager.is_in_scope(&product, Some(&mapping))
+                }
+                _ => return Err(OracleError::InvalidNumberOfAccounts.into()),
+            };
+            pyth_assert(is_in_scope, OracleError::PermissionViolation.into())
+        }
+        additional_signers => check_permissioned_funding_account(
+            program_id,
+            price_account,
+            funding_account,
+            permissions_account,
+            additional_signers,
+            cmd_hdr,
         ),
-        OracleError::PermissionViolation.into(),
+    }
+}
+
+fn is_publisher_manager_account(program_id: &Pubkey, account: &AccountInfo) -> bool {
+    account.owner == program_id
+        && account.data_len() >= size_of::<AccountHeader>()
+        && load_account_as::<AccountHeader>(account)
+            .map(|header| {
+                header.magic_number == PC_MAGIC
+                    && header.account_type == PublisherManagerAccount::ACCOUNT_TYPE
+            })
+            .unwrap_or(false)
+}
+
+/// Load the publisher blocklist account optionally passed to the price update instructions.
+/// Returns `None` if the account is empty, i.e. the blocklist hasn't been created yet, in which
+/// case no publisher is blocked and passing the account is the same as omitting it. Otherwise
+/// the account must be the PDA at `PUBLISHER_BLOCKLIST_SEED`, which is checked with the bump seed
+/// stored in the account rather than derived on every price update.
+pub fn load_publisher_blocklist<'a>(
+    program_id: &Pubkey,
+    account: &'a AccountInfo,
+    version: u32,
+) -> Result<Option<RefMut<'a, PublisherBlocklistAccount>>, ProgramError> {
+    if account.data_is_empty() {
+        return Ok(None);
+    }
+    check_valid_readable_account(program_id, account)?;
+    let publisher_blocklist = load_checked::<PublisherBlocklistAccount>(account, version)?;
+    let publisher_blocklist_pda_address = Pubkey::create_program_address(
+        &[
+            PUBLISHER_BLOCKLIST_SEED.as_bytes(),
+            &[publisher_blocklist.bump],
+        ],
+        program_id,
+    )
+    .map_err(|_| OracleError::InvalidPda)?;
+    pyth_assert(
+        publisher_blocklist_pda_address == *account.key,
+        OracleError::InvalidPda.into(),
     )?;
-    check_valid_writable_account(program_id, account)
+    Ok(Some(publisher_blocklist))
+}
+
+/// Load the `TradingSchedule` of the product account optionally passed to the price update
+/// instructions, which must be the product of `price_data`. Returns `None` if the product doesn't
+/// store a schedule.
+pub fn load_trading_schedule(
+    program_id: &Pubkey,
+    product_account: &AccountInfo,
+    price_data: &PriceAccount,
+    version: u32,
+) -> Result<Option<TradingSchedule>, ProgramError> {
+    check_valid_readable_account(program_id, product_account)?;
+    pyth_assert(
+        price_data.product_account == *product_account.key,
+        ProgramError::InvalidArgument,
+    )?;
+    load_checked::<ProductAccount>(product_account, version)?;
+    Ok(ProductAccount::trading_schedule(
+        &product_account.try_borrow_data()?,
+    ))
 }
 
 /// Returns `true` if the `account` is fresh, i.e., its data can be overwritten.
@@ -164,7 +377,9 @@ pub fn is_component_update(cmd_args: &UpdPriceArgs) -> Result<bool, OracleError>
     match OracleCommand::from_i32(cmd_args.header.command)
         .ok_or(OracleError::UnrecognizedInstruction)?
     {
-        OracleCommand::UpdPrice | OracleCommand::UpdPriceNoFailOnError => Ok(true),
+        OracleCommand::UpdPrice
+        | OracleCommand::UpdPriceNoFailOnError
+        | OracleCommand::UpdPriceBatch => Ok(true),
         _ => Ok(false),
     }
 }
@@ -175,7 +390,17 @@ pub fn get_status_for_conf_price_ratio(
     confidence: u64,
     status: u32,
 ) -> Result<u32, OracleError> {
-    let threshold_conf = price.abs() / MAX_CI_DIVISOR;
+    get_status_for_conf_divisor(price, confidence, status, MAX_CI_DIVISOR)
+}
+
+// Return PC_STATUS_IGNORED if confidence is bigger than price divided by conf_divisor else returns status
+pub fn get_status_for_conf_divisor(
+    price: i64,
+    confidence: u64,
+    status: u32,
+    conf_divisor: i64,
+) -> Result<u32, OracleError> {
+    let threshold_conf = price.abs() / conf_divisor;
 
     if confidence > try_convert::<_, u64>(threshold_conf)? {
         Ok(PC_STATUS_IGNORED)
@@ -184,6 +409,17 @@ pub fn get_status_for_conf_price_ratio(
     }
 }
 
+// Return PC_STATUS_IGNORED if price is outside of price_band, if any and enabled, else returns
+// status
+pub fn get_status_for_price_band(price: i64, status: u32, price_band: Option<&PriceBand>) -> u32 {
+    match price_band {
+        Some(price_band) if price_band.is_enabled() && !price_band.contains(price) => {
+            PC_STATUS_IGNORED
+        }
+        _ => status,
+    }
+}
+
 /// This struct represents UpgradeableLoaderState from bpf-upgradable-loader.
 /// Solana uses bincode for the struct. However the bincode crate is too big the space we have onchain,
 /// therefore we will use bytemuck for deserialization
@@ -245,7 +481,6 @@ pub fn get_rent() -> Result<Rent, ProgramError> {
     Ok(Rent::default())
 }
 
-#[allow(dead_code)]
 pub fn send_lamports<'a>(
     from: &AccountInfo<'a>,
     to: &AccountInfo<'a>,
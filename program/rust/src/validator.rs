@@ -2,17 +2,20 @@ use {
     crate::{
         accounts::{
             AccountHeader,
+            AggregationExtensions,
             PriceAccount,
             PriceAccountFlags,
+            ProductAccount,
+            PublisherBlocklistAccount,
             PythAccount,
             PythOracleSerialize,
         },
+        aggregation::{
+            update_aggregate,
+            EmaParams,
+        },
         c_oracle_header::PC_MAGIC,
         error::OracleError,
-        processor::{
-            c_upd_aggregate,
-            c_upd_twap,
-        },
         utils::pyth_assert,
     },
     pythnet_sdk::messages::{
@@ -49,34 +52,23 @@ fn check_price_account_header(price_account_info: &[u8]) -> Result<(), ProgramEr
     Ok(())
 }
 
-fn update_aggregate(slot: u64, timestamp: i64, price_account: &mut PriceAccount) {
-    // NOTE: c_upd_aggregate must use a raw pointer to price data. We already
-    // have the exclusive mut reference so we can simply cast before calling
-    // the function.
-    let updated = unsafe {
-        c_upd_aggregate(
-            price_account as *mut PriceAccount as *mut u8,
-            slot,
-            timestamp,
-        )
-    };
+// Checks that the account is a ProductAccount from the length and header.
+fn check_product_account_header(product_account_info: &[u8]) -> Result<(), ProgramError> {
+    pyth_assert(
+        product_account_info.len() >= ProductAccount::MINIMUM_SIZE,
+        OracleError::AccountTooSmall.into(),
+    )?;
 
-    // If the aggregate was successfully updated, calculate the difference and update TWAP.
-    if updated {
-        let agg_diff = (slot as i64) - price_account.prev_slot_ as i64;
+    let account_header =
+        bytemuck::from_bytes::<AccountHeader>(&product_account_info[0..size_of::<AccountHeader>()]);
 
-        // See comment on unsafe `c_upd_aggregate` call above for details.
-        unsafe {
-            c_upd_twap(price_account as *mut PriceAccount as *mut u8, agg_diff);
-        }
+    pyth_assert(
+        account_header.magic_number == PC_MAGIC
+            && account_header.account_type == ProductAccount::ACCOUNT_TYPE,
+        OracleError::InvalidAccountHeader.into(),
+    )?;
 
-        // We want to send a message every time the aggregate price updates. However, during the migration,
-        // not every publisher will necessarily provide the accumulator accounts. The message_sent_ flag
-        // ensures that after every aggregate update, the next publisher who provides the accumulator accounts
-        // will send the message.
-        price_account.message_sent_ = 0;
-        price_account.update_price_cumulative();
-    }
+    Ok(())
 }
 
 #[derive(Debug, PartialEq, thiserror::Error)]
@@ -87,19 +79,64 @@ pub enum AggregationError {
     V1AggregationMode,
     #[error("AlreadyAggregated")]
     AlreadyAggregated,
+    #[error("InvalidPublisherBlocklist")]
+    InvalidPublisherBlocklist,
+    #[error("InvalidProductAccount")]
+    InvalidProductAccount,
 }
 
 /// Attempts to read a price account and create a new price aggregate if v2
-/// aggregation is enabled on this price account. Modifies `price_account_data` accordingly.
+/// aggregation is enabled on this price account. Modifies `price_account` accordingly.
 /// Returns messages that should be included in the merkle tree, unless v1 aggregation
 /// is still in use.
 /// Note that the `messages` may be returned even if aggregation fails for some reason.
+///
+/// This ignores the extensions of the price account, the publisher blocklist and the trading
+/// schedule, see `aggregate_price_with_extensions`.
 pub fn aggregate_price(
     slot: u64,
     timestamp: i64,
     price_account_pubkey: &Pubkey,
     price_account: &mut PriceAccount,
 ) -> Result<[Vec<u8>; 2], AggregationError> {
+    let mut messages = aggregate_price_with_extensions(
+        slot,
+        timestamp,
+        price_account_pubkey,
+        bytemuck::bytes_of_mut(price_account),
+        &[],
+        &[],
+    )?;
+    // The price feed and TWAP messages
+    messages.truncate(2);
+    messages
+        .try_into()
+        .map_err(|_| AggregationError::NotPriceFeedAccount)
+}
+
+/// Same as `aggregate_price`, given the whole data of the price account so that the aggregation
+/// uses its extensions. Returns the price feed and TWAP messages, followed by the EMA horizons
+/// message if the price account has EMA horizons, by the corporate action status message if it
+/// has `PriceAccountFlags::CORP_ACT_STATUS`, by the publisher stats message if it has
+/// `PublisherStats` and by the aggregate confidence message if it has an `AggregateConfidence`.
+///
+/// `publisher_blocklist_data` is the data of the account at `PUBLISHER_BLOCKLIST_SEED`, or empty
+/// if it doesn't exist, and the quotes of the blocked publishers are ignored as by `upd_price`.
+/// `product_account_data` is the data of the account at the `product_account` of the price
+/// account, or empty to ignore it, and the aggregate is halted outside of the hours of its
+/// `TradingSchedule`.
+pub fn aggregate_price_with_extensions(
+    slot: u64,
+    timestamp: i64,
+    price_account_pubkey: &Pubkey,
+    price_account_data: &mut [u8],
+    publisher_blocklist_data: &[u8],
+    product_account_data: &[u8],
+) -> Result<Vec<Vec<u8>>, AggregationError> {
+    let ema_params = EmaParams::from_half_life(PriceAccount::ema_half_life(price_account_data));
+    let (price_account, mut extensions) =
+        checked_load_price_account_with_extensions_mut(price_account_data)
+            .map_err(|_| AggregationError::NotPriceFeedAccount)?;
     if !price_account
         .flags
         .contains(PriceAccountFlags::ACCUMULATOR_V2)
@@ -120,15 +157,70 @@ pub fn aggregate_price(
         // (this should normally happen only in the slot that contains the v1->v2 transition).
         return Err(AggregationError::AlreadyAggregated);
     }
-    update_aggregate(slot, timestamp, price_account);
-    Ok([
+    let publisher_blocklist = if publisher_blocklist_data.is_empty() {
+        None
+    } else {
+        Some(
+            checked_load_publisher_blocklist(publisher_blocklist_data)
+                .ok_or(AggregationError::InvalidPublisherBlocklist)?,
+        )
+    };
+    let trading_schedule = if product_account_data.is_empty() {
+        None
+    } else {
+        check_product_account_header(product_account_data)
+            .map_err(|_| AggregationError::InvalidProductAccount)?;
+        ProductAccount::trading_schedule(product_account_data)
+    };
+    update_aggregate(
+        price_account,
+        slot,
+        timestamp,
+        ema_params,
+        &mut extensions,
+        publisher_blocklist,
+        trading_schedule.as_ref(),
+    );
+    let mut messages = vec![
         price_account
             .as_price_feed_message(price_account_pubkey)
             .to_bytes(),
         price_account
             .as_twap_message(price_account_pubkey)
             .to_bytes(),
-    ])
+    ];
+    if let Some(ema_horizons) = extensions.ema_horizons.filter(|h| h.is_enabled()) {
+        messages.push(
+            price_account
+                .as_ema_horizons_message(price_account_pubkey, ema_horizons)
+                .to_bytes(),
+        );
+    }
+    if price_account
+        .flags
+        .contains(PriceAccountFlags::CORP_ACT_STATUS)
+    {
+        messages.push(
+            price_account
+                .as_corp_act_status_message(price_account_pubkey)
+                .to_bytes(),
+        );
+    }
+    if let Some(publisher_stats) = extensions.publisher_stats {
+        messages.push(
+            price_account
+                .as_publisher_stats_message(price_account_pubkey, publisher_stats)
+                .to_bytes(),
+        );
+    }
+    if let Some(aggregate_confidence) = extensions.aggregate_confidence {
+        messages.push(
+            price_account
+                .as_aggregate_confidence_message(price_account_pubkey, aggregate_confidence)
+                .to_bytes(),
+        );
+    }
+    Ok(messages)
 }
 
 /// Load a price account as read-only, returning `None` if it isn't a valid price account.
@@ -139,6 +231,26 @@ pub fn checked_load_price_account(price_account_info: &[u8]) -> Option<&PriceAcc
     ))
 }
 
+/// Load the publisher blocklist account, returning `None` if it isn't a valid publisher
+/// blocklist account. Only the account at `PUBLISHER_BLOCKLIST_SEED` should be passed.
+pub fn checked_load_publisher_blocklist(
+    publisher_blocklist_info: &[u8],
+) -> Option<&PublisherBlocklistAccount> {
+    let account_header = bytemuck::try_from_bytes::<AccountHeader>(
+        publisher_blocklist_info.get(..size_of::<AccountHeader>())?,
+    )
+    .ok()?;
+    if account_header.magic_number != PC_MAGIC
+        || account_header.account_type != PublisherBlocklistAccount::ACCOUNT_TYPE
+    {
+        return None;
+    }
+    bytemuck::try_from_bytes(
+        publisher_blocklist_info.get(..size_of::<PublisherBlocklistAccount>())?,
+    )
+    .ok()
+}
+
 pub fn checked_load_price_account_mut(
     price_account_info: &mut [u8],
 ) -> Result<&mut PriceAccount, ProgramError> {
@@ -148,6 +260,20 @@ pub fn checked_load_price_account_mut(
     ))
 }
 
+/// Same as `checked_load_price_account_mut`, also returning the extensions of the price account
+/// written by the aggregation.
+pub fn checked_load_price_account_with_extensions_mut(
+    price_account_info: &mut [u8],
+) -> Result<(&mut PriceAccount, AggregationExtensions), ProgramError> {
+    check_price_account_header(price_account_info)?;
+    let (price_account_info, extensions) =
+        price_account_info.split_at_mut(size_of::<PriceAccount>());
+    Ok((
+        bytemuck::from_bytes_mut::<PriceAccount>(price_account_info),
+        AggregationExtensions::new(extensions),
+    ))
+}
+
 /// Computes the stake caps for each publisher based on the oracle program accounts provided
 /// - `account_datas` - the account datas of the oracle program accounts
 /// - `timestamp` - the timestamp to include in the message